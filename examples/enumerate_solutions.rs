@@ -0,0 +1,27 @@
+//! Enumerates the solutions of a board of zeros one at a time, where every shortest path to the
+//! end cell is a solution.
+//!
+//! ```sh
+//! $ cargo run --example enumerate_solutions
+//! ```
+
+use die_agony::{Board, Solution, Solver};
+
+fn main() {
+    let Some(mut solver) = Solver::builder()
+        .board(Board::from_rows([[0; 4]; 3]))
+        .max_depth(5)
+        .build()
+    else {
+        return;
+    };
+
+    let mut count = 0;
+    while let Some(solution) = solver.next_solution() {
+        if let Solution::Found(report) = solution {
+            count += 1;
+            println!("{}. {}", count, report.moves_as_letters());
+        }
+    }
+    println!("The board has {} solutions within 5 turns.", count);
+}
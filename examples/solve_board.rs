@@ -0,0 +1,35 @@
+//! Solves the board written in the given file, or the puzzle board if none is given.
+//!
+//! ```sh
+//! $ cargo run --example solve_board -- board.txt
+//! ```
+
+use die_agony::{Board, Solution, Solver};
+
+use std::{env, fs::File, io::BufReader, process};
+
+fn main() {
+    let board = match env::args().nth(1) {
+        Some(path) => File::open(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|file| Board::from_reader(BufReader::new(file)))
+            .unwrap_or_else(|error| {
+                eprintln!("Cannot read the board in {}: {}", path, error);
+                process::exit(1);
+            }),
+        None => Board::new(),
+    };
+
+    let Some(solver) = Solver::builder().board(board).build() else {
+        process::exit(1);
+    };
+    match solver.solve() {
+        Solution::Found(report) => println!(
+            "The sum of values in the unvisited cells is {}, moving {}.\n{}",
+            report.unvisited_sum,
+            report.moves_as_letters(),
+            report
+        ),
+        Solution::NotFound(reason, ..) => println!("No solution found, as {}.", reason),
+    }
+}
@@ -0,0 +1,30 @@
+//! Checks that the given moves solve the puzzle board with the given answer, replaying them
+//! independently of the solver.
+//!
+//! ```sh
+//! $ cargo run --example verify_path -- URRDLUUUUURDLLDRRDRDDRRUULLUURRU 1935
+//! ```
+
+use die_agony::{fuzz::verify_solution, Board};
+
+use std::{env, process};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [moves, answer] = args.as_slice() else {
+        eprintln!("Usage: verify_path <moves> <answer>");
+        process::exit(2);
+    };
+    let Ok(answer) = answer.parse() else {
+        eprintln!("`{}` is not a valid answer", answer);
+        process::exit(2);
+    };
+
+    match verify_solution(&Board::new(), &moves.to_ascii_uppercase(), answer) {
+        Ok(()) => println!("The moves solve the puzzle, and the answer is {}.", answer),
+        Err(error) => {
+            println!("The moves do not solve the puzzle: {}.", error);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    board::{Notation, Position},
+    direction::Direction,
+    schema::versioned,
+};
+
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// Enumerates the formats the ancestry of a solution can be exported in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AncestryFormat {
+    /// A JSON document listing each decision point and its siblings.
+    Json,
+    /// A Graphviz graph, the journey of the solution drawn with bold edges.
+    Dot,
+}
+
+/// What became of a branch leaving a decision point, in a direction the journey to the solution did
+/// not take.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Branch {
+    /// The dice would roll off the board.
+    OffBoard,
+    /// The dice lands on the destination, and the search explored that many steps from there, the
+    /// deepest of them after the given turn. The search may have stopped before exhausting them.
+    Explored {
+        destination: Position,
+        steps: usize,
+        last_turn: i16,
+    },
+    /// The dice cannot land on the destination, or no solution can go through it, for the given
+    /// reason, e.g. `would revisit it too many times`.
+    Pruned {
+        destination: Position,
+        reason: String,
+    },
+}
+
+/// A turn of the journey to the solution, where the dice could have rolled in other directions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    /// Turn the dice is rolled at, from 1.
+    pub turn: i16,
+    pub from: Position,
+    pub direction: Direction,
+    pub to: Position,
+    /// Top of the dice once landed on `to`.
+    pub top: Option<i16>,
+    /// The other directions, with what became of their branch.
+    pub siblings: Vec<(Direction, Branch)>,
+}
+
+/// Exports the decision points of a solution as a document in the given format. Positions are
+/// written in the given notation in the graph, and as `[row, column]` pairs in JSON.
+pub fn export_ancestry(
+    decisions: &[Decision],
+    format: AncestryFormat,
+    notation: Notation,
+) -> String {
+    match format {
+        AncestryFormat::Json => export_json(decisions).to_string(),
+        AncestryFormat::Dot => export_dot(decisions, notation),
+    }
+}
+
+fn export_json(decisions: &[Decision]) -> Value {
+    let moves: String = decisions
+        .iter()
+        .map(|decision| decision.direction.letter())
+        .collect();
+    let decisions: Vec<Value> = decisions
+        .iter()
+        .map(|decision| {
+            let siblings: Vec<Value> = decision
+                .siblings
+                .iter()
+                .map(|(direction, branch)| {
+                    let mut sibling = match branch {
+                        Branch::OffBoard => json!({ "outcome": "off-board" }),
+                        Branch::Explored {
+                            destination,
+                            steps,
+                            last_turn,
+                        } => json!({
+                            "to": destination,
+                            "outcome": "explored",
+                            "steps": steps,
+                            "last_turn": last_turn,
+                        }),
+                        Branch::Pruned {
+                            destination,
+                            reason,
+                        } => json!({
+                            "to": destination,
+                            "outcome": "pruned",
+                            "reason": reason,
+                        }),
+                    };
+                    sibling["direction"] = json!(direction.letter().to_string());
+                    sibling
+                })
+                .collect();
+
+            json!({
+                "turn": decision.turn,
+                "from": decision.from,
+                "direction": decision.direction.letter().to_string(),
+                "to": decision.to,
+                "top": decision.top,
+                "siblings": siblings,
+            })
+        })
+        .collect();
+
+    versioned(json!({ "moves": moves, "decisions": decisions }))
+}
+
+fn export_dot(decisions: &[Decision], notation: Notation) -> String {
+    let mut dot = String::from("digraph ancestry {\n  node [shape=box];\n");
+    if let Some(first) = decisions.first() {
+        writeln!(dot, "  t0 [label=\"{}\"];", notation.format(first.from)).unwrap();
+    }
+    for decision in decisions {
+        let (parent, node) = (decision.turn - 1, decision.turn);
+        let top = decision
+            .top
+            .map_or_else(|| "?".to_string(), |top| top.to_string());
+        writeln!(
+            dot,
+            "  t{} [label=\"{}\\ntop {}\"];",
+            node,
+            notation.format(decision.to),
+            top
+        )
+        .unwrap();
+        writeln!(
+            dot,
+            "  t{} -> t{} [label=\"{}\", style=bold];",
+            parent,
+            node,
+            decision.direction.letter()
+        )
+        .unwrap();
+
+        for (direction, branch) in &decision.siblings {
+            let label = match branch {
+                Branch::OffBoard => "off the board".to_string(),
+                Branch::Explored {
+                    destination,
+                    steps,
+                    last_turn,
+                } => format!(
+                    "{}\\n{} steps explored, up to turn {}",
+                    notation.format(*destination),
+                    steps,
+                    last_turn
+                ),
+                Branch::Pruned {
+                    destination,
+                    reason,
+                } => format!(
+                    "{}\\n{}",
+                    notation.format(*destination),
+                    reason.replace('"', "\\\"")
+                ),
+            };
+            writeln!(
+                dot,
+                "  t{}{} [label=\"{}\", style=dashed];",
+                parent,
+                direction.letter(),
+                label
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "  t{} -> t{}{} [label=\"{}\", style=dashed];",
+                parent,
+                parent,
+                direction.letter(),
+                direction.letter()
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    fn decisions() -> Vec<Decision> {
+        vec![Decision {
+            turn: 1,
+            from: (5, 0),
+            direction: Direction::UP,
+            to: (4, 0),
+            top: Some(5),
+            siblings: vec![
+                (
+                    Direction::RIGHT,
+                    Branch::Explored {
+                        destination: (5, 1),
+                        steps: 3,
+                        last_turn: 3,
+                    },
+                ),
+                (Direction::DOWN, Branch::OffBoard),
+                (
+                    Direction::LEFT,
+                    Branch::Pruned {
+                        destination: (5, 0),
+                        reason: "is not allowed, as the cell is avoided".to_string(),
+                    },
+                ),
+            ],
+        }]
+    }
+
+    #[test]
+    fn json_lists_siblings_with_their_outcome() {
+        let exported = export_ancestry(&decisions(), AncestryFormat::Json, Notation::RowCol);
+        let document: Value = serde_json::from_str(&exported).unwrap();
+
+        assert_eq!(document["format_version"], 1);
+        assert_eq!(document["moves"], "U");
+        let decision = &document["decisions"][0];
+        assert_eq!(decision["to"], json!([4, 0]));
+        assert_eq!(decision["top"], 5);
+        assert_eq!(decision["siblings"][0]["outcome"], "explored");
+        assert_eq!(decision["siblings"][0]["steps"], 3);
+        assert_eq!(decision["siblings"][1]["direction"], "D");
+        assert_eq!(decision["siblings"][1]["outcome"], "off-board");
+        assert_eq!(decision["siblings"][2]["outcome"], "pruned");
+    }
+
+    #[test]
+    fn dot_draws_pruned_siblings_dashed() {
+        let exported = export_ancestry(
+            &decisions(),
+            AncestryFormat::Dot,
+            Board::new().chess_notation(),
+        );
+
+        assert!(exported.starts_with("digraph ancestry {"));
+        assert!(exported.contains("t0 [label=\"a1\"];"));
+        assert!(exported.contains("t0 -> t1 [label=\"U\", style=bold];"));
+        assert!(exported.contains("t0L [label=\"a1\\nis not allowed, as the cell is avoided\""));
+        assert!(exported.contains("t0 -> t0D [label=\"D\", style=dashed];"));
+    }
+}
@@ -1,18 +1,341 @@
 use crate::direction::Direction;
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    io::BufRead,
+};
 
 /// A position is a (row, column) tuple.
-pub(crate) type Position = (usize, usize);
+pub type Position = (usize, usize);
 
+/// Width of the puzzle board, which is square.
+#[cfg(test)]
 const BOARD_WIDTH: usize = 6;
-const END_CELL_POSITION: Position = (0, BOARD_WIDTH - 1);
+/// Most rows or columns a board can have, so that positions fit in a byte when logged or shared.
+pub const MAX_BOARD_SIDE: usize = u8::MAX as usize;
 
-/// Encapsulates both the value stored in that cell, as well as its position on the board.
+/// Enumerates the ways positions can be written.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Notation {
+    /// 0-based `(row, col)` pairs, from the top-left cell, e.g. `(5, 0)` for the start cell.
+    #[default]
+    RowCol,
+    /// Chess-like squares, with a file letter for the column and a 1-based rank for the row, from
+    /// the origin corner of a board of the given size, e.g. `a1` for the start cell when counting
+    /// from the bottom-left cell. Past the 26th column, files take several letters like spreadsheet
+    /// columns do: `z` is followed by `aa`, `ab` and so on.
+    Chess {
+        rows: usize,
+        cols: usize,
+        origin: Corner,
+    },
+}
+
+impl Notation {
+    /// Writes the position in this notation.
+    pub fn format(self, (row, col): Position) -> String {
+        match self {
+            Self::RowCol => format!("({}, {})", row, col),
+            Self::Chess { rows, cols, origin } => match origin.count_from((row, col), rows, cols) {
+                Some((rank, file)) => format!("{}{}", format_file(file), rank + 1),
+                // Positions beyond the board have no square.
+                None => Self::RowCol.format((row, col)),
+            },
+        }
+    }
+}
+
+/// Enumerates the corners of a board, which chess-like squares can be counted from.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Corner {
+    /// The corner the dice starts on, as chess boards are seen by white.
+    #[default]
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+}
+
+impl Corner {
+    /// Returns the name of the corner, as declared by board files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+        }
+    }
+
+    /// Returns the 0-based (rank, file) of the position, counting rows and columns from this
+    /// corner of a board of the given size, or `None` if the position is beyond the board.
+    /// Counting is its own inverse, so this also turns a (rank, file) back into a position.
+    fn count_from(self, (row, col): Position, rows: usize, cols: usize) -> Option<(usize, usize)> {
+        if row >= rows || col >= cols {
+            return None;
+        }
+        let rank = match self {
+            Self::BottomLeft | Self::BottomRight => rows - 1 - row,
+            Self::TopLeft | Self::TopRight => row,
+        };
+        let file = match self {
+            Self::BottomLeft | Self::TopLeft => col,
+            Self::BottomRight | Self::TopRight => cols - 1 - col,
+        };
+
+        Some((rank, file))
+    }
+}
+
+/// Parses the origin corner of chess-like squares declared by a board file, e.g. `top-left`.
+pub fn parse_corner(corner: &str) -> Result<Corner, String> {
+    [
+        Corner::BottomLeft,
+        Corner::BottomRight,
+        Corner::TopLeft,
+        Corner::TopRight,
+    ]
+    .into_iter()
+    .find(|candidate| candidate.name() == corner.trim())
+    .ok_or_else(|| {
+        format!(
+            "`{}` is not a corner, which is either `bottom-left`, `bottom-right`, `top-left` or \
+             `top-right`",
+            corner.trim()
+        )
+    })
+}
+
+/// Enumerates how the dice moves from cell to cell, as declared by the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Movement {
+    /// The dice rolls onto the orthogonal neighbours of its cell, and cannot roll off the board.
+    /// This is the rule of the original puzzle.
+    #[default]
+    Orthogonal,
+    /// The dice rolls onto the orthogonal neighbours of its cell, rolling off an edge of the board
+    /// onto the opposite edge, as on a torus.
+    OrthogonalWrap,
+}
+
+impl Movement {
+    /// Returns the name of the movement model, as declared by board files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Orthogonal => "orthogonal",
+            Self::OrthogonalWrap => "orthogonal+wrap",
+        }
+    }
+}
+
+/// Parses the movement model declared by a board file, e.g. `orthogonal+wrap`.
+pub fn parse_movement(movement: &str) -> Result<Movement, String> {
+    match movement.trim() {
+        "orthogonal" => Ok(Movement::Orthogonal),
+        "orthogonal+wrap" => Ok(Movement::OrthogonalWrap),
+        // A dice only ever rolls over one of its edges, onto an orthogonal neighbour.
+        movement
+            if movement == "orthogonal+diagonal"
+                || movement.split_whitespace().next() == Some("offsets") =>
+        {
+            Err(format!(
+                "the `{}` movement is not supported, as the dice can only roll onto orthogonal \
+                 neighbours",
+                movement
+            ))
+        }
+        movement => Err(format!(
+            "`{}` is not a movement, which is either `orthogonal` or `orthogonal+wrap`",
+            movement
+        )),
+    }
+}
+
+/// A cell written either as a position, or as a chess-like square which can only be turned into a
+/// position once the size of the board and the corner squares are counted from are known.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Square {
+    Position(Position),
+    Chess { col: usize, rank: usize },
+}
+
+impl Square {
+    /// Returns the position of the square on the given board, counting chess-like squares from
+    /// its origin corner. Returns `None` if the square is beyond the edges of the board.
+    pub fn resolve(self, board: &Board) -> Option<Position> {
+        match self {
+            Self::Position(position) => Some(position),
+            Self::Chess { col, rank } => {
+                board
+                    .origin
+                    .count_from((rank - 1, col), board.rows, board.cols)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Position((row, col)) => write!(f, "({}, {})", row, col),
+            Self::Chess { col, rank } => write!(f, "{}{}", format_file(*col), rank),
+        }
+    }
+}
+
+/// Writes the file of the given column, from `a` for the first column.
+fn format_file(col: usize) -> String {
+    let mut file = Vec::new();
+    let mut col = col + 1;
+    while col > 0 {
+        col -= 1;
+        file.push(b'a' + (col % 26) as u8);
+        col /= 26;
+    }
+
+    file.iter().rev().map(|&letter| letter as char).collect()
+}
+
+/// Parses a chess-like square, e.g. `a1` for the cell in the origin corner, or `aa1` for the cell of
+/// the 27th file on the first rank. Returns `None` if this is not a square.
+pub fn parse_chess_square(square: &str) -> Option<Square> {
+    let rank_start = square
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(square.len());
+    let (file, rank) = square.split_at(rank_start);
+    // Parsing would also accept a leading sign, which is not part of a square.
+    if file.is_empty() || !rank.bytes().all(|digit| digit.is_ascii_digit()) {
+        return None;
+    }
+    let rank: usize = rank.parse().ok()?;
+    if rank == 0 {
+        return None;
+    }
+
+    let col = file.bytes().try_fold(0usize, |col, letter| {
+        col.checked_mul(26)?
+            .checked_add((letter.to_ascii_lowercase() - b'a') as usize + 1)
+    })?;
+    Some(Square::Chess { col: col - 1, rank })
+}
+
+/// Parses a cell written either as a `row,col` pair, or as a chess-like square, e.g. `a1`.
+pub fn parse_square(square: &str) -> Result<Square, String> {
+    let Some((row, col)) = square.split_once(',') else {
+        return parse_chess_square(square.trim()).ok_or_else(|| {
+            format!(
+                "`{}` is neither a `row,col` pair nor a square like `a1`",
+                square
+            )
+        });
+    };
+    let parse = |index: &str| {
+        index
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("`{}` is not a valid index", index))
+    };
+
+    Ok(Square::Position((parse(row)?, parse(col)?)))
+}
+
+/// Parses a color a board file can give a cell, either a CSS color name like `gold` or a
+/// hexadecimal color like `#fc0` or `#ffcc00`.
+pub fn parse_color(color: &str) -> Result<String, String> {
+    let color = color.trim();
+    let valid = match color.strip_prefix('#') {
+        Some(hex) => [3, 6].contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic()),
+    };
+    if valid {
+        Ok(color.to_string())
+    } else {
+        Err(format!(
+            "`{}` is not a color, which is either a name like `gold` or a hexadecimal color like \
+             `#ffcc00`",
+            color
+        ))
+    }
+}
+
+/// Optional annotations of a cell, which do not change how the puzzle is solved, but are shown
+/// in explanations and drawings, e.g. to point out the trap cell of a teaching board.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotation {
+    /// Name of the cell, e.g. `the trap cell`.
+    pub label: Option<String>,
+    /// Color the cell is drawn in.
+    pub color: Option<String>,
+}
+
+/// Enumerates the reasons why values cannot make up a board.
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) struct Cell {
+pub enum BoardError {
+    /// There are no rows, or no columns.
+    Empty,
+    /// A row does not have as many values as the first one.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The board has more than MAX_BOARD_SIDE rows or columns.
+    UnsupportedSize { rows: usize, cols: usize },
+    /// The board has a single cell, which would be both the start and the end cell.
+    SingleCell,
+    /// A value does not fit in the range of scores the solver works with.
+    ValueOutOfRange { position: Position, value: i64 },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the board is empty"),
+            Self::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} values, but the first row has {}",
+                row, found, expected
+            ),
+            Self::UnsupportedSize { rows, cols } => write!(
+                f,
+                "the board is {}x{}, but boards have at most {} rows and {} columns",
+                rows, cols, MAX_BOARD_SIDE, MAX_BOARD_SIDE
+            ),
+            Self::SingleCell => write!(
+                f,
+                "the board has a single cell, so the dice would start on the end cell"
+            ),
+            Self::ValueOutOfRange {
+                position: (row, col),
+                value,
+            } => write!(
+                f,
+                "the value {} at ({}, {}) is out of range [{}, {}]",
+                value,
+                row,
+                col,
+                i16::MIN,
+                i16::MAX
+            ),
+        }
+    }
+}
+
+impl Error for BoardError {}
+
+/// Encapsulates both the value stored in that cell, as well as its position on the board.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
     value: i16,
     position: Position,
+    /// Whether this is the end cell of the board the cell comes from.
+    end: bool,
 }
 
 impl Cell {
@@ -21,7 +344,7 @@ impl Cell {
     }
 
     pub fn is_end_cell(&self) -> bool {
-        self.position == END_CELL_POSITION
+        self.end
     }
 
     pub fn get_position(&self) -> &Position {
@@ -29,40 +352,281 @@ impl Cell {
     }
 }
 
-/// Holds a matrix of values of size BOARD_WIDTH x BOARD_WIDTH.
+/// Holds a matrix of values of size rows x cols, stored row by row.
 /// The start cell is the bottom-left cell, and the goal is to reach
-/// the end cell, at the top-right.
-pub(crate) struct Board {
-    board: [[i16; BOARD_WIDTH]; BOARD_WIDTH],
+/// the end cell, at the top-right unless moved.
+#[derive(Clone)]
+pub struct Board {
+    values: Vec<i16>,
+    rows: usize,
+    cols: usize,
+    end: Position,
+    movement: Movement,
+    /// Corner chess-like squares are counted from.
+    origin: Corner,
+    /// Annotations of the cells which have any.
+    annotations: HashMap<Position, Annotation>,
 }
 
 impl Board {
     pub fn new() -> Self {
+        Self::from_rows([
+            [57, 33, 132, 268, 492, 732],
+            [81, 123, 240, 443, 353, 508],
+            [186, 42, 195, 704, 452, 228],
+            [-7, 2, 357, 452, 317, 395],
+            [5, 23, -4, 592, 445, 620],
+            [0, 77, 32, 403, 337, 452],
+        ])
+    }
+
+    /// Creates a board holding the given values, row by row from the top of the board. The start
+    /// and end cells are at the same corners as on the puzzle board.
+    ///
+    /// The size of the board is checked at compile time: it needs at least two cells, so that the
+    /// start and end cells differ, and at most `MAX_BOARD_SIDE` rows and columns. Use
+    /// `Board::try_from` for boards sized at runtime.
+    ///
+    /// ```compile_fail
+    /// use die_agony::Board;
+    ///
+    /// let board = Board::from_rows([[0; 0]; 3]);
+    /// ```
+    pub fn from_rows<const ROWS: usize, const COLS: usize>(board: [[i16; COLS]; ROWS]) -> Self {
+        const {
+            assert!(
+                ROWS * COLS > 1 && ROWS <= MAX_BOARD_SIDE && COLS <= MAX_BOARD_SIDE,
+                "boards have at least two cells, and at most MAX_BOARD_SIDE rows and columns"
+            )
+        };
+        Self::from_values(board.as_flattened().to_vec(), ROWS, COLS)
+    }
+
+    /// Creates a board of the given size from its values, row by row from the top of the board.
+    /// The size must have been checked to be supported.
+    fn from_values(values: Vec<i16>, rows: usize, cols: usize) -> Self {
         Self {
-            board: [
-                [57, 33, 132, 268, 492, 732],
-                [81, 123, 240, 443, 353, 508],
-                [186, 42, 195, 704, 452, 228],
-                [-7, 2, 357, 452, 317, 395],
-                [5, 23, -4, 592, 445, 620],
-                [0, 77, 32, 403, 337, 452],
-            ],
+            values,
+            rows,
+            cols,
+            end: (0, cols - 1),
+            movement: Movement::default(),
+            origin: Corner::default(),
+            annotations: HashMap::new(),
         }
     }
 
-    /// Creates a new cell representing the value and position at the starting position.
-    pub fn start_cell(&self) -> Cell {
+    /// Returns the cell at the given position, which must be on the board. This skips the checks
+    /// of `get_cell_at` where the position is known to be on the board, e.g. when moving.
+    fn cell(&self, position: Position) -> Cell {
         Cell {
-            value: self.board[BOARD_WIDTH - 1][0],
-            position: (BOARD_WIDTH - 1, 0),
+            value: self.values[position.0 * self.cols + position.1],
+            position,
+            end: position == self.end,
+        }
+    }
+
+    /// Returns the number of rows of the board.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns of the board.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Reads a board written row by row from the top of the board, one row per line, with values
+    /// separated by spaces or commas. Blank lines are skipped. The board can declare how the dice
+    /// moves on it with a `movement: <model>` line, e.g. `movement: orthogonal+wrap`, and which
+    /// corner chess-like squares are counted from with an `origin: <corner>` line, e.g.
+    /// `origin: top-left`. Cells can be named with `label: <cell> <text>` lines, e.g.
+    /// `label: c3 the trap cell`, and colored with `color: <cell> <color>` lines, e.g.
+    /// `color: 3,2 gold`, where cells are `row,col` pairs without spaces or chess-like squares.
+    ///
+    /// ```
+    /// use die_agony::Board;
+    ///
+    /// let board = Board::from_reader("1 2 3\n4, 5, 6\n".as_bytes()).unwrap();
+    /// assert_eq!((board.rows(), board.cols()), (2, 3));
+    /// assert_eq!(board.start_cell().get_value(), 4);
+    ///
+    /// assert!(Board::from_reader("1 2 3\n4 5\n".as_bytes()).is_err());
+    /// ```
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, String> {
+        let mut rows = Vec::new();
+        let mut movement = Movement::default();
+        let mut origin = Corner::default();
+        let mut annotations = Vec::new();
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|error| error.to_string())?;
+            let at_line = |error| format!("line {}: {}", line_number + 1, error);
+            match line.split_once(':') {
+                Some((key, value)) if key.trim() == "movement" => {
+                    movement = parse_movement(value).map_err(at_line)?;
+                }
+                Some((key, value)) if key.trim() == "origin" => {
+                    origin = parse_corner(value).map_err(at_line)?;
+                }
+                Some((key, value)) if ["label", "color"].contains(&key.trim()) => {
+                    let (square, text) =
+                        value
+                            .trim()
+                            .split_once(char::is_whitespace)
+                            .ok_or_else(|| {
+                                at_line(format!(
+                                    "`{}` is not a cell followed by a {}",
+                                    value.trim(),
+                                    key.trim()
+                                ))
+                            })?;
+                    let square = parse_square(square).map_err(at_line)?;
+                    let mut annotation = Annotation::default();
+                    if key.trim() == "label" {
+                        annotation.label = Some(text.trim().to_string());
+                    } else {
+                        annotation.color = Some(parse_color(text).map_err(at_line)?);
+                    }
+                    annotations.push((line_number + 1, square, annotation));
+                }
+                Some((key, _)) => {
+                    return Err(at_line(format!(
+                        "`{}` is not a property of the board",
+                        key.trim()
+                    )))
+                }
+                None if line.trim().is_empty() => {}
+                None => rows.push(parse_row(&line, line_number + 1)?),
+            }
+        }
+
+        let mut board = Self::try_from(rows)
+            .map_err(|error| error.to_string())?
+            .set_movement(movement)
+            .set_origin(origin);
+        // Squares are resolved once the whole file is read, as the origin can come after them.
+        for (line_number, square, annotation) in annotations {
+            let not_a_cell = || {
+                format!(
+                    "line {}: {} is not a cell of the board",
+                    line_number, square
+                )
+            };
+            let position = square.resolve(&board).ok_or_else(not_a_cell)?;
+            board = board
+                .annotate(position, annotation)
+                .ok_or_else(not_a_cell)?;
+        }
+
+        Ok(board)
+    }
+
+    /// Returns how the dice moves on the board.
+    pub fn movement(&self) -> Movement {
+        self.movement
+    }
+
+    /// Returns the position of the end cell.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns the corner chess-like squares are counted from.
+    pub fn origin(&self) -> Corner {
+        self.origin
+    }
+
+    /// Returns the chess-like notation of the board, counting squares from its origin corner.
+    pub fn chess_notation(&self) -> Notation {
+        Notation::Chess {
+            rows: self.rows,
+            cols: self.cols,
+            origin: self.origin,
         }
     }
 
+    /// Changes how the dice moves on the board, and returns the board.
+    pub fn set_movement(mut self, movement: Movement) -> Self {
+        self.movement = movement;
+
+        self
+    }
+
+    /// Returns the annotations of the cell at the given position, if it has any.
+    pub fn annotation(&self, position: Position) -> Option<&Annotation> {
+        self.annotations.get(&position)
+    }
+
+    /// Returns the label of the cell at the given position, if it has one.
+    pub fn label(&self, position: Position) -> Option<&str> {
+        self.annotation(position)?.label.as_deref()
+    }
+
+    /// Annotates the cell at the given position, keeping the annotations it already has unless
+    /// replaced, and returns the board. Returns `None` if the position is out of bounds.
+    pub fn annotate(mut self, position: Position, annotation: Annotation) -> Option<Self> {
+        self.get_cell_at(position)?;
+        let existing = self.annotations.entry(position).or_default();
+        existing.label = annotation.label.or(existing.label.take());
+        existing.color = annotation.color.or(existing.color.take());
+
+        Some(self)
+    }
+
+    /// Changes the corner chess-like squares are counted from, and returns the board.
+    pub fn set_origin(mut self, origin: Corner) -> Self {
+        self.origin = origin;
+
+        self
+    }
+
+    /// Moves the end cell to the given position, and returns the board. Returns `None` if the
+    /// position is out of bounds.
+    pub fn set_end(mut self, position: Position) -> Option<Self> {
+        self.get_cell_at(position)?;
+        self.end = position;
+
+        Some(self)
+    }
+
+    /// Creates a new cell representing the value and position at the starting position.
+    pub fn start_cell(&self) -> Cell {
+        // A board is never empty, so its bottom left corner is always one of its cells.
+        self.cell((self.rows - 1, 0))
+    }
+
+    /// Returns the Manhattan distance between the given cell and the end cell, which is the
+    /// minimum number of movements needed to reach it. When the dice wraps around the edges of the
+    /// board, going around is counted if shorter.
+    pub fn distance_to_end_cell(&self, cell: &Cell) -> usize {
+        let distance = |from: usize, to: usize, side: usize| {
+            let distance = from.abs_diff(to);
+            match self.movement {
+                Movement::Orthogonal => distance,
+                Movement::OrthogonalWrap => distance.min(side - distance),
+            }
+        };
+
+        distance(cell.position.0, self.end.0, self.rows)
+            + distance(cell.position.1, self.end.1, self.cols)
+    }
+
     /// Tries to return the cell we land onto after moving from the current cell
     /// in the given direction.
     /// Returns `None` if that movement would be out of bounds.
     pub fn move_in(&self, curr_cell: &Cell, direction: Direction) -> Option<Cell> {
         let (row, col) = curr_cell.position;
+        if self.movement == Movement::OrthogonalWrap {
+            let (rows, cols) = (self.rows, self.cols);
+            let wrapped_position = match direction {
+                Direction::UP => ((row + rows - 1) % rows, col),
+                Direction::RIGHT => (row, (col + 1) % cols),
+                Direction::DOWN => ((row + 1) % rows, col),
+                Direction::LEFT => (row, (col + cols - 1) % cols),
+            };
+            // Wrapping around the edges always lands on the board.
+            return Some(self.cell(wrapped_position));
+        }
 
         let moved_position = match direction {
             Direction::UP => {
@@ -88,26 +652,167 @@ impl Board {
         self.get_cell_at(moved_position)
     }
 
+    /// Returns the cell mirroring the given one across the diagonal going through the start and
+    /// end cells. The board must be square.
+    pub(crate) fn mirror(&self, cell: &Cell) -> Cell {
+        let (row, col) = cell.position;
+        self.cell((self.cols - 1 - col, self.rows - 1 - row))
+    }
+
+    /// Returns whether the board is symmetric across the diagonal going through the start and end
+    /// cells, in which case every journey has a mirror image. A board which is not square, or which
+    /// end cell was moved off that diagonal, is never symmetric.
+    pub fn is_symmetric(&self) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+
+        let end_cell = self.cell(self.end);
+        self.mirror(&end_cell) == end_cell
+            && self
+                .cells()
+                .all(|cell| self.mirror(&cell).value == cell.value)
+    }
+
+    /// Writes the values of the board row by row, from the top of the board, separating values with
+    /// commas and rows with semicolons. The end cell and the movement model follow, e.g.
+    /// `|end=2,3|movement=orthogonal+wrap`, unless they are those of the puzzle. Equal boards always
+    /// have the same canonical form.
+    pub fn canonical_form(&self) -> String {
+        let mut form = self
+            .values
+            .chunks(self.cols)
+            .map(|row| row.iter().map(i16::to_string).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>()
+            .join(";");
+        if self.end != (0, self.cols - 1) {
+            form += &format!("|end={},{}", self.end.0, self.end.1);
+        }
+        if self.movement != Movement::default() {
+            form += &format!("|movement={}", self.movement.name());
+        }
+
+        form
+    }
+
+    /// Lists the positions of all the cells of the board, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    /// Lists all the cells of the board, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.positions().map(|position| self.cell(position))
+    }
+
+    /// Returns the index of the given cell, when cells are numbered row by row from 0.
+    pub fn index_of(&self, cell: &Cell) -> usize {
+        cell.position.0 * self.cols + cell.position.1
+    }
+
     pub fn get_cell_at(&self, position: Position) -> Option<Cell> {
-        let value = *self.board.get(position.0)?.get(position.1)?;
-        Some(Cell { value, position })
+        if position.0 >= self.rows || position.1 >= self.cols {
+            return None;
+        }
+
+        Some(self.cell(position))
+    }
+
+    /// Changes the value of the cell at the given position, and returns the board. Returns `None`
+    /// if the position is out of bounds.
+    pub fn set_cell_value(mut self, position: Position, value: i16) -> Option<Self> {
+        if position.0 >= self.rows || position.1 >= self.cols {
+            return None;
+        }
+        self.values[position.0 * self.cols + position.1] = value;
+
+        Some(self)
     }
 
     pub fn compute_sum_of_unvisited_cells(
         &self,
         unique_visited_positions: &HashSet<&Position>,
-    ) -> i16 {
-        let mut sum = 0;
-        for row in 0..BOARD_WIDTH {
-            for col in 0..BOARD_WIDTH {
-                let position = (row, col);
-                if !unique_visited_positions.contains(&position) {
-                    sum += self.board[row][col];
-                }
+    ) -> i32 {
+        self.positions()
+            .zip(&self.values)
+            .filter(|(position, _)| !unique_visited_positions.contains(position))
+            .map(|(_, &value)| value as i32)
+            .sum()
+    }
+}
+
+/// Parses the values of a row of a board, separated by spaces or commas. The line number is only
+/// used to report invalid values.
+pub fn parse_row(line: &str, line_number: usize) -> Result<Vec<i64>, String> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .map_err(|_| format!("line {}: `{}` is not a valid value", line_number, value))
+        })
+        .collect()
+}
+
+impl Default for Board {
+    /// Returns the puzzle board.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: AsRef<[i64]>> TryFrom<&[R]> for Board {
+    type Error = BoardError;
+
+    /// Creates a board from its rows, from the top of the board.
+    fn try_from(rows: &[R]) -> Result<Self, Self::Error> {
+        let cols = rows.first().map_or(0, |row| row.as_ref().len());
+        if cols == 0 {
+            return Err(BoardError::Empty);
+        }
+        if let Some((row, values)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, values)| values.as_ref().len() != cols)
+        {
+            return Err(BoardError::RaggedRow {
+                row,
+                expected: cols,
+                found: values.as_ref().len(),
+            });
+        }
+        if rows.len() > MAX_BOARD_SIDE || cols > MAX_BOARD_SIDE {
+            return Err(BoardError::UnsupportedSize {
+                rows: rows.len(),
+                cols,
+            });
+        }
+        if rows.len() * cols == 1 {
+            return Err(BoardError::SingleCell);
+        }
+
+        let mut board = Vec::with_capacity(rows.len() * cols);
+        for (row, values) in rows.iter().enumerate() {
+            for (col, &value) in values.as_ref().iter().enumerate() {
+                board.push(
+                    i16::try_from(value).map_err(|_| BoardError::ValueOutOfRange {
+                        position: (row, col),
+                        value,
+                    })?,
+                );
             }
         }
 
-        sum
+        Ok(Self::from_values(board, rows.len(), cols))
+    }
+}
+
+impl TryFrom<Vec<Vec<i64>>> for Board {
+    type Error = BoardError;
+
+    fn try_from(rows: Vec<Vec<i64>>) -> Result<Self, Self::Error> {
+        Self::try_from(rows.as_slice())
     }
 }
 
@@ -119,10 +824,12 @@ mod tests {
     fn try_moving_from_bottom_left() {
         let board = Board::new();
         let cell = Cell {
-            value: board.board[BOARD_WIDTH - 1][0],
+            value: board.values[(BOARD_WIDTH - 1) * BOARD_WIDTH],
             position: (BOARD_WIDTH - 1, 0),
+            end: false,
         };
         assert!(!cell.is_end_cell());
+        assert_eq!(board.distance_to_end_cell(&cell), 2 * (BOARD_WIDTH - 1));
         assert_eq!(cell, board.start_cell());
 
         assert!(board.move_in(&cell, Direction::LEFT).is_none());
@@ -131,24 +838,50 @@ mod tests {
             board.move_in(&cell, Direction::UP),
             Some(Cell {
                 value: 5,
-                position: (4, 0)
+                position: (4, 0),
+                end: false,
             })
         );
         assert_eq!(
             board.move_in(&cell, Direction::RIGHT),
             Some(Cell {
                 value: 77,
-                position: (5, 1)
+                position: (5, 1),
+                end: false,
             })
         );
     }
 
+    #[test]
+    fn set_end_moves_the_end_cell() {
+        let board = Board::new().set_end((2, 3)).unwrap();
+        let cell = board.get_cell_at((2, 3)).unwrap();
+        assert!(cell.is_end_cell());
+        assert!(!board.get_cell_at((0, 5)).unwrap().is_end_cell());
+        assert_eq!(board.distance_to_end_cell(&board.start_cell()), 3 + 3);
+        assert!(Board::new().set_end((0, 6)).is_none());
+
+        // Off the diagonal going through the start cell, the end cell breaks the symmetry.
+        let zeros = Board::from_rows([[0; BOARD_WIDTH]; BOARD_WIDTH]);
+        assert!(zeros.clone().set_end((2, 3)).unwrap().is_symmetric());
+        assert!(!zeros.set_end((2, 2)).unwrap().is_symmetric());
+    }
+
+    #[test]
+    fn set_cell_value_changes_a_single_cell() {
+        let board = Board::new().set_cell_value((3, 0), 42).unwrap();
+        assert_eq!(board.get_cell_at((3, 0)).unwrap().get_value(), 42);
+        assert_eq!(board.get_cell_at((3, 1)).unwrap().get_value(), 2);
+        assert!(Board::new().set_cell_value((6, 0), 42).is_none());
+    }
+
     #[test]
     fn try_moving_from_top_left() {
         let board = Board::new();
         let cell = Cell {
-            value: board.board[0][0],
+            value: board.values[0],
             position: (0, 0),
+            end: false,
         };
         assert!(!cell.is_end_cell());
         assert_ne!(cell, board.start_cell());
@@ -159,14 +892,16 @@ mod tests {
             board.move_in(&cell, Direction::RIGHT),
             Some(Cell {
                 value: 33,
-                position: (0, 1)
+                position: (0, 1),
+                end: false,
             })
         );
         assert_eq!(
             board.move_in(&cell, Direction::DOWN),
             Some(Cell {
                 value: 81,
-                position: (1, 0)
+                position: (1, 0),
+                end: false,
             })
         );
     }
@@ -175,10 +910,12 @@ mod tests {
     fn try_moving_from_top_right() {
         let board = Board::new();
         let cell = Cell {
-            value: board.board[0][BOARD_WIDTH - 1],
+            value: board.values[BOARD_WIDTH - 1],
             position: (0, BOARD_WIDTH - 1),
+            end: true,
         };
         assert!(cell.is_end_cell());
+        assert_eq!(board.distance_to_end_cell(&cell), 0);
         assert_ne!(cell, board.start_cell());
 
         assert!(board.move_in(&cell, Direction::UP).is_none());
@@ -187,14 +924,16 @@ mod tests {
             board.move_in(&cell, Direction::DOWN),
             Some(Cell {
                 value: 508,
-                position: (1, BOARD_WIDTH - 1)
+                position: (1, BOARD_WIDTH - 1),
+                end: false,
             })
         );
         assert_eq!(
             board.move_in(&cell, Direction::LEFT),
             Some(Cell {
                 value: 492,
-                position: (0, BOARD_WIDTH - 2)
+                position: (0, BOARD_WIDTH - 2),
+                end: false,
             })
         );
     }
@@ -203,8 +942,9 @@ mod tests {
     fn try_moving_from_bottom_right() {
         let board = Board::new();
         let cell = Cell {
-            value: board.board[BOARD_WIDTH - 1][BOARD_WIDTH - 1],
+            value: board.values[BOARD_WIDTH * BOARD_WIDTH - 1],
             position: (BOARD_WIDTH - 1, BOARD_WIDTH - 1),
+            end: false,
         };
         assert!(!cell.is_end_cell());
         assert_ne!(cell, board.start_cell());
@@ -215,28 +955,39 @@ mod tests {
             board.move_in(&cell, Direction::LEFT),
             Some(Cell {
                 value: 337,
-                position: (BOARD_WIDTH - 1, BOARD_WIDTH - 2)
+                position: (BOARD_WIDTH - 1, BOARD_WIDTH - 2),
+                end: false,
             })
         );
         assert_eq!(
             board.move_in(&cell, Direction::UP),
             Some(Cell {
                 value: 620,
-                position: (BOARD_WIDTH - 2, BOARD_WIDTH - 1)
+                position: (BOARD_WIDTH - 2, BOARD_WIDTH - 1),
+                end: false,
             })
         );
     }
 
+    #[test]
+    fn positions_cover_the_board() {
+        let board = Board::new();
+        let positions: Vec<_> = board.positions().collect();
+
+        assert_eq!(positions.len(), BOARD_WIDTH * BOARD_WIDTH);
+        assert_eq!(positions[0], (0, 0));
+        assert_eq!(positions[1], (0, 1));
+        assert_eq!(positions.last(), Some(&(BOARD_WIDTH - 1, BOARD_WIDTH - 1)));
+        assert!(positions
+            .iter()
+            .all(|&position| board.get_cell_at(position).is_some()));
+    }
+
     #[test]
     fn compute_sum_of_unvisited_cells_works() {
         let board = Board::new();
 
-        let mut sum_of_all_cells = 0;
-        for row in 0..BOARD_WIDTH {
-            for col in 0..BOARD_WIDTH {
-                sum_of_all_cells += board.board[row][col];
-            }
-        }
+        let sum_of_all_cells: i32 = board.values.iter().map(|&value| value as i32).sum();
 
         let mut unique_visited_positions = HashSet::new();
         assert_eq!(
@@ -244,12 +995,374 @@ mod tests {
             sum_of_all_cells
         );
 
-        let value_top_left_cell = board.board[0][0];
+        let value_top_left_cell = board.values[0] as i32;
         let top_left_cell_position = (0, 0);
         unique_visited_positions.insert(&top_left_cell_position);
         assert_eq!(
             board.compute_sum_of_unvisited_cells(&unique_visited_positions),
             sum_of_all_cells - value_top_left_cell
-        )
+        );
+    }
+
+    #[test]
+    fn sum_of_unvisited_cells_does_not_overflow() {
+        let board = Board::from_rows([[i16::MAX; BOARD_WIDTH]; BOARD_WIDTH]);
+        assert_eq!(
+            board.compute_sum_of_unvisited_cells(&HashSet::new()),
+            36 * i16::MAX as i32
+        );
+    }
+
+    #[test]
+    fn try_from_rows_works() {
+        let rows: Vec<Vec<i64>> = (0..6)
+            .map(|row| (0..6).map(|col| row * 6 + col).collect())
+            .collect();
+        let board = Board::try_from(rows).unwrap();
+        assert_eq!(board.get_cell_at((0, 0)).unwrap().get_value(), 0);
+        assert_eq!(board.get_cell_at((2, 3)).unwrap().get_value(), 15);
+
+        let rows: [&[i64]; 6] = [&[1; 6]; 6];
+        let board = Board::try_from(&rows[..]).unwrap();
+        assert_eq!(board.start_cell().get_value(), 1);
+    }
+
+    #[test]
+    fn try_from_rows_reports_problems() {
+        assert_eq!(
+            Board::try_from(Vec::<Vec<i64>>::new()).err(),
+            Some(BoardError::Empty)
+        );
+        assert_eq!(
+            Board::try_from(vec![vec![]; 6]).err(),
+            Some(BoardError::Empty)
+        );
+
+        let mut rows = vec![vec![0; 6]; 6];
+        rows[3].pop();
+        assert_eq!(
+            Board::try_from(rows).err(),
+            Some(BoardError::RaggedRow {
+                row: 3,
+                expected: 6,
+                found: 5
+            })
+        );
+
+        assert_eq!(
+            Board::try_from(vec![vec![0; 256]; 2]).err(),
+            Some(BoardError::UnsupportedSize { rows: 2, cols: 256 })
+        );
+        // On a single cell, the dice would start on the end cell, without any journey to search.
+        assert_eq!(
+            Board::try_from(vec![vec![7]]).err(),
+            Some(BoardError::SingleCell)
+        );
+        assert!(Board::from_reader("7\n".as_bytes()).is_err());
+        assert!(Board::try_from(vec![vec![7, 0]]).is_ok());
+        assert!(Board::try_from(vec![vec![0; 6]; 5]).is_ok());
+
+        let mut rows = vec![vec![0; 6]; 6];
+        rows[1][4] = 40_000;
+        assert_eq!(
+            Board::try_from(rows).err(),
+            Some(BoardError::ValueOutOfRange {
+                position: (1, 4),
+                value: 40_000
+            })
+        );
+    }
+
+    #[test]
+    fn chess_notation_starts_at_the_bottom_left() {
+        let board = Board::new();
+        let chess = board.chess_notation();
+        assert_eq!(chess.format((5, 0)), "a1");
+        assert_eq!(chess.format((0, BOARD_WIDTH - 1)), "f6");
+        assert_eq!(chess.format((3, 2)), "c3");
+        let short = Board::from_rows([[0; BOARD_WIDTH]; 4]);
+        assert_eq!(short.chess_notation().format((3, 2)), "c1");
+        assert_eq!(Notation::RowCol.format((3, 2)), "(3, 2)");
+
+        for position in board.positions() {
+            let square = parse_chess_square(&chess.format(position)).unwrap();
+            assert_eq!(square.resolve(&board), Some(position));
+        }
+        let square = parse_chess_square("C3").unwrap();
+        assert_eq!(square.to_string(), "c3");
+        assert_eq!(square.resolve(&board), Some((3, 2)));
+        assert_eq!(square.resolve(&Board::from_rows([[0; 6]; 2])), None);
+        assert_eq!(square.resolve(&Board::from_rows([[0; 2]; 6])), None);
+        for square in ["", "a", "a0", "11", "a1b", "+1"] {
+            assert_eq!(parse_chess_square(square), None, "{}", square);
+        }
+    }
+
+    #[test]
+    fn chess_notation_counts_from_the_origin_of_the_board() {
+        for (corner, first, last) in [
+            (Corner::BottomLeft, (2, 0), (0, 3)),
+            (Corner::BottomRight, (2, 3), (0, 0)),
+            (Corner::TopLeft, (0, 0), (2, 3)),
+            (Corner::TopRight, (0, 3), (2, 0)),
+        ] {
+            let board = Board::from_rows([[0; 4]; 3]).set_origin(corner);
+            let chess = board.chess_notation();
+            assert_eq!(chess.format(first), "a1", "{}", corner.name());
+            assert_eq!(chess.format(last), "d3", "{}", corner.name());
+            for position in board.positions() {
+                let square = parse_chess_square(&chess.format(position)).unwrap();
+                assert_eq!(square.resolve(&board), Some(position));
+            }
+        }
+
+        let file = "origin: top-left\n".to_string() + &"0 0 0 0 0 0\n".repeat(6);
+        let board = Board::from_reader(file.as_bytes()).unwrap();
+        assert_eq!(board.origin(), Corner::TopLeft);
+        assert_eq!(
+            board
+                .chess_notation()
+                .format(*board.start_cell().get_position()),
+            "a6"
+        );
+        assert_eq!(
+            Board::from_reader("origin: middle\n".as_bytes()).err(),
+            Some(
+                "line 1: `middle` is not a corner, which is either `bottom-left`, \
+                 `bottom-right`, `top-left` or `top-right`"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn chess_files_take_several_letters_on_wide_boards() {
+        let chess = Notation::Chess {
+            rows: 1,
+            cols: 703,
+            origin: Corner::BottomLeft,
+        };
+        for (col, square) in [
+            (25, "z1"),
+            (26, "aa1"),
+            (27, "ab1"),
+            (701, "zz1"),
+            (702, "aaa1"),
+        ] {
+            assert_eq!(chess.format((0, col)), square);
+            let parsed = parse_chess_square(square).unwrap();
+            assert_eq!(parsed, Square::Chess { col, rank: 1 });
+            assert_eq!(parsed.to_string(), square);
+        }
+        assert_eq!(
+            parse_chess_square("AB12"),
+            Some(Square::Chess { col: 27, rank: 12 })
+        );
+        // Files too long to be a column of any board are not squares.
+        assert_eq!(parse_chess_square(&("z".repeat(20) + "1")), None);
+    }
+
+    #[test]
+    fn chess_ranks_are_only_digits() {
+        for square in ["a+1", "a-1", "a 1", "a1 ", "a١"] {
+            assert_eq!(parse_chess_square(square), None, "{}", square);
+        }
+        assert!(parse_square("a+1").is_err());
+        assert_eq!(
+            parse_chess_square("a01"),
+            Some(Square::Chess { col: 0, rank: 1 })
+        );
+    }
+
+    #[test]
+    fn mirror_swaps_rows_and_columns_across_the_diagonal() {
+        let board = Board::new();
+        let start_cell = board.start_cell();
+        assert_eq!(board.mirror(&start_cell), start_cell);
+
+        let cell = board.get_cell_at((4, 0)).unwrap();
+        assert_eq!(board.mirror(&cell), board.get_cell_at((5, 1)).unwrap());
+        assert!(!board.is_symmetric());
+
+        let mut rows = [[0; BOARD_WIDTH]; BOARD_WIDTH];
+        rows[4][0] = 3;
+        rows[5][1] = 3;
+        assert!(Board::from_rows(rows).is_symmetric());
+    }
+
+    #[test]
+    fn canonical_form_lists_values_row_by_row() {
+        let mut rows = [[0; BOARD_WIDTH]; BOARD_WIDTH];
+        rows[0][1] = -4;
+        rows[5][5] = 12;
+        assert_eq!(
+            Board::from_rows(rows).canonical_form(),
+            "0,-4,0,0,0,0;0,0,0,0,0,0;0,0,0,0,0,0;0,0,0,0,0,0;0,0,0,0,0,0;0,0,0,0,0,12"
+        );
+    }
+
+    #[test]
+    fn rectangular_boards_start_and_end_at_their_corners() {
+        let board = Board::from_rows([[1, 2, 3], [4, 5, 6]]);
+        let start_cell = board.start_cell();
+        assert_eq!(start_cell.position, (1, 0));
+        assert_eq!(start_cell.value, 4);
+        assert_eq!(board.get_cell_at((0, 2)).unwrap().value, 3);
+        assert!(board.get_cell_at((0, 2)).unwrap().is_end_cell());
+        assert!(board.get_cell_at((2, 0)).is_none());
+        assert!(board.get_cell_at((0, 3)).is_none());
+        assert_eq!(board.distance_to_end_cell(&start_cell), 3);
+        assert!(!board.is_symmetric());
+
+        let right = board.move_in(&start_cell, Direction::RIGHT).unwrap();
+        assert_eq!(right.position, (1, 1));
+        assert!(board.move_in(&start_cell, Direction::DOWN).is_none());
+        assert_eq!(
+            board.compute_sum_of_unvisited_cells(&HashSet::from([&(1, 0), &(1, 1)])),
+            12
+        );
+        assert_eq!(board.canonical_form(), "1,2,3;4,5,6");
+
+        let board = board.set_movement(Movement::OrthogonalWrap);
+        assert_eq!(
+            board.clone().set_end((1, 2)).unwrap().canonical_form(),
+            "1,2,3;4,5,6|end=1,2|movement=orthogonal+wrap"
+        );
+        assert_eq!(board.distance_to_end_cell(&start_cell), 2);
+        assert_eq!(
+            board
+                .move_in(&start_cell, Direction::LEFT)
+                .unwrap()
+                .position,
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn from_reader_reads_rows_of_values() {
+        let file = "57 33 132 268 492 732\n81,123,240,443,353,508\n\n186, 42, 195, 704, 452, 228\n\
+                    -7 2 357 452 317 395\n5 23 -4 592 445 620\n0 77 32 403 337 452\n";
+        let board = Board::from_reader(file.as_bytes()).unwrap();
+        assert_eq!(board.canonical_form(), Board::new().canonical_form());
+
+        assert_eq!(
+            Board::from_reader("0 0\n0 x\n".as_bytes()).err(),
+            Some("line 2: `x` is not a valid value".to_string())
+        );
+        let board = Board::from_reader("0 0 0\n0 0 0\n".as_bytes()).unwrap();
+        assert_eq!((board.rows(), board.cols()), (2, 3));
+    }
+
+    #[test]
+    fn wrapping_boards_roll_off_the_edges() {
+        let board = Board::new().set_movement(Movement::OrthogonalWrap);
+        let start_cell = board.start_cell();
+        assert_eq!(
+            board
+                .move_in(&start_cell, Direction::DOWN)
+                .unwrap()
+                .position,
+            (0, 0)
+        );
+        assert_eq!(
+            board
+                .move_in(&start_cell, Direction::LEFT)
+                .unwrap()
+                .position,
+            (BOARD_WIDTH - 1, BOARD_WIDTH - 1)
+        );
+        // Going down then left is shorter than going up then right.
+        assert_eq!(board.distance_to_end_cell(&start_cell), 2);
+    }
+
+    #[test]
+    fn from_reader_reads_the_annotations_of_cells() {
+        let file = "label: c3 the trap cell\ncolor: 3,2 #ffcc00\nlabel: 0,0 a corner\n".to_string()
+            + &"0 0 0 0 0 0\n".repeat(6)
+            + "origin: top-left\n";
+        let board = Board::from_reader(file.as_bytes()).unwrap();
+        // The chess-like square is counted from the origin declared after it.
+        assert_eq!(
+            board.annotation((2, 2)),
+            Some(&Annotation {
+                label: Some("the trap cell".to_string()),
+                color: None,
+            })
+        );
+        assert_eq!(board.label((3, 2)), None);
+        assert_eq!(
+            board.annotation((3, 2)).unwrap().color.as_deref(),
+            Some("#ffcc00")
+        );
+        assert_eq!(board.label((0, 0)), Some("a corner"));
+
+        let board = Board::new()
+            .annotate(
+                (0, 0),
+                Annotation {
+                    label: Some("a corner".to_string()),
+                    color: None,
+                },
+            )
+            .unwrap()
+            .annotate(
+                (0, 0),
+                Annotation {
+                    label: None,
+                    color: Some("gold".to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(board.label((0, 0)), Some("a corner"));
+        assert!(Board::new()
+            .annotate((6, 0), Annotation::default())
+            .is_none());
+
+        let rows = "0 0\n0 0\n";
+        for (line, error) in [
+            ("label: c3 trap", "line 1: c3 is not a cell of the board"),
+            (
+                "label: 2,0 trap",
+                "line 1: (2, 0) is not a cell of the board",
+            ),
+            (
+                "label: a1",
+                "line 1: `a1` is not a cell followed by a label",
+            ),
+            (
+                "color: a1 #ffcc",
+                "line 1: `#ffcc` is not a color, which is either a name like `gold` or a \
+                 hexadecimal color like `#ffcc00`",
+            ),
+        ] {
+            let file = format!("{}\n{}", line, rows);
+            assert_eq!(
+                Board::from_reader(file.as_bytes()).err(),
+                Some(error.to_string()),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn from_reader_reads_the_movement() {
+        let file = "movement: orthogonal+wrap\n".to_string() + &"0 0 0 0 0 0\n".repeat(6);
+        let board = Board::from_reader(file.as_bytes()).unwrap();
+        assert_eq!(board.movement, Movement::OrthogonalWrap);
+
+        assert_eq!(
+            Board::from_reader("movement: orthogonal+diagonal\n".as_bytes()).err(),
+            Some(
+                "line 1: the `orthogonal+diagonal` movement is not supported, as the dice can \
+                 only roll onto orthogonal neighbours"
+                    .to_string()
+            )
+        );
+        assert!(Board::from_reader("movement: offsets 1,2 2,1\n".as_bytes()).is_err());
+        assert_eq!(
+            Board::from_reader("size: 6\n".as_bytes()).err(),
+            Some("line 1: `size` is not a property of the board".to_string())
+        );
     }
 }
@@ -0,0 +1,211 @@
+use crate::{
+    board::{parse_row, Board},
+    solver::{Solution, SolverBuilder},
+};
+
+use std::time::{Duration, Instant};
+
+/// Parses a campaign, listing boards one after the other, separated by blank lines. Each board is
+/// written row by row from the top of the board, with values separated by spaces or commas.
+pub fn parse_campaign(campaign: &str) -> Result<Vec<Board>, String> {
+    let mut boards = Vec::new();
+    let mut rows: Vec<Vec<i64>> = Vec::new();
+    for (line_number, line) in campaign.lines().chain([""]).enumerate() {
+        if line.trim().is_empty() {
+            if !rows.is_empty() {
+                let board = Board::try_from(std::mem::take(&mut rows))
+                    .map_err(|error| format!("board {}: {}", boards.len() + 1, error))?;
+                boards.push(board);
+            }
+            continue;
+        }
+
+        rows.push(parse_row(line, line_number + 1)?);
+    }
+    if boards.is_empty() {
+        return Err("the campaign does not list any board".to_string());
+    }
+
+    Ok(boards)
+}
+
+/// Solves the boards of a campaign in order, each with a solver configured by the given builder.
+/// The dice ending the journey on a board starts the journey on the next one, with the same
+/// orientation and face values. The campaign stops at the first board which cannot be solved,
+/// which is the last solution returned. Returns `None` if the builder cannot build a solver for
+/// one of the boards, as its avoided cells or waypoints do not fit it.
+pub fn solve_campaign(builder: SolverBuilder, boards: Vec<Board>) -> Option<Vec<Solution>> {
+    let mut solutions = Vec::with_capacity(boards.len());
+    let mut dice = None;
+    for board in boards {
+        let mut board_builder = builder.clone().board(board);
+        if let Some(dice) = dice {
+            board_builder = board_builder.dice(dice);
+        }
+        let solution = board_builder.build()?.solve();
+        match &solution {
+            Solution::Found(report) => dice = Some(report.final_dice()),
+            Solution::NotFound(..) => {
+                solutions.push(solution);
+                break;
+            }
+        }
+        solutions.push(solution);
+    }
+
+    Some(solutions)
+}
+
+/// Results of solving the boards of a set independently, each with the dice the builder starts
+/// with, e.g. to run the solver over a whole season of puzzles.
+pub struct CampaignSummary {
+    boards: Vec<BoardSummary>,
+}
+
+/// Result of solving a single board of a set.
+struct BoardSummary {
+    /// Sum of the unvisited cells, if the board was solved.
+    answer: Option<i32>,
+    elapsed: Duration,
+    /// Steps expanded by the search.
+    nodes: u64,
+}
+
+impl CampaignSummary {
+    /// Solves each of the boards with a solver configured by the given builder. Returns `None` if
+    /// the builder cannot build a solver for one of the boards.
+    pub fn solve(builder: SolverBuilder, boards: Vec<Board>) -> Option<Self> {
+        let boards = boards
+            .into_iter()
+            .map(|board| {
+                let start = Instant::now();
+                let (solution, stats) = builder.clone().board(board).build()?.solve_with_stats();
+                Some(BoardSummary {
+                    answer: match solution {
+                        Solution::Found(report) => Some(report.unvisited_sum),
+                        Solution::NotFound(..) => None,
+                    },
+                    elapsed: start.elapsed(),
+                    nodes: stats.expanded,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { boards })
+    }
+
+    /// Describes the result of each board, one per line, followed by the totals across boards.
+    pub fn describe(&self) -> String {
+        let mut lines: Vec<String> = self
+            .boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| {
+                format!(
+                    "Board {}: {} in {:.3?}, expanding {} nodes.",
+                    index + 1,
+                    board
+                        .answer
+                        .map_or("no solution found".to_string(), |answer| {
+                            format!("the answer is {}", answer)
+                        }),
+                    board.elapsed,
+                    board.nodes
+                )
+            })
+            .collect();
+
+        let solved = self.boards.iter().filter(|board| board.answer.is_some());
+        lines.push(format!(
+            "Total: {} of {} boards solved, with answers summing to {}, in {:.3?}, expanding {} nodes.",
+            solved.clone().count(),
+            self.boards.len(),
+            solved.filter_map(|board| board.answer).map(i64::from).sum::<i64>(),
+            self.boards.iter().map(|board| board.elapsed).sum::<Duration>(),
+            self.boards.iter().map(|board| board.nodes).sum::<u64>()
+        ));
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    #[test]
+    fn parse_campaign_splits_boards_on_blank_lines() {
+        let boards = parse_campaign(
+            "0 0 0 0 0 1\n0 0 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n\n\n\
+             0,0,0,0,0,2\n0,0,0,0,0,0\n0,0,0,0,0,0\n0,0,0,0,0,0\n0,0,0,0,0,0\n0,0,0,0,0,0\n",
+        )
+        .unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].get_cell_at((0, 5)).unwrap().get_value(), 1);
+        assert_eq!(boards[1].get_cell_at((0, 5)).unwrap().get_value(), 2);
+    }
+
+    #[test]
+    fn parse_campaign_rejects_invalid_boards() {
+        assert!(parse_campaign("").is_err());
+        assert!(parse_campaign("0 0 0\n0 0\n").is_err());
+        assert!(parse_campaign("0 0 0 0 0 x\n").is_err());
+    }
+
+    #[test]
+    fn campaign_carries_the_dice_over() {
+        let zeros = || Board::from_rows([[0; 6]; 6]);
+        let solutions =
+            solve_campaign(Solver::builder(), vec![Board::new(), zeros(), zeros()]).unwrap();
+        let Solution::Found(report) = &solutions[0] else {
+            panic!("the puzzle board should be solved");
+        };
+        assert_eq!(report.unvisited_sum, 1935);
+        assert!(report
+            .final_dice()
+            .known_faces()
+            .any(|(_, value)| value != 0));
+
+        // On a board of zeros, every face scored must hold 0, which the dice ending the puzzle
+        // board does not allow, so the campaign stops there.
+        assert_eq!(solutions.len(), 2);
+        assert!(matches!(solutions[1], Solution::NotFound(..)));
+        assert!(matches!(
+            Solver::builder().board(zeros()).build().unwrap().solve(),
+            Solution::Found(..)
+        ));
+    }
+
+    #[test]
+    fn campaign_stops_at_the_first_unsolvable_board() {
+        let solutions = solve_campaign(
+            Solver::builder().max_depth(5),
+            vec![Board::new(), Board::new(), Board::new()],
+        )
+        .unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!(matches!(solutions[0], Solution::NotFound(..)));
+    }
+
+    #[test]
+    fn campaign_summary_totals_the_answers() {
+        // The puzzle board needs 32 turns, but a board of zeros only 10.
+        let summary = CampaignSummary::solve(
+            Solver::builder().max_depth(12),
+            vec![
+                Board::from_rows([[0; 6]; 6]),
+                Board::new(),
+                Board::from_rows([[1; 6]; 6]),
+            ],
+        )
+        .unwrap();
+        let description = summary.describe();
+        let lines: Vec<&str> = description.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Board 1: the answer is 0 in "));
+        assert!(lines[1].starts_with("Board 2: no solution found in "));
+        assert!(lines[2].starts_with("Board 3: the answer is "));
+        assert!(lines[3].starts_with("Total: 2 of 3 boards solved, with answers summing to "));
+    }
+}
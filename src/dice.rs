@@ -1,101 +1,291 @@
 use crate::direction::Direction;
 
+use std::fmt;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Number of distinct ways a cube can sit on the board.
+const ORIENTATION_COUNT: usize = 24;
+
+/// Enumerates the six sides of the dice, as seen from the board. Each side holds one of the six
+/// faces of the dice, depending on its orientation.
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq)]
+pub enum Face {
+    /// The side facing up, away from the board.
+    Top,
+    /// The side touching the board.
+    Bottom,
+    /// The side facing the left of the board.
+    Left,
+    /// The side facing the right of the board.
+    Right,
+    /// The side facing the top of the board.
+    Front,
+    /// The side facing the bottom of the board.
+    Back,
+}
+
+impl Face {
+    /// Returns the side opposite to this one.
+    pub fn opposite(self) -> Self {
+        match self {
+            Face::Top => Face::Bottom,
+            Face::Bottom => Face::Top,
+            Face::Left => Face::Right,
+            Face::Right => Face::Left,
+            Face::Front => Face::Back,
+            Face::Back => Face::Front,
+        }
+    }
+
+    /// Returns the name of the side, as written in descriptions of the dice.
+    pub fn name(self) -> &'static str {
+        match self {
+            Face::Top => "top",
+            Face::Bottom => "bottom",
+            Face::Left => "left",
+            Face::Right => "right",
+            Face::Front => "front",
+            Face::Back => "back",
+        }
+    }
+}
+
+const TOP: usize = Face::Top as usize;
+const BOTTOM: usize = Face::Bottom as usize;
+const LEFT: usize = Face::Left as usize;
+const RIGHT: usize = Face::Right as usize;
+const FRONT: usize = Face::Front as usize;
+const BACK: usize = Face::Back as usize;
+
+/// For each direction, lists which side each side of the dice comes from after rolling.
+/// Indexed first by `Direction`, then by the side after the roll.
+const ROLL_SOURCES: [[usize; 6]; 4] = [
+    // UP
+    [BACK, FRONT, LEFT, RIGHT, TOP, BOTTOM],
+    // RIGHT
+    [LEFT, RIGHT, BOTTOM, TOP, FRONT, BACK],
+    // DOWN
+    [FRONT, BACK, LEFT, RIGHT, BOTTOM, TOP],
+    // LEFT
+    [RIGHT, LEFT, TOP, BOTTOM, FRONT, BACK],
+];
+
+/// Precomputed orientations of the dice, and the orientation reached by rolling in each direction.
+struct OrientationTable {
+    /// For each orientation, the face label found on each side.
+    labels: [[u8; 6]; ORIENTATION_COUNT],
+    /// For each orientation, the orientation reached after rolling in each direction.
+    rolls: [[u8; 4]; ORIENTATION_COUNT],
+}
+
+const ORIENTATIONS: OrientationTable = build_orientation_table();
+
+/// Discovers all 24 orientations by rolling the identity orientation in every direction,
+/// recording the transitions along the way.
+const fn build_orientation_table() -> OrientationTable {
+    let mut table = OrientationTable {
+        labels: [[0; 6]; ORIENTATION_COUNT],
+        rolls: [[0; 4]; ORIENTATION_COUNT],
+    };
+    table.labels[0] = [0, 1, 2, 3, 4, 5];
+
+    let mut discovered = 1;
+    let mut orientation = 0;
+    while orientation < discovered {
+        let mut direction = 0;
+        while direction < 4 {
+            let mut rolled = [0; 6];
+            let mut side = 0;
+            while side < 6 {
+                rolled[side] = table.labels[orientation][ROLL_SOURCES[direction][side]];
+                side += 1;
+            }
+
+            let mut found = 0;
+            while found < discovered && !same_labels(&table.labels[found], &rolled) {
+                found += 1;
+            }
+            if found == discovered {
+                table.labels[discovered] = rolled;
+                discovered += 1;
+            }
+
+            table.rolls[orientation][direction] = found as u8;
+            direction += 1;
+        }
+
+        orientation += 1;
+    }
+
+    table
+}
+
+const fn same_labels(a: &[u8; 6], b: &[u8; 6]) -> bool {
+    let mut side = 0;
+    while side < 6 {
+        if a[side] != b[side] {
+            return false;
+        }
+        side += 1;
+    }
+
+    true
+}
+
 /// Holds the value on each side of a 6-sided dice.
+/// The dice is split into its orientation (one of the 24 ways a cube can sit), and the values
+/// assigned to each of its physical faces. Rolling the dice only changes its orientation, which
+/// is a simple table lookup, while the face values stay attached to their face.
 /// The values are optional, because we don't always know the value
 /// on any side of the dice.
-#[derive(Clone, Debug, Default, PartialEq)]
-pub(crate) struct Dice {
-    top: Option<i16>,
-    bottom: Option<i16>,
-    left: Option<i16>,
-    right: Option<i16>,
-    front: Option<i16>,
-    back: Option<i16>,
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub struct Dice {
+    /// Index in the orientation table.
+    orientation: u8,
+    /// Values of the dice, indexed by face label rather than by side.
+    faces: [Option<i16>; 6],
 }
 
 impl Dice {
-    /// Mutates the top value of the dice, and returns it.
-    pub fn set_top(mut self, top: i16) -> Self {
-        self.top = Some(top);
+    /// Creates a dice holding the given values on its top, bottom, left, right, front and back
+    /// sides, in that order.
+    pub fn from_sides(sides: [Option<i16>; 6]) -> Self {
+        Self {
+            orientation: 0,
+            faces: sides,
+        }
+    }
+
+    /// Returns the mirror image of the dice across the diagonal going through the start and end
+    /// cells of the board, which swaps the sides facing the top and the right of the board, and
+    /// the sides facing the bottom and the left of the board.
+    pub fn mirror(&self) -> Self {
+        Self::from_sides([
+            self.get_face(Face::Top),
+            self.get_face(Face::Bottom),
+            self.get_face(Face::Back),
+            self.get_face(Face::Front),
+            self.get_face(Face::Right),
+            self.get_face(Face::Left),
+        ])
+    }
+
+    /// Mutates the value on the given side of the dice, and returns it.
+    pub fn set_face(mut self, face: Face, value: i16) -> Self {
+        let label = self.label_on(face as usize);
+        self.faces[label] = Some(value);
 
         self
     }
 
     /// Creates a new dice, based on a rotation in the given direction of the current dice.
     pub fn roll_in(&self, direction: Direction) -> Self {
-        match direction {
-            Direction::UP => self.roll_up(),
-            Direction::RIGHT => self.roll_right(),
-            Direction::DOWN => self.roll_down(),
-            Direction::LEFT => self.roll_left(),
+        Self {
+            orientation: ORIENTATIONS.rolls[self.orientation as usize][direction as usize],
+            faces: self.faces,
         }
     }
 
+    #[cfg(test)]
     pub fn roll_up(&self) -> Self {
-        Self {
-            top: self.back,
-            bottom: self.front,
-            left: self.left,
-            right: self.right,
-            front: self.top,
-            back: self.bottom,
-        }
+        self.roll_in(Direction::UP)
     }
 
+    #[cfg(test)]
     pub fn roll_down(&self) -> Self {
-        Self {
-            top: self.front,
-            bottom: self.back,
-            left: self.left,
-            right: self.right,
-            front: self.bottom,
-            back: self.top,
-        }
+        self.roll_in(Direction::DOWN)
     }
 
+    #[cfg(test)]
     pub fn roll_left(&self) -> Self {
-        Self {
-            top: self.right,
-            bottom: self.left,
-            left: self.top,
-            right: self.bottom,
-            front: self.front,
-            back: self.back,
-        }
+        self.roll_in(Direction::LEFT)
     }
 
+    #[cfg(test)]
     pub fn roll_right(&self) -> Self {
-        Self {
-            top: self.left,
-            bottom: self.right,
-            left: self.bottom,
-            right: self.top,
-            front: self.front,
-            back: self.back,
-        }
+        self.roll_in(Direction::RIGHT)
+    }
+
+    /// Returns the value on the given side of the dice, if any.
+    pub fn get_face(&self, face: Face) -> Option<i16> {
+        self.value_on(face as usize)
+    }
+
+    /// Lists the sides of the dice which values are known, with their values.
+    pub fn known_faces(&self) -> impl Iterator<Item = (Face, i16)> + '_ {
+        Face::iter().filter_map(|face| Some((face, self.get_face(face)?)))
+    }
+
+    /// Returns how many sides of the dice have an unknown value.
+    pub fn unknown_face_count(&self) -> usize {
+        self.faces.iter().filter(|value| value.is_none()).count()
     }
 
-    /// Returns the value on top of the dice, if any.
-    pub fn get_top(&self) -> Option<i16> {
-        self.top
+    /// Returns whether the values on all the sides of the dice are known.
+    pub fn is_fully_known(&self) -> bool {
+        self.unknown_face_count() == 0
+    }
+
+    fn label_on(&self, side: usize) -> usize {
+        ORIENTATIONS.labels[self.orientation as usize][side] as usize
+    }
+
+    fn value_on(&self, side: usize) -> Option<i16> {
+        self.faces[self.label_on(side)]
+    }
+}
+
+impl fmt::Debug for Dice {
+    // Describes the dice by what is visible on each of its sides, which is what readers care about,
+    // rather than by its internal orientation index.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dice")
+            .field("top", &self.value_on(TOP))
+            .field("bottom", &self.value_on(BOTTOM))
+            .field("left", &self.value_on(LEFT))
+            .field("right", &self.value_on(RIGHT))
+            .field("front", &self.value_on(FRONT))
+            .field("back", &self.value_on(BACK))
+            .finish()
+    }
+}
+
+impl fmt::Display for Dice {
+    // Lists the value on each side of the dice, marking unknown values with `?`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for face in Face::iter() {
+            if face != Face::Top {
+                write!(f, ", ")?;
+            }
+            match self.get_face(face) {
+                Some(value) => write!(f, "{}={}", face.name(), value)?,
+                None => write!(f, "{}=?", face.name())?,
+            }
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use strum::IntoEnumIterator;
 
     fn create_default_dice() -> Dice {
+        // The identity orientation holds face label `i` on side `i`.
         Dice {
-            top: Some(1),
-            bottom: Some(3),
-            left: Some(4),
-            right: Some(5),
-            front: Some(0),
-            back: Some(2),
+            orientation: 0,
+            faces: [Some(1), Some(3), Some(4), Some(5), Some(0), Some(2)],
         }
     }
 
+    /// Lists the values on each side of the dice: top, bottom, left, right, front, back.
+    fn sides(dice: &Dice) -> [Option<i16>; 6] {
+        [TOP, BOTTOM, LEFT, RIGHT, FRONT, BACK].map(|side| dice.value_on(side))
+    }
+
     #[test]
     fn roll_in_works() {
         let dice = create_default_dice();
@@ -109,15 +299,8 @@ mod tests {
     fn roll_left_works() {
         let dice = create_default_dice();
         assert_eq!(
-            dice.roll_left(),
-            Dice {
-                top: Some(5),
-                bottom: Some(4),
-                left: Some(1),
-                right: Some(3),
-                front: Some(0),
-                back: Some(2),
-            }
+            sides(&dice.roll_left()),
+            [Some(5), Some(4), Some(1), Some(3), Some(0), Some(2)]
         )
     }
 
@@ -125,15 +308,8 @@ mod tests {
     fn roll_right_works() {
         let dice = create_default_dice();
         assert_eq!(
-            dice.roll_right(),
-            Dice {
-                top: Some(4),
-                bottom: Some(5),
-                left: Some(3),
-                right: Some(1),
-                front: Some(0),
-                back: Some(2),
-            }
+            sides(&dice.roll_right()),
+            [Some(4), Some(5), Some(3), Some(1), Some(0), Some(2)]
         )
     }
 
@@ -141,15 +317,8 @@ mod tests {
     fn roll_up_works() {
         let dice = create_default_dice();
         assert_eq!(
-            dice.roll_up(),
-            Dice {
-                top: Some(2),
-                bottom: Some(0),
-                left: Some(4),
-                right: Some(5),
-                front: Some(1),
-                back: Some(3),
-            }
+            sides(&dice.roll_up()),
+            [Some(2), Some(0), Some(4), Some(5), Some(1), Some(3)]
         )
     }
 
@@ -157,15 +326,8 @@ mod tests {
     fn roll_down_works() {
         let dice = create_default_dice();
         assert_eq!(
-            dice.roll_down(),
-            Dice {
-                top: Some(0),
-                bottom: Some(2),
-                left: Some(4),
-                right: Some(5),
-                front: Some(3),
-                back: Some(1),
-            }
+            sides(&dice.roll_down()),
+            [Some(0), Some(2), Some(4), Some(5), Some(3), Some(1)]
         )
     }
 
@@ -173,21 +335,17 @@ mod tests {
     fn complex_roll_works() {
         let dice = create_default_dice();
         assert_eq!(
-            dice.roll_down()
-                .roll_left()
-                .roll_right()
-                .roll_right()
-                .roll_up()
-                .roll_up()
-                .roll_right(),
-            Dice {
-                top: Some(2),
-                bottom: Some(0),
-                left: Some(4),
-                right: Some(5),
-                front: Some(1),
-                back: Some(3),
-            }
+            sides(
+                &dice
+                    .roll_down()
+                    .roll_left()
+                    .roll_right()
+                    .roll_right()
+                    .roll_up()
+                    .roll_up()
+                    .roll_right()
+            ),
+            [Some(2), Some(0), Some(4), Some(5), Some(1), Some(3)]
         );
     }
 
@@ -285,16 +443,128 @@ mod tests {
 
     #[test]
     fn top_accessors_work() {
-        assert!(Dice::default().get_top().is_none());
+        assert!(Dice::default().get_face(Face::Top).is_none());
 
-        let mut dice = create_default_dice();
         let new_top = 42;
-        dice.top = Some(new_top);
+        let dice = create_default_dice().set_face(Face::Top, new_top);
 
-        let top = dice.get_top();
+        let top = dice.get_face(Face::Top);
         assert_eq!(top, Some(new_top));
 
         let new_top = 27;
-        assert_eq!(dice.set_top(new_top).get_top(), Some(new_top));
+        assert_eq!(
+            dice.set_face(Face::Top, new_top).get_face(Face::Top),
+            Some(new_top)
+        );
+    }
+
+    #[test]
+    fn from_sides_places_values_on_each_side() {
+        let values = [Some(1), None, Some(3), Some(-4), Some(5), None];
+        let dice = Dice::from_sides(values);
+        assert_eq!(sides(&dice), values);
+
+        // Rolling it behaves like any other dice.
+        assert_eq!(dice.roll_up().get_face(Face::Top), None);
+        assert_eq!(dice.roll_down().get_face(Face::Top), Some(5));
+    }
+
+    #[test]
+    fn side_accessors_work() {
+        let dice = create_default_dice();
+        assert_eq!(
+            [
+                dice.get_face(Face::Top),
+                dice.get_face(Face::Bottom),
+                dice.get_face(Face::Left),
+                dice.get_face(Face::Right),
+                dice.get_face(Face::Front),
+                dice.get_face(Face::Back)
+            ],
+            sides(&dice)
+        );
+
+        let dice = dice.roll_down();
+        assert_eq!(dice.get_face(Face::Right), Some(5));
+        assert_eq!(dice.get_face(Face::Back), Some(1));
+    }
+
+    #[test]
+    fn orientation_table_is_consistent() {
+        // All 24 orientations are distinct arrangements of the six face labels.
+        for (i, labels) in ORIENTATIONS.labels.iter().enumerate() {
+            let mut sorted = *labels;
+            sorted.sort();
+            assert_eq!(sorted, [0, 1, 2, 3, 4, 5]);
+            assert!(ORIENTATIONS.labels[..i].iter().all(|other| other != labels));
+        }
+
+        // Opposite rolls undo each other from every orientation.
+        for orientation in 0..ORIENTATION_COUNT {
+            let dice = Dice {
+                orientation: orientation as u8,
+                faces: create_default_dice().faces,
+            };
+            assert_eq!(dice, dice.roll_up().roll_down());
+            assert_eq!(dice, dice.roll_left().roll_right());
+        }
+    }
+
+    #[test]
+    fn set_face_only_sets_the_given_face() {
+        let dice = Dice::default()
+            .roll_right()
+            .set_face(Face::Top, 6)
+            .roll_left();
+        assert_eq!(sides(&dice), [None, None, Some(6), None, None, None]);
+
+        for face in Face::iter() {
+            let dice = Dice::default().roll_up().set_face(face, 6);
+            for other_face in Face::iter() {
+                let expected = (other_face == face).then_some(6);
+                assert_eq!(dice.get_face(other_face), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_commutes_with_rolls() {
+        let dice = Dice::from_sides([Some(1), Some(2), Some(3), Some(4), Some(5), Some(6)]);
+        assert_eq!(sides(&dice.mirror().mirror()), sides(&dice));
+        for direction in Direction::iter() {
+            assert_eq!(
+                sides(&dice.roll_in(direction).mirror()),
+                sides(&dice.mirror().roll_in(direction.mirror()))
+            );
+        }
+    }
+
+    #[test]
+    fn introspection_lists_known_faces() {
+        let dice = Dice::from_sides([Some(1), None, Some(3), Some(-4), Some(5), None]).roll_up();
+        assert_eq!(
+            dice.known_faces().collect::<Vec<_>>(),
+            [
+                (Face::Bottom, 5),
+                (Face::Left, 3),
+                (Face::Right, -4),
+                (Face::Front, 1)
+            ]
+        );
+        assert_eq!(dice.unknown_face_count(), 2);
+        assert!(!dice.is_fully_known());
+
+        assert_eq!(Dice::default().known_faces().count(), 0);
+        assert_eq!(Dice::default().unknown_face_count(), 6);
+        assert!(create_default_dice().is_fully_known());
+    }
+
+    #[test]
+    fn display_marks_unknown_values() {
+        let dice = Dice::from_sides([Some(1), None, Some(3), Some(-4), Some(5), None]);
+        assert_eq!(
+            dice.to_string(),
+            "top=1, bottom=?, left=3, right=-4, front=5, back=?"
+        );
     }
 }
@@ -1,10 +1,161 @@
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 /// Enumerates the orthogonal movements a dice can do on the board.
-#[derive(Clone, Copy, Debug, EnumIter)]
-pub(crate) enum Direction {
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Direction {
     UP,
     RIGHT,
     DOWN,
     LEFT,
 }
+
+impl Direction {
+    /// Returns the direction undoing a movement in this direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::UP => Direction::DOWN,
+            Direction::RIGHT => Direction::LEFT,
+            Direction::DOWN => Direction::UP,
+            Direction::LEFT => Direction::RIGHT,
+        }
+    }
+
+    /// Returns the direction a movement in this direction becomes when the board is mirrored
+    /// across the diagonal going through its start and end cells.
+    pub fn mirror(self) -> Self {
+        match self {
+            Direction::UP => Direction::RIGHT,
+            Direction::RIGHT => Direction::UP,
+            Direction::DOWN => Direction::LEFT,
+            Direction::LEFT => Direction::DOWN,
+        }
+    }
+
+    /// Returns the letter representing this direction in a move string, e.g. `U` for `UP`.
+    pub fn letter(self) -> char {
+        match self {
+            Direction::UP => 'U',
+            Direction::RIGHT => 'R',
+            Direction::DOWN => 'D',
+            Direction::LEFT => 'L',
+        }
+    }
+
+    /// Returns the direction represented by a letter of a move string, if any.
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Self::iter().find(|direction| direction.letter() == letter)
+    }
+}
+
+/// Enumerates the movements of the dice relative to its heading, which is the direction of its
+/// last movement, as if seen from the dice.
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq)]
+pub enum RelativeMove {
+    Forward,
+    Right,
+    Back,
+    Left,
+}
+
+/// Heading of the dice before its first movement: toward the top of the board.
+pub const INITIAL_HEADING: Direction = Direction::UP;
+
+impl RelativeMove {
+    /// Returns the letter representing this movement in a relative move string, e.g. `F` for
+    /// `Forward`.
+    pub fn letter(self) -> char {
+        match self {
+            RelativeMove::Forward => 'F',
+            RelativeMove::Right => 'R',
+            RelativeMove::Back => 'B',
+            RelativeMove::Left => 'L',
+        }
+    }
+
+    /// Returns the movement represented by a letter of a relative move string, if any.
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Self::iter().find(|relative_move| relative_move.letter() == letter)
+    }
+
+    /// Returns the direction of this movement for a dice with the given heading.
+    pub fn direction_from(self, heading: Direction) -> Direction {
+        // Both enumerations go clockwise, so quarter turns add up.
+        let quarter_turns = (heading as usize + self as usize) % 4;
+        [
+            Direction::UP,
+            Direction::RIGHT,
+            Direction::DOWN,
+            Direction::LEFT,
+        ][quarter_turns]
+    }
+
+    /// Returns the movement of a dice with the given heading rolling in the given direction.
+    pub fn between(heading: Direction, direction: Direction) -> Self {
+        let quarter_turns = (direction as usize + 4 - heading as usize) % 4;
+        [Self::Forward, Self::Right, Self::Back, Self::Left][quarter_turns]
+    }
+}
+
+/// Converts a move string, e.g. `URRD`, into a relative move string, e.g. `FRFR`, where each
+/// movement is relative to the previous one, starting from the initial heading.
+pub fn to_relative_moves(moves: &str) -> Result<String, String> {
+    let mut heading = INITIAL_HEADING;
+    moves
+        .chars()
+        .map(|letter| {
+            let direction = Direction::from_letter(letter)
+                .ok_or_else(|| format!("`{}` is not an absolute move", letter))?;
+            let relative_move = RelativeMove::between(heading, direction);
+            heading = direction;
+            Ok(relative_move.letter())
+        })
+        .collect()
+}
+
+/// Converts a relative move string, e.g. `FRFR`, into a move string, e.g. `URRD`. This is the
+/// inverse of `to_relative_moves`.
+pub fn to_absolute_moves(relative_moves: &str) -> Result<String, String> {
+    let mut heading = INITIAL_HEADING;
+    relative_moves
+        .chars()
+        .map(|letter| {
+            let relative_move = RelativeMove::from_letter(letter)
+                .ok_or_else(|| format!("`{}` is not a relative move", letter))?;
+            heading = relative_move.direction_from(heading);
+            Ok(heading.letter())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_moves_follow_the_heading() {
+        assert_eq!(
+            RelativeMove::Right.direction_from(Direction::LEFT),
+            Direction::UP
+        );
+        assert_eq!(
+            RelativeMove::Back.direction_from(Direction::DOWN),
+            Direction::UP
+        );
+        assert_eq!(
+            RelativeMove::between(Direction::UP, Direction::LEFT),
+            RelativeMove::Left
+        );
+
+        assert_eq!(to_relative_moves("URRD").unwrap(), "FRFR");
+        assert_eq!(to_absolute_moves("FRFR").unwrap(), "URRD");
+        let moves = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+        assert_eq!(
+            to_absolute_moves(&to_relative_moves(moves).unwrap()).unwrap(),
+            moves
+        );
+        assert!(to_absolute_moves("FU").is_err());
+        assert!(to_relative_moves("UF").is_err());
+    }
+}
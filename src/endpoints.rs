@@ -0,0 +1,148 @@
+use crate::{
+    board::{Board, Notation, Position},
+    solver::{Solution, SolverBuilder},
+};
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// Solves the board for every pair of start and end cells, and writes the answers as a CSV matrix:
+/// one row per start cell, one column per end cell, and an empty field where there is no solution.
+/// Boards are solved by the solvers the builder configures for them, giving up on journeys longer
+/// than `max_depth` turns. Cells are written in the given notation.
+pub fn compute_endpoints_matrix(
+    builder: &SolverBuilder,
+    board: &Board,
+    max_depth: i16,
+    notation: Notation,
+) -> String {
+    let positions: Vec<Position> = board.positions().collect();
+    let builder = builder.clone().max_depth(max_depth);
+    // Start cells are handed out to a worker per available core, each solving its start cell for
+    // every end cell in turn.
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let next_start = AtomicUsize::new(0);
+    let mut rows: Vec<(usize, Vec<Option<i32>>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, positions.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut rows = Vec::new();
+                    loop {
+                        let index = next_start.fetch_add(1, Ordering::Relaxed);
+                        let Some(&start) = positions.get(index) else {
+                            return rows;
+                        };
+                        let answers = positions
+                            .iter()
+                            .map(|&end| solve_between(&builder, board, start, end))
+                            .collect();
+                        rows.push((index, answers));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("the solver panicked"))
+            .collect()
+    });
+    rows.sort_by_key(|(index, _)| *index);
+
+    let mut lines = vec![std::iter::once("start".to_string())
+        .chain(positions.iter().map(|&end| csv_field(notation.format(end))))
+        .collect::<Vec<_>>()
+        .join(",")];
+    for (&start, (_, answers)) in positions.iter().zip(rows) {
+        let fields = answers
+            .into_iter()
+            .map(|answer| answer.map_or(String::new(), |answer| answer.to_string()));
+        lines.push(
+            std::iter::once(csv_field(notation.format(start)))
+                .chain(fields)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the answer of the first solution of the board from the start cell to the end cell, if
+/// any.
+fn solve_between(
+    builder: &SolverBuilder,
+    board: &Board,
+    start: Position,
+    end: Position,
+) -> Option<i32> {
+    let board = board.clone().set_end(end)?;
+    match builder
+        .clone()
+        .board(board)
+        .build()?
+        .set_start(start)?
+        .solve()
+    {
+        Solution::Found(report) => Some(report.unvisited_sum),
+        Solution::NotFound(..) => None,
+    }
+}
+
+/// Quotes a CSV field holding commas, e.g. a position written as `(5, 0)`.
+fn csv_field(field: String) -> String {
+    if field.contains(',') {
+        format!("\"{}\"", field)
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    #[test]
+    fn endpoints_matrix_holds_the_puzzle_answer() {
+        let matrix = compute_endpoints_matrix(
+            &Solver::builder(),
+            &Board::new(),
+            36,
+            Board::new().chess_notation(),
+        );
+        let lines: Vec<&str> = matrix.lines().collect();
+
+        assert_eq!(lines.len(), 1 + 36);
+        assert!(lines[0].starts_with("start,a6,b6,"));
+        // The puzzle goes from a1, first on the last row, to f6, in the sixth column.
+        let a1: Vec<&str> = lines[1 + 30].split(',').collect();
+        assert_eq!(a1[0], "a1");
+        assert_eq!(a1[6], "1935");
+
+        // Pairs are solved under the rules of the builder, and within the depth limit.
+        let matrix = compute_endpoints_matrix(
+            &Solver::builder().avoided_cells(vec![(4, 1)]),
+            &Board::new(),
+            36,
+            Board::new().chess_notation(),
+        );
+        assert_eq!(
+            matrix.lines().nth(1 + 30).unwrap().split(',').nth(6),
+            Some("")
+        );
+        let matrix =
+            compute_endpoints_matrix(&Solver::builder(), &Board::new(), 31, Notation::RowCol);
+        assert!(!matrix.contains("1935"));
+    }
+
+    #[test]
+    fn csv_field_quotes_commas() {
+        assert_eq!(csv_field(Notation::RowCol.format((0, 1))), "\"(0, 1)\"");
+        assert_eq!(
+            csv_field(Board::new().chess_notation().format((0, 1))),
+            "b6"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::schema::versioned;
+
+use serde_json::{json, Value};
+use std::{
+    io::Write,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Writes structured events about the progress of the solver, one JSON object per line (NDJSON),
+/// so that external tools can analyze its behaviour without parsing human-readable logs.
+pub struct EventLog {
+    writer: Box<dyn Write + Send>,
+    start: Instant,
+}
+
+impl EventLog {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    /// Writes an event of the given kind. Each event holds the format version, the kind, a Unix
+    /// timestamp and the time elapsed since the log was created (both in milliseconds), on top of
+    /// the given fields.
+    pub fn emit(&mut self, kind: &str, fields: Value) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |timestamp| timestamp.as_millis());
+        let mut event = versioned(json!({
+            "event": kind,
+            "timestamp_ms": timestamp_ms,
+            "elapsed_ms": self.start.elapsed().as_millis(),
+        }));
+        if let (Value::Object(event), Value::Object(fields)) = (&mut event, fields) {
+            event.extend(fields);
+        }
+
+        // Events are best effort: failing to write them must not interrupt the search.
+        let _ = writeln!(self.writer, "{}", event).and_then(|_| self.writer.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    /// Writer keeping what was written in a shared buffer, so that tests can read it back.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn emit_writes_one_json_object_per_line() {
+        let buffer = SharedBuffer::default();
+        let mut events = EventLog::new(Box::new(buffer.clone()));
+        events.emit("start", json!({ "strategy": "Bfs" }));
+        events.emit("finish", json!({ "expanded": 42 }));
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(output.starts_with(r#"{"format_version":1,"event":"start","#));
+        assert_eq!(lines[0]["event"], "start");
+        assert_eq!(lines[0]["strategy"], "Bfs");
+        assert!(lines[0]["timestamp_ms"].as_u64().unwrap() > 0);
+        assert!(lines[0]["elapsed_ms"].is_u64());
+        assert_eq!(lines[1]["event"], "finish");
+        assert_eq!(lines[1]["expanded"], 42);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::turn::Turn;
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+};
+
+/// Enumerates the orders in which the solver can explore candidate journeys.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Strategy {
+    /// Breadth-first search, exploring journeys turn by turn. Guarantees the shortest solution.
+    #[default]
+    Bfs,
+    /// A* search, exploring first the journeys minimizing `turn + weight x distance to the end cell`.
+    /// With a weight of 1, this also guarantees the shortest solution. Larger weights trade that
+    /// guarantee for a faster search.
+    AStar { weight: f64 },
+    /// Greedy best-first search, always exploring first the journey closest to the end cell.
+    /// Fast, but the solution found might not be the shortest.
+    Greedy,
+}
+
+/// A step waiting in a prioritized frontier.
+pub(crate) struct Candidate {
+    priority: f64,
+    /// Insertion order, used to break ties in a FIFO manner.
+    order: usize,
+    step: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // `BinaryHeap` is a max-heap, so the ordering is reversed to pop the lowest priority first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Holds the last steps of the candidate journeys, in the order they should be explored.
+pub(crate) enum Frontier {
+    /// Plain FIFO list, for BFS.
+    Fifo(VecDeque<usize>),
+    /// Priority queue, for A* and greedy best-first search.
+    Prioritized {
+        strategy: Strategy,
+        candidates: BinaryHeap<Candidate>,
+        pushed: usize,
+    },
+}
+
+impl Frontier {
+    pub fn new(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Bfs => Self::Fifo(VecDeque::new()),
+            _ => Self::Prioritized {
+                strategy,
+                candidates: BinaryHeap::new(),
+                pushed: 0,
+            },
+        }
+    }
+
+    /// Adds a step to the frontier, given its turn and its distance to the end cell.
+    pub fn push(&mut self, step: usize, turn: Turn, distance_to_end: usize) {
+        match self {
+            Self::Fifo(steps) => steps.push_back(step),
+            Self::Prioritized {
+                strategy,
+                candidates,
+                pushed,
+            } => {
+                let priority = match strategy {
+                    Strategy::AStar { weight } => {
+                        f64::from(i16::from(turn)) + *weight * distance_to_end as f64
+                    }
+                    _ => distance_to_end as f64,
+                };
+                candidates.push(Candidate {
+                    priority,
+                    order: *pushed,
+                    step,
+                });
+                *pushed += 1;
+            }
+        }
+    }
+
+    /// Removes the next step to explore, if any.
+    pub fn pop(&mut self) -> Option<usize> {
+        match self {
+            Self::Fifo(steps) => steps.pop_front(),
+            Self::Prioritized { candidates, .. } => {
+                candidates.pop().map(|candidate| candidate.step)
+            }
+        }
+    }
+
+    /// Returns how many steps are waiting to be explored.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Fifo(steps) => steps.len(),
+            Self::Prioritized { candidates, .. } => candidates.len(),
+        }
+    }
+
+    /// Lists the steps waiting to be explored, in the order they were pushed, so that pushing them
+    /// again in that order into an empty frontier restores the order they are explored in.
+    pub fn steps_in_push_order(&self) -> Vec<usize> {
+        match self {
+            Self::Fifo(steps) => steps.iter().copied().collect(),
+            Self::Prioritized { candidates, .. } => {
+                let mut candidates: Vec<&Candidate> = candidates.iter().collect();
+                candidates.sort_by_key(|candidate| candidate.order);
+                candidates.iter().map(|candidate| candidate.step).collect()
+            }
+        }
+    }
+
+    /// Makes room for at least `additional` more steps.
+    #[cfg(test)]
+    pub fn reserve(&mut self, additional: usize) {
+        match self {
+            Self::Fifo(steps) => steps.reserve(additional),
+            Self::Prioritized { candidates, .. } => candidates.reserve(additional),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bfs_pops_in_insertion_order() {
+        let mut frontier = Frontier::new(Strategy::Bfs);
+        frontier.push(3, Turn::from(1), 10);
+        frontier.push(1, Turn::from(5), 0);
+        frontier.push(2, Turn::from(2), 4);
+
+        assert_eq!(frontier.pop(), Some(3));
+        assert_eq!(frontier.pop(), Some(1));
+        assert_eq!(frontier.pop(), Some(2));
+        assert_eq!(frontier.pop(), None);
+    }
+
+    #[test]
+    fn astar_pops_lowest_estimate_first() {
+        let mut frontier = Frontier::new(Strategy::AStar { weight: 1.0 });
+        frontier.push(0, Turn::from(1), 10);
+        frontier.push(1, Turn::from(5), 0);
+        frontier.push(2, Turn::from(2), 4);
+        frontier.push(3, Turn::from(4), 2);
+
+        // Estimates are 11, 5, 6 and 6: ties are broken by insertion order.
+        assert_eq!(frontier.pop(), Some(1));
+        assert_eq!(frontier.pop(), Some(2));
+        assert_eq!(frontier.pop(), Some(3));
+        assert_eq!(frontier.pop(), Some(0));
+        assert_eq!(frontier.pop(), None);
+    }
+
+    #[test]
+    fn weight_favours_distance_over_turns() {
+        let mut frontier = Frontier::new(Strategy::AStar { weight: 3.0 });
+        frontier.push(0, Turn::from(1), 3);
+        frontier.push(1, Turn::from(6), 1);
+
+        // Estimates are 10 and 9.
+        assert_eq!(frontier.pop(), Some(1));
+        assert_eq!(frontier.pop(), Some(0));
+    }
+
+    #[test]
+    fn steps_in_push_order_restore_the_exploration_order() {
+        let mut frontier = Frontier::new(Strategy::AStar { weight: 1.0 });
+        let pushes = [(0, 1, 10), (1, 5, 0), (2, 2, 4), (3, 4, 2)];
+        for (step, turn, distance) in pushes {
+            frontier.push(step, Turn::from(turn), distance);
+        }
+        assert_eq!(frontier.steps_in_push_order(), vec![0, 1, 2, 3]);
+
+        let mut restored = Frontier::new(Strategy::AStar { weight: 1.0 });
+        for step in frontier.steps_in_push_order() {
+            let (_, turn, distance) = pushes[step];
+            restored.push(step, Turn::from(turn), distance);
+        }
+        while let Some(step) = frontier.pop() {
+            assert_eq!(restored.pop(), Some(step));
+        }
+    }
+
+    #[test]
+    fn greedy_ignores_turns() {
+        let mut frontier = Frontier::new(Strategy::Greedy);
+        frontier.push(0, Turn::from(1), 3);
+        frontier.push(1, Turn::from(100), 2);
+
+        assert_eq!(frontier.pop(), Some(1));
+        assert_eq!(frontier.pop(), Some(0));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    board::Board,
+    dice::{Dice, Face},
+    direction::Direction,
+    solver::{Solution, Solver},
+    task::SolveTask,
+};
+
+use std::{
+    collections::HashSet,
+    ops::RangeInclusive,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Journeys of random boards are not explored past this many turns, so that each solve is short.
+const FUZZ_MAX_DEPTH: i16 = 12;
+/// Time after which a solve is deemed to never terminate.
+const FUZZ_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Small and fast pseudo-random generator (SplitMix64), so that a failing board can be generated
+/// again from its seed, on any platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the given range, both ends included.
+    fn in_range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        // The span of the full range of `i64` wraps around to 0.
+        match (range.end().wrapping_sub(*range.start()) as u64).wrapping_add(1) {
+            0 => self.next() as i64,
+            span => range.start().wrapping_add((self.next() % span) as i64),
+        }
+    }
+}
+
+/// Counts the outcomes of the boards fuzzed so far.
+#[derive(Debug, Default)]
+pub struct FuzzSummary {
+    /// Boards rejected when parsed, e.g. because a value does not fit on a board.
+    pub rejected: usize,
+    /// Boards for which a solution was found, and verified.
+    pub solved: usize,
+    /// Boards for which no solution was found.
+    pub unsolved: usize,
+}
+
+/// Describes a board on which the solver misbehaved, with the seed generating it again.
+#[derive(Debug)]
+pub struct FuzzFailure {
+    pub seed: u64,
+    pub size: usize,
+    pub reason: String,
+}
+
+/// Generates a random square board for each seed from the given one, with a size in the given range,
+/// and solves it with tight limits. Fails on the first board where the solver panics, does not
+/// terminate in time, or reports a solution which does not verify.
+pub fn fuzz_solve(
+    iterations: u64,
+    sizes: RangeInclusive<usize>,
+    seed: u64,
+) -> Result<FuzzSummary, FuzzFailure> {
+    let mut summary = FuzzSummary::default();
+    for iteration in 0..iterations {
+        let seed = seed.wrapping_add(iteration);
+        let mut rng = SplitMix64(seed);
+        let size = rng.in_range(*sizes.start() as i64..=*sizes.end() as i64) as usize;
+        let fail = |reason: String| FuzzFailure { seed, size, reason };
+
+        let Ok(board) = Board::try_from(random_rows(&mut rng, size)) else {
+            summary.rejected += 1;
+            continue;
+        };
+        match solve_before_deadline(board.clone()).map_err(fail)? {
+            Solution::Found(report) => {
+                verify_solution(&board, &report.moves_as_letters(), report.unvisited_sum)
+                    .map_err(fail)?;
+                summary.solved += 1;
+            }
+            Solution::NotFound(..) => summary.unsolved += 1,
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Generates the rows of a random board. Most values are small, and some boards are mostly zeros,
+/// so that some boards are solvable, while a few values are extreme or do not even fit on a board.
+fn random_rows(rng: &mut SplitMix64, size: usize) -> Vec<Vec<i64>> {
+    let zeros_percentage = if rng.in_range(0..=3) == 0 { 90 } else { 30 };
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| match rng.in_range(0..=99) {
+                    0 if rng.in_range(0..=99) == 0 => rng.in_range(i64::MIN..=i64::MAX),
+                    0 => rng.in_range(i16::MIN as i64..=i16::MAX as i64),
+                    percentile if percentile <= zeros_percentage => 0,
+                    _ => rng.in_range(-40..=40),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn solve_before_deadline(board: Board) -> Result<Solution, String> {
+    let solver = Solver::builder()
+        .board(board)
+        .max_depth(FUZZ_MAX_DEPTH)
+        .build()
+        .ok_or("the solver cannot be built")?;
+    let task = SolveTask::spawn(solver);
+    let start = Instant::now();
+    while !task.is_finished() {
+        if start.elapsed() > FUZZ_DEADLINE {
+            return Err(format!(
+                "the solve did not terminate within {:?}",
+                FUZZ_DEADLINE
+            ));
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    task.join().map_err(|_| "the solver panicked".to_string())
+}
+
+/// Replays the moves on the board with the rules of the puzzle, independently of the solver, to
+/// check that they form a journey from the start cell to the end cell, and that the answer is the
+/// sum of the unvisited cells.
+///
+/// ```
+/// use die_agony::{fuzz::verify_solution, Board};
+///
+/// let board = Board::new();
+/// let moves = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+/// assert!(verify_solution(&board, moves, 1935).is_ok());
+/// assert!(verify_solution(&board, &moves[..31], 1935).is_err());
+/// ```
+pub fn verify_solution(board: &Board, moves: &str, answer: i32) -> Result<(), String> {
+    let mut cell = board.start_cell();
+    let mut visited = HashSet::from([*cell.get_position()]);
+    let mut dice = Dice::default();
+    let mut score = cell.get_value() as i64;
+    for (turn, letter) in moves.chars().enumerate() {
+        let turn = turn as i64 + 1;
+        let direction =
+            Direction::from_letter(letter).ok_or_else(|| format!("`{}` is not a move", letter))?;
+        cell = board
+            .move_in(&cell, direction)
+            .ok_or_else(|| format!("turn {} leaves the board", turn))?;
+        visited.insert(*cell.get_position());
+        dice = dice.roll_in(direction);
+
+        let target = cell.get_value() as i64;
+        let top = match dice.get_face(Face::Top) {
+            Some(top) => top as i64,
+            None if (target - score) % turn == 0 => {
+                let top = i16::try_from((target - score) / turn)
+                    .map_err(|_| format!("turn {} needs a top out of range", turn))?;
+                dice = dice.set_face(Face::Top, top);
+                top as i64
+            }
+            None => return Err(format!("no top lands turn {} on its cell", turn)),
+        };
+        score += turn * top;
+        if score != target {
+            return Err(format!(
+                "turn {} scores {}, but lands on {}",
+                turn, score, target
+            ));
+        }
+    }
+    if !cell.is_end_cell() {
+        return Err("the journey does not end on the end cell".to_string());
+    }
+
+    let unvisited: i64 = board
+        .cells()
+        .filter(|cell| !visited.contains(cell.get_position()))
+        .map(|cell| cell.get_value() as i64)
+        .sum();
+    if unvisited != answer as i64 {
+        return Err(format!(
+            "the answer is {}, but the unvisited cells sum to {}",
+            answer, unvisited
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_solution_accepts_the_puzzle_solution_only() {
+        let board = Board::new();
+        let moves = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+        assert!(verify_solution(&board, moves, 1935).is_ok());
+        assert!(verify_solution(&board, moves, 1934).is_err());
+        assert!(verify_solution(&board, &moves[..31], 1935).is_err());
+        assert!(verify_solution(&board, "URRDLUUUUURDLLDRRDRDDRRUULLUURRR", 1935).is_err());
+        assert!(verify_solution(&board, "L", 1935).is_err());
+    }
+
+    #[test]
+    fn fuzzing_random_boards_finds_no_failure() {
+        let summary = fuzz_solve(40, 6..=6, 42).unwrap();
+        assert_eq!(summary.rejected, 0);
+        assert_eq!(summary.solved + summary.unsolved, 40);
+        assert!(summary.solved > 0);
+
+        // Boards of other sizes are solved just the same.
+        let summary = fuzz_solve(10, 4..=5, 42).unwrap();
+        assert_eq!(summary.rejected, 0);
+        assert_eq!(summary.solved + summary.unsolved, 10);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    board::{Board, Cell, Position},
+    direction::Direction,
+    rules::Rules,
+    turn::Turn,
+};
+
+use std::{error::Error, fmt};
+use strum::IntoEnumIterator;
+
+/// How many turns ahead invariants are derived, when journeys have no maximum length.
+const DEFAULT_HORIZON: i16 = 100;
+
+/// Enumerates the reasons why a puzzle is found unsolvable before searching.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unsolvable {
+    /// No roll can land on the end cell within the given number of turns, whichever the journey.
+    NoLandingOnEndCell { turns: i16 },
+    /// Rolls can land on the end cell, but every journey from the start cell gets stuck before
+    /// getting there.
+    DeadEnd,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoLandingOnEndCell { turns } => {
+                write!(f, "no roll can land on the end cell within {} turns", turns)
+            }
+            Self::DeadEnd => write!(
+                f,
+                "every journey from the start cell gets stuck before reaching the end cell"
+            ),
+        }
+    }
+}
+
+impl Error for Unsolvable {}
+
+/// Invariants derived from the board and the rules before searching, which hold for any journey
+/// regardless of the dice. They are used to prune journeys which can provably not reach the end
+/// cell, whatever the values on the dice.
+///
+/// Rolling from a cell onto another on a given turn is only possible if some top lands there, e.g.
+/// with integers, if the difference of their values is divisible by the multiplier of that turn.
+/// Working backwards from the last turn, a cell can lead to the end cell on a given turn if it is
+/// the end cell, or if such a roll is possible onto a neighbour which can lead to the end cell on
+/// the next turn. Ignoring the dice makes this a relaxation: a journey which cannot reach the end
+/// cell under it cannot reach it with any dice either.
+pub(crate) struct Invariants {
+    /// Last turn covered. Beyond it, every cell is assumed to possibly lead to the end cell.
+    horizon: i16,
+    /// Whether the dice can roll from each cell in each direction, turn by turn, as far as
+    /// divisibility is concerned. This saves the arithmetic of most invalid movements while
+    /// searching.
+    can_roll: Vec<bool>,
+    /// Whether each cell can lead to the end cell, turn by turn.
+    can_finish: Vec<bool>,
+    cell_count: usize,
+}
+
+impl Invariants {
+    /// Derives the invariants of the board under the given rules, where the dice can never land on
+    /// the avoided cells. If journeys cannot last more than `max_depth` turns, the invariants are
+    /// exact up to that turn.
+    pub fn derive(
+        board: &Board,
+        rules: &Rules,
+        max_depth: Option<Turn>,
+        avoided_cells: &[Position],
+    ) -> Self {
+        let horizon = max_depth.map_or(DEFAULT_HORIZON, i16::from).max(0);
+        let cells: Vec<Cell> = board.cells().collect();
+        let cell_count = cells.len();
+        let mut can_roll = vec![false; horizon as usize * cell_count * Direction::iter().len()];
+        for turn in 0..horizon {
+            let Some(multiplier) = rules.multiplier.at(Turn::from(turn + 1)) else {
+                continue;
+            };
+            for cell in &cells {
+                // Only the start cell is relevant before the first turn.
+                let score = if turn == 0 {
+                    rules.initial_score(cell.get_value())
+                } else {
+                    cell.get_value()
+                };
+                for direction in Direction::iter() {
+                    can_roll[roll_index(cell_count, board, turn, cell, direction)] =
+                        board.move_in(cell, direction).is_some_and(|neighbour| {
+                            !avoided_cells.contains(neighbour.get_position())
+                                && rules.can_land(score, multiplier, neighbour.get_value())
+                        });
+                }
+            }
+        }
+
+        let mut can_finish = vec![true; (horizon as usize + 1) * cell_count];
+        if max_depth.is_some() {
+            for cell in &cells {
+                can_finish[horizon as usize * cell_count + board.index_of(cell)] =
+                    cell.is_end_cell();
+            }
+        }
+        for turn in (0..horizon).rev() {
+            for cell in &cells {
+                let possible = cell.is_end_cell()
+                    || Direction::iter().any(|direction| {
+                        can_roll[roll_index(cell_count, board, turn, cell, direction)]
+                            && board.move_in(cell, direction).is_some_and(|neighbour| {
+                                can_finish
+                                    [(turn as usize + 1) * cell_count + board.index_of(&neighbour)]
+                            })
+                    });
+                can_finish[turn as usize * cell_count + board.index_of(cell)] = possible;
+            }
+        }
+
+        Self {
+            horizon,
+            can_roll,
+            can_finish,
+            cell_count,
+        }
+    }
+
+    /// Returns whether a dice on the given cell after the given turn can roll in the given
+    /// direction on the next turn, as far as the invariants can tell.
+    pub fn can_roll(&self, board: &Board, turn: Turn, cell: &Cell, direction: Direction) -> bool {
+        let turn = i16::from(turn);
+        turn >= self.horizon
+            || self.can_roll[roll_index(self.cell_count, board, turn, cell, direction)]
+    }
+
+    /// Returns whether a journey on the given cell after the given turn can still reach the end
+    /// cell, as far as the invariants can tell.
+    pub fn can_finish(&self, board: &Board, turn: Turn, cell: &Cell) -> bool {
+        let turn = i16::from(turn);
+        turn > self.horizon
+            || self.can_finish[turn as usize * self.cell_count + board.index_of(cell)]
+    }
+
+    /// Checks that journeys starting on the given cell can reach the end cell, as far as the
+    /// invariants can tell, explaining why they cannot otherwise.
+    pub fn check_solvable(&self, board: &Board, start_cell: &Cell) -> Result<(), Unsolvable> {
+        if self.can_finish(board, Turn::START, start_cell) {
+            return Ok(());
+        }
+
+        let lands_on_end_cell = board
+            .positions()
+            .filter_map(|position| board.get_cell_at(position))
+            .any(|cell| {
+                Direction::iter().any(|direction| {
+                    board
+                        .move_in(&cell, direction)
+                        .is_some_and(|neighbour| neighbour.is_end_cell())
+                        && (0..self.horizon)
+                            .any(|turn| self.can_roll(board, Turn::from(turn), &cell, direction))
+                })
+            });
+        if lands_on_end_cell {
+            Err(Unsolvable::DeadEnd)
+        } else {
+            Err(Unsolvable::NoLandingOnEndCell {
+                turns: self.horizon,
+            })
+        }
+    }
+}
+
+/// Returns the index of a movement in the table of movements.
+fn roll_index(
+    cell_count: usize,
+    board: &Board,
+    turn: i16,
+    cell: &Cell,
+    direction: Direction,
+) -> usize {
+    (turn as usize * cell_count + board.index_of(cell)) * Direction::iter().len()
+        + direction as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Multiplier;
+
+    #[test]
+    fn end_cell_can_always_finish() {
+        let board = Board::new();
+        let invariants = Invariants::derive(&board, &Rules::default(), None, &[]);
+        let end_cell = board.get_cell_at((0, 5)).unwrap();
+        for turn in 0..=DEFAULT_HORIZON {
+            assert!(invariants.can_finish(&board, Turn::from(turn), &end_cell));
+        }
+    }
+
+    #[test]
+    fn cells_too_far_cannot_finish_within_the_maximum_depth() {
+        let board = Board::from_rows([[0; 6]; 6]);
+        let invariants = Invariants::derive(&board, &Rules::default(), Some(Turn::from(10)), &[]);
+
+        // The start cell is 10 moves away from the end cell.
+        let start_cell = board.start_cell();
+        assert!(invariants.can_finish(&board, Turn::from(0), &start_cell));
+        assert!(!invariants.can_finish(&board, Turn::from(1), &start_cell));
+    }
+
+    #[test]
+    fn indivisible_differences_cannot_finish() {
+        // On turn 2, the multiplier is 2, and odd differences cannot be bridged.
+        let mut rows = [[0; 6]; 6];
+        rows[0][4] = 1;
+        rows[1][5] = 1;
+        let board = Board::from_rows(rows);
+        let invariants = Invariants::derive(&board, &Rules::default(), None, &[]);
+
+        // Every neighbour of the cell holding 1 holds 0, so it cannot be left on turn 2.
+        let cell = board.get_cell_at((0, 4)).unwrap();
+        assert!(!invariants.can_finish(&board, Turn::from(1), &cell));
+        // On turn 1 however, the multiplier divides everything.
+        assert!(invariants.can_finish(&board, Turn::from(0), &cell));
+    }
+
+    #[test]
+    fn indivisible_differences_cannot_roll() {
+        let mut rows = [[0; 6]; 6];
+        rows[0][4] = 1;
+        let board = Board::from_rows(rows);
+        let invariants = Invariants::derive(&board, &Rules::default(), None, &[]);
+
+        let cell = board.get_cell_at((0, 4)).unwrap();
+        assert!(invariants.can_roll(&board, Turn::from(0), &cell, Direction::RIGHT));
+        assert!(!invariants.can_roll(&board, Turn::from(1), &cell, Direction::RIGHT));
+        assert!(!invariants.can_roll(&board, Turn::from(1), &cell, Direction::UP));
+        // Any difference is divisible by 1, so only the edges of the board block the first turn.
+        let start_cell = board.start_cell();
+        assert!(invariants.can_roll(&board, Turn::from(0), &start_cell, Direction::UP));
+        assert!(!invariants.can_roll(&board, Turn::from(0), &start_cell, Direction::DOWN));
+    }
+
+    #[test]
+    fn check_solvable_explains_why_boards_are_unsolvable() {
+        let board = Board::new();
+        let invariants = Invariants::derive(&board, &Rules::default(), None, &[]);
+        assert_eq!(
+            invariants.check_solvable(&board, &board.start_cell()),
+            Ok(())
+        );
+
+        // The end cell is 10 moves away from the start cell.
+        let invariants = Invariants::derive(&board, &Rules::default(), Some(Turn::from(9)), &[]);
+        assert_eq!(
+            invariants.check_solvable(&board, &board.start_cell()),
+            Err(Unsolvable::DeadEnd)
+        );
+
+        // Both neighbours of the end cell differ from it by 1, which only the first multiplier
+        // divides, but they cannot be reached on the first turn.
+        let mut rows = [[0; 6]; 6];
+        rows[0][4] = 1;
+        rows[1][5] = 1;
+        let board = Board::from_rows(rows);
+        let invariants = Invariants::derive(&board, &Rules::default(), Some(Turn::from(20)), &[]);
+        assert_eq!(
+            invariants.check_solvable(&board, &board.start_cell()),
+            Err(Unsolvable::DeadEnd)
+        );
+
+        // Counting down from 20, the first 10 multipliers are all greater than 1.
+        let rules = Rules {
+            multiplier: Multiplier::Descending { from: 20 },
+            ..Rules::default()
+        };
+        let invariants = Invariants::derive(&board, &rules, Some(Turn::from(10)), &[]);
+        assert_eq!(
+            invariants.check_solvable(&board, &board.start_cell()),
+            Err(Unsolvable::NoLandingOnEndCell { turns: 10 })
+        );
+    }
+
+    #[test]
+    fn avoided_cells_cannot_be_landed_on() {
+        let board = Board::new();
+        let invariants = Invariants::derive(&board, &Rules::default(), None, &[(4, 0), (5, 1)]);
+
+        let start_cell = board.start_cell();
+        assert!(!invariants.can_roll(&board, Turn::from(0), &start_cell, Direction::UP));
+        assert_eq!(
+            invariants.check_solvable(&board, &start_cell),
+            Err(Unsolvable::DeadEnd)
+        );
+    }
+
+    #[test]
+    fn multiplier_running_out_cannot_finish() {
+        let board = Board::new();
+        let rules = Rules {
+            multiplier: Multiplier::Descending { from: 3 },
+            ..Rules::default()
+        };
+        let invariants = Invariants::derive(&board, &rules, None, &[]);
+        assert!(!invariants.can_finish(&board, Turn::from(3), &board.start_cell()));
+    }
+}
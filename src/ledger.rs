@@ -0,0 +1,92 @@
+use crate::frontier::Strategy;
+
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Header of the ledger, written when it is created.
+const LEDGER_HEADER: &str = "timestamp,version,board_hash,strategy,time_ms,nodes,answer";
+
+/// Metrics of a single solve, appended as a row to a CSV ledger so that the performance of the
+/// solver can be tracked across runs, machines and versions.
+pub struct LedgerEntry {
+    pub board_hash: String,
+    pub strategy: Strategy,
+    pub elapsed: Duration,
+    /// Steps expanded by the search.
+    pub nodes: u64,
+    /// Sum of the unvisited cells, if a solution was found.
+    pub answer: Option<i32>,
+}
+
+impl LedgerEntry {
+    /// Appends the entry to the ledger at the given path, creating it with a header row if needed.
+    pub fn append_to(&self, path: &Path) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", LEDGER_HEADER)?;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        writeln!(file, "{}", self.to_row(timestamp))
+    }
+
+    /// Formats the entry as a CSV row, recorded at the given Unix timestamp.
+    fn to_row(&self, timestamp: u64) -> String {
+        let strategy = match self.strategy {
+            Strategy::Bfs => "bfs".to_string(),
+            Strategy::AStar { weight } => format!("astar:{}", weight),
+            Strategy::Greedy => "greedy".to_string(),
+        };
+        format!(
+            "{},{},{},{},{:.3},{},{}",
+            timestamp,
+            env!("CARGO_PKG_VERSION"),
+            self.board_hash,
+            strategy,
+            self.elapsed.as_secs_f64() * 1000.0,
+            self.nodes,
+            self.answer
+                .map_or(String::new(), |answer| answer.to_string())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    #[test]
+    fn ledger_appends_rows_after_a_single_header() {
+        let path =
+            std::env::temp_dir().join(format!("die-agony-ledger-{}.csv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut entry = LedgerEntry {
+            board_hash: "250b5ab0c9c2e39e".to_string(),
+            strategy: Strategy::AStar { weight: 2.0 },
+            elapsed: Duration::from_micros(1500),
+            nodes: 35,
+            answer: Some(1935),
+        };
+        entry.append_to(&path).unwrap();
+        entry.strategy = Strategy::Bfs;
+        entry.answer = None;
+        entry.append_to(&path).unwrap();
+
+        let ledger = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = ledger.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], LEDGER_HEADER);
+        assert!(lines[1].ends_with(",250b5ab0c9c2e39e,astar:2,1.500,35,1935"));
+        assert!(lines[2].ends_with(",250b5ab0c9c2e39e,bfs,1.500,35,"));
+    }
+}
@@ -0,0 +1,55 @@
+//! Solves the _Die Agony_ puzzle, described in
+//! <https://www.janestreet.com/puzzles/die-agony-index/>, and variants of it.
+//!
+//! A [`Board`] holds the values of the cells the dice rolls on, and a [`Dice`] the values of its
+//! faces, as far as they are known. A [`Solver`] searches the journeys of the dice from the start
+//! cell of a board, moving in a [`Direction`] on each turn, for one reaching the end cell, and
+//! reports what it found as a [`Solution`]. A solution found comes with a [`SolutionReport`],
+//! listing its turns, which explains it when displayed.
+//!
+//! ```
+//! use die_agony::{Solution, Solver};
+//!
+//! let Solution::Found(report) = Solver::new().solve() else {
+//!     panic!("the puzzle has a solution");
+//! };
+//! assert_eq!(report.unvisited_sum, 1935);
+//! assert_eq!(report.moves_as_letters(), "URRDLUUUUURDLLDRRDRDDRRUULLUURRU");
+//! ```
+
+pub mod ancestry;
+pub mod board;
+pub mod campaign;
+pub mod dice;
+pub mod direction;
+pub mod endpoints;
+pub mod events;
+pub mod frontier;
+pub mod fuzz;
+pub mod invariants;
+pub mod ledger;
+pub mod net;
+pub mod objective;
+pub mod partial;
+pub mod progress;
+pub mod record;
+pub mod render;
+pub mod report;
+pub mod resources;
+pub mod rules;
+pub mod schema;
+pub mod share;
+pub mod signature;
+pub mod solver;
+pub mod stats;
+pub mod submission;
+pub mod task;
+pub mod teach;
+pub mod turn;
+pub mod whatif;
+
+pub use board::Board;
+pub use dice::Dice;
+pub use direction::Direction;
+pub use report::SolutionReport;
+pub use solver::{Solution, Solver};
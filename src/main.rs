@@ -1,36 +1,1760 @@
-//! This is a Rust solution to the _Die Agony_ puzzle,
+//! Command line interface of the solver of the _Die Agony_ puzzle,
 //! described in <https://www.janestreet.com/puzzles/die-agony-index/>.
 
-mod board;
-mod dice;
-mod direction;
-mod solver;
+use die_agony::{
+    ancestry::AncestryFormat,
+    board::{parse_square, Board, Notation, Position, Square},
+    campaign::{parse_campaign, solve_campaign, CampaignSummary},
+    dice::Dice,
+    direction::{to_absolute_moves, to_relative_moves},
+    endpoints::compute_endpoints_matrix,
+    events::EventLog,
+    frontier::Strategy,
+    fuzz::fuzz_solve,
+    ledger::LedgerEntry,
+    net::{parse_net, parse_physical},
+    objective::{Criterion, Objective},
+    partial::PartialCredit,
+    progress::{ProgressInterval, ProgressReporter},
+    record::{Record, SearchLog, SearchRecorder},
+    render::{draw_dead_end_map, draw_dead_end_map_svg, draw_qr_code},
+    resources::ResourceUsage,
+    rules::{Arithmetic, FaceRange, FinalScore, Multiplier, Recurrence, Rules, Scoring},
+    share::{decode_share_link, encode_share_link},
+    signature::{compute_board_hash, compute_signature},
+    solver::{
+        ExplanationStyle, NetFormat, Solution, Solver, SolverBuilder, WaypointOrder, MAX_WAYPOINTS,
+    },
+    stats::{BoardStats, MoveStats},
+    submission::{format_submission, SubmissionContent},
+    task::{find_solutions_in_parallel, solve_each_in_parallel},
+    teach::teach,
+    whatif::{analyze_what_if, parse_edit_values, CellEdit},
+};
 
-use solver::{Solution, Solver};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use std::{
+    fs,
+    fs::File,
+    io::{self, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use clap::Parser;
+/// Counts the allocations of the process with a global allocator wrapping the system one, reported
+/// with `--report-resources`.
+#[cfg(feature = "count-allocations")]
+mod allocations {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    pub fn count() -> Option<u64> {
+        Some(ALLOCATIONS.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(not(feature = "count-allocations"))]
+mod allocations {
+    pub fn count() -> Option<u64> {
+        None
+    }
+}
+
+/// Enumerates the search strategies selectable from the command line.
+#[derive(Clone, Copy, ValueEnum)]
+enum StrategyArg {
+    /// Breadth-first search, which finds the shortest solution.
+    Bfs,
+    /// A* search, which finds the shortest solution when the weight is 1.
+    Astar,
+    /// Greedy best-first search, which quickly finds a solution, not necessarily the shortest.
+    Greedy,
+}
+
+/// Enumerates the criteria selectable from the command line, for the anytime mode.
+#[derive(Clone, Copy, ValueEnum)]
+enum CriterionArg {
+    /// Prefer solutions with fewer turns.
+    #[value(alias = "shortest")]
+    Turns,
+    /// Prefer solutions leaving a smaller sum of values in the unvisited cells.
+    #[value(alias = "min-unvisited-sum")]
+    UnvisitedSum,
+    /// Prefer solutions with a lexicographically smaller move string.
+    Moves,
+}
+
+/// Enumerates the solutions statistics can be computed on.
+#[derive(Clone, Copy, ValueEnum)]
+enum StatsScope {
+    /// Only the solution found.
+    Solution,
+    /// Every solution of the board.
+    All,
+}
+
+/// Enumerates the notations of positions selectable from the command line.
+#[derive(Clone, Copy, ValueEnum)]
+enum NotationArg {
+    /// 0-based `(row, col)` pairs from the top-left cell, or 1-based rows and columns in sentences.
+    RowCol,
+    /// Chess-like squares from the origin corner of the board, e.g. `a1` for the start cell unless
+    /// the board file declares another corner.
+    Chess,
+}
+
+/// Enumerates the values of the dice which can be scored.
+#[derive(Clone, Copy, ValueEnum)]
+enum ScoringArg {
+    /// The value on top of the dice.
+    Top,
+    /// The value on the face touching the board.
+    Bottom,
+    /// The sum of the values on the five faces not touching the board.
+    VisibleSum,
+}
+
+/// Enumerates how the score evolves, and when the dice lands on a cell.
+#[derive(Clone, Copy, ValueEnum)]
+enum RecurrenceArg {
+    /// The scored value times the multiplier is added to the score, which must match the cell value.
+    Affine,
+    /// The cell value must be divisible by the scored value, and the score counts the turns.
+    Divisibility,
+    /// The score is multiplied by the scored value, and must match the cell value.
+    Multiplicative,
+}
+
+/// Enumerates the orders in which waypoints can be visited.
+#[derive(Clone, Copy, ValueEnum)]
+enum WaypointOrderArg {
+    /// Waypoints can be visited in any order.
+    Any,
+    /// Waypoints must be visited in the order they are given.
+    Given,
+}
+
+/// Enumerates the formats in which the ancestry of the solution can be exported.
+#[derive(Clone, Copy, ValueEnum)]
+enum AncestryFormatArg {
+    /// A JSON document listing each decision point and its siblings.
+    Json,
+    /// A Graphviz graph.
+    Dot,
+}
+
+/// Enumerates the formats in which the net of the initial dice can be drawn.
+#[derive(Clone, Copy, ValueEnum)]
+enum NetFormatArg {
+    /// ASCII squares.
+    Text,
+    /// An SVG document.
+    Svg,
+}
+
+/// Enumerates the metrics the journey coming the closest to a solution can be chosen on.
+#[derive(Clone, Copy, ValueEnum)]
+enum PartialCreditArg {
+    /// Fewest movements left to reach the end cell.
+    Closest,
+    /// Most distinct cells visited.
+    Cells,
+    /// Highest score reached.
+    Score,
+}
+
+/// Enumerates the ways moves can be written.
+#[derive(Clone, Copy, ValueEnum)]
+enum MoveNotationArg {
+    /// Directions on the board: up, right, down and left, e.g. `URRD`.
+    Absolute,
+    /// Movements relative to the heading of the dice: forward, right, back and left, e.g. `FRFR`.
+    Relative,
+}
+
+/// Enumerates the formats in which the result of a solve can be printed.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FormatArg {
+    /// A report describing the solution in sentences.
+    Report,
+    /// Exactly the text to submit as the answer of the puzzle.
+    Submission,
+    /// A JSON document describing the solution turn by turn, to be read by scripts.
+    Json,
+}
+
+/// Enumerates what the answer submitted to a puzzle can hold.
+#[derive(Clone, Copy, ValueEnum)]
+enum SubmitArg {
+    /// Only the sum of the values in the unvisited cells.
+    Answer,
+    /// The sum of the values in the unvisited cells, and the cells visited in the notation.
+    AnswerAndPath,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Exhaustively counts the solutions of the board, and reports whether it has exactly one.
+    /// If another solution is found, it is explained.
+    CheckUnique {
+        /// Stop counting once this many solutions are found. Must be at least 2 to prove uniqueness.
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        bound: u64,
+        /// Count on this many threads, the search being partitioned by the first movement. The
+        /// count does not depend on the number of threads.
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        threads: Option<u64>,
+    },
+    /// Explains why each turn of the unique solution of the board is forced, listing the other
+    /// movements at each turn and why they were eliminated.
+    WhyForced,
+    /// Solves the board with the values of some cells changed, and reports how the number of
+    /// solutions and the answer change, compared to the original board. Or, solves the board for
+    /// every pair of start and end cells.
+    Analyze {
+        /// Cell to change, and its new value, e.g. `3,0=-7` or `a3=-7`. The value can also be a
+        /// range, e.g. `a3=-10..10`, solving the board with each value in turn. At most one cell can
+        /// be given a range.
+        #[arg(long = "what-if", value_name = "CELL=VALUE", required_unless_present_any = ["endpoints_matrix", "board_stats"], num_args = 1.., value_parser = parse_cell_edit)]
+        what_if: Vec<(Square, RangeInclusive<i16>)>,
+        /// Print, as a CSV matrix, the answer of the board for every pair of start cell (one row
+        /// each) and end cell (one column each), leaving the field empty without a solution.
+        /// Journeys are limited to as many turns as the board has cells, unless `--max-depth` is
+        /// given before the subcommand.
+        #[arg(long, conflicts_with = "what_if")]
+        endpoints_matrix: bool,
+        /// Print statistics on the values of the board: their distribution, extremes, duplicates,
+        /// sums by row and column, and the values the dice cannot land on at any turn under the
+        /// rules given before the subcommand.
+        #[arg(long, conflicts_with_all = ["what_if", "endpoints_matrix"])]
+        board_stats: bool,
+        /// Stop counting the solutions of a board once this many are found.
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+        bound: u64,
+    },
+    /// Maps the dead ends of the board: runs the search to exhaustion, and draws each cell with the
+    /// last turn on which a journey dying out before reaching the end cell visited it.
+    DeadEnds {
+        /// Draw the map as an SVG document, shading cells as journeys die out on them later.
+        #[arg(long)]
+        svg: bool,
+    },
+    /// Checks whether a signature printed with a solution is the signature of a solution of the
+    /// board, or whether moves form a solution of the board.
+    Verify {
+        /// Signature to check, as printed with a solution.
+        #[arg(long, required_unless_present = "moves", conflicts_with = "moves")]
+        signature: Option<String>,
+        /// Moves to check, e.g. `URRD`.
+        #[arg(long)]
+        moves: Option<String>,
+        /// Read the moves relative to the heading of the dice, e.g. `FRFR`: forward, right, back or
+        /// left of its last movement, the dice first heading toward the top of the board.
+        #[arg(long, requires = "moves")]
+        relative: bool,
+        /// Give up matching the signature once this many solutions were enumerated, as some boards
+        /// have endlessly many.
+        #[arg(long, default_value_t = 1000, conflicts_with = "moves", value_parser = clap::value_parser!(u64).range(1..))]
+        bound: u64,
+    },
+    /// Converts moves between absolute directions, e.g. `URRD`, and movements relative to the
+    /// heading of the dice, e.g. `FRFR`.
+    ConvertMoves {
+        /// Moves to convert, written in the other notation.
+        moves: String,
+        /// Notation to convert the moves to.
+        #[arg(long, value_enum)]
+        to: MoveNotationArg,
+    },
+    /// Decodes a share link, printing the board and the moves of the solution it holds, if any.
+    Decode {
+        /// Share link, as printed with `--share-link`.
+        link: String,
+    },
+    /// Reads back a search log written with `--record-search`, summarizing it or answering queries
+    /// about the search without running it again.
+    SearchReplay {
+        /// Search log to read.
+        log: PathBuf,
+        /// Replay the search record by record.
+        #[arg(long)]
+        animate: bool,
+        /// Report when a cell was first reached, as a `row,col` pair or a square like `a1`.
+        #[arg(long, value_name = "CELL", value_parser = parse_square)]
+        first_reached: Option<Square>,
+        /// Report how many journeys reached this depth, i.e. lasted this many turns.
+        #[arg(long, value_name = "DEPTH")]
+        alive_at_depth: Option<i16>,
+    },
+    /// Solves the puzzle from every start cell, and reports which ones admit a solution.
+    TryStarts {
+        /// Start cells to try, as `row,col` pairs or squares like `a1`. All cells of the board are
+        /// tried if none are given.
+        #[arg(value_parser = parse_square)]
+        cells: Vec<Square>,
+    },
+    /// Steps through the solution interactively, asking before each turn where the dice rolls or
+    /// what its top must be, and explaining the deduction behind each answer.
+    Teach,
+    /// Solves random boards with tight limits, checking that the solver never panics, always
+    /// terminates, and only reports solutions which verify. Prints a command reproducing the first
+    /// failure, if any.
+    FuzzSolve {
+        /// How many boards to solve.
+        #[arg(long, default_value_t = 100)]
+        iterations: u64,
+        /// Range of the sizes of the boards, both ends included.
+        #[arg(long, value_name = "LO..HI", default_value = "4..10", value_parser = parse_size_range)]
+        size_range: RangeInclusive<usize>,
+        /// Seed of the first board, the next boards using the following seeds. Random by default.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Enumerates the solutions of the board page by page, saving where the enumeration stands to
+    /// a file, so that the next call resumes from there.
+    Solutions {
+        /// How many solutions to print.
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+        /// File holding the state of the enumeration, read if it exists, and written back after
+        /// printing the solutions.
+        #[arg(long, value_name = "PATH")]
+        state: PathBuf,
+    },
+}
 
 #[derive(Parser)]
 #[command(version, about)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// How positions are written in the output. Positions given as input can always use either.
+    #[arg(long, value_enum, global = true, default_value_t = NotationArg::RowCol)]
+    notation: NotationArg,
+
+    /// How to print the result of the solve.
+    #[arg(long, value_enum, default_value_t = FormatArg::Report)]
+    format: FormatArg,
+
+    /// What the submission printed with `--format submission` holds, instead of what the puzzle
+    /// expects, which is only the answer for Die Agony.
+    #[arg(long, value_enum)]
+    submit: Option<SubmitArg>,
+
     /// Print a textual explanation of the solution, if any is found.
     #[arg(short, long)]
     explain: bool,
+
+    /// Draw the dice in pseudo-3D after each turn of the explanation, showing its orientation.
+    #[arg(long, requires = "explain")]
+    isometric: bool,
+
+    /// Explain the solution in full sentences with explicit coordinates, without any drawing,
+    /// for screen readers.
+    #[arg(long, requires = "explain", conflicts_with = "isometric")]
+    accessible: bool,
+
+    /// Draw the unfolded net of the initial dice in the explanation, ready to be cut out and folded.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        requires = "explain",
+        conflicts_with = "accessible"
+    )]
+    net: Option<NetFormatArg>,
+
+    /// End the explanation with a table comparing the score of each turn with the value of the cell
+    /// reached, and a checksum over all turns.
+    #[arg(long, requires = "explain", conflicts_with = "accessible")]
+    verify: bool,
+
+    /// Print how many moves went in each direction, and the longest straight run, for the solution
+    /// found, or across all the solutions of the board.
+    #[arg(long, value_enum, value_name = "SCOPE", num_args = 0..=1, default_missing_value = "solution")]
+    stats: Option<StatsScope>,
+
+    /// Stop gathering statistics across all solutions once this many are found, as some boards
+    /// have endlessly many.
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+    stats_bound: u64,
+
+    /// Print a compact, URL-safe string encoding the board and its solution, to share them.
+    #[arg(long)]
+    share_link: bool,
+
+    /// Also print the share link as a QR code, to grab it by phone from a terminal on a projector.
+    #[arg(long, requires = "share_link")]
+    qr: bool,
+
+    /// Order in which candidate journeys are explored.
+    #[arg(long, value_enum, default_value_t = StrategyArg::Bfs)]
+    strategy: StrategyArg,
+
+    /// Weight of the distance to the end cell, for the A* strategy.
+    /// Weights above 1 find a solution faster, but not necessarily the shortest one.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_weight)]
+    weight: f64,
+
+    /// Count the multiplier down from this value instead of up from 1, i.e. the score grows by
+    /// (N - turn + 1) x top on each turn.
+    #[arg(long, value_name = "N")]
+    descending_from: Option<i16>,
+
+    /// Use a sequence of multipliers instead of the turn number: `fibonacci`, `primes`, or a file
+    /// listing one multiplier per turn, separated by spaces, commas or new lines. Journeys cannot
+    /// last more turns than the file lists multipliers.
+    #[arg(long, value_name = "NAME|PATH", value_parser = parse_multipliers, conflicts_with = "descending_from")]
+    multipliers: Option<Multiplier>,
+
+    /// Do not extend journeys past this many turns.
+    #[arg(long, value_name = "N")]
+    max_depth: Option<i16>,
+
+    /// Do not revisit any cell more than this many times, e.g. 0 to never revisit a cell.
+    #[arg(long, value_name = "K")]
+    max_revisits: Option<u16>,
+
+    /// Do not roll in the same direction more than this many times in a row, i.e. turn at least
+    /// every K movements.
+    #[arg(long, value_name = "K", visible_alias = "must-turn-every", value_parser = clap::value_parser!(u16).range(1..))]
+    max_straight: Option<u16>,
+
+    /// Discard journeys inferring a face value outside of this range, both ends included, e.g. `-9..9`.
+    #[arg(long, value_name = "LO..HI", value_parser = parse_face_range, allow_hyphen_values = true)]
+    face_range: Option<FaceRange>,
+
+    /// Require opposite faces of the dice to sum to this value, e.g. 7 for a standard dice, or 5
+    /// for one labelled from 0 to 5. Inferring a face then infers its opposite face as well.
+    #[arg(long, value_name = "S", allow_hyphen_values = true)]
+    opposite_sum: Option<i16>,
+
+    /// Discard journeys inferring a face value which is not in this comma-separated list, e.g.
+    /// `1,2,3,4,5,6`.
+    #[arg(long, value_name = "VALUES", value_parser = parse_faces, allow_hyphen_values = true)]
+    faces: Option<Arc<[i16]>>,
+
+    /// Discard journeys inferring a face value already held by another face of the dice.
+    #[arg(long)]
+    distinct_faces: bool,
+
+    /// Only accept solutions visiting these cells, as `row,col` pairs or squares like `a1`.
+    #[arg(long, value_name = "CELL", num_args = 1.., value_parser = parse_square)]
+    via: Vec<Square>,
+
+    /// Never land on these cells, as if they were walls, given as `row,col` pairs or squares like `a1`.
+    #[arg(long, value_name = "CELL", num_args = 1.., value_parser = parse_square)]
+    avoid: Vec<Square>,
+
+    /// Order in which the cells given with `--via` must be visited.
+    #[arg(long, value_enum, default_value_t = WaypointOrderArg::Any, requires = "via")]
+    via_order: WaypointOrderArg,
+
+    /// Which value of the dice is multiplied by the turn number and added to the score.
+    #[arg(long, value_enum, default_value_t = ScoringArg::Top)]
+    scoring: ScoringArg,
+
+    /// Maintain the score modulo this value, landing on a cell when the score is congruent to its value.
+    #[arg(long, value_name = "M", value_parser = clap::value_parser!(i16).range(1..))]
+    modulus: Option<i16>,
+
+    /// How the score evolves from turn to turn, and when the dice lands on a cell.
+    #[arg(long, value_enum, default_value_t = RecurrenceArg::Affine)]
+    recurrence: RecurrenceArg,
+
+    /// Score before the first turn, with the multiplicative recurrence.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        allow_hyphen_values = true
+    )]
+    initial_score: i16,
+
+    /// Only accept solutions ending with this score on the end cell: `end-cell` for the value of
+    /// the end cell, or a target value. By default, landing on the end cell suffices.
+    #[arg(long, value_name = "end-cell|N", value_parser = parse_final_score, allow_hyphen_values = true)]
+    final_score: Option<FinalScore>,
+
+    /// Solve the board written in this file instead of the puzzle board, subcommands included. The
+    /// board can have any number of rows and columns, and is written row by row from the top, with
+    /// values separated by spaces or commas. It can declare how the dice moves with a
+    /// `movement: orthogonal|orthogonal+wrap` line.
+    #[arg(long, value_name = "PATH", conflicts_with = "campaign")]
+    board: Option<PathBuf>,
+
+    /// Solve the boards listed in this file in order, separated by blank lines, each journey
+    /// starting with the dice ending the previous one. Boards are written row by row from the top,
+    /// with values separated by spaces or commas. Options about a single board, its solution or
+    /// its search, such as `--via`, `--explain` or `--turns-csv`, cannot be combined with it.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = [
+            "format", "submit", "explain", "stats", "share_link", "via", "avoid", "turns_csv",
+            "journey_svg", "ancestry", "ledger", "partial_credit", "warm_start", "report_resources",
+            "record_search", "events", "progress", "anytime",
+        ]
+    )]
+    campaign: Option<PathBuf>,
+
+    /// Solve the boards of the campaign independently instead, each with the same initial dice,
+    /// and report the total of their answers, their solve times and node counts.
+    #[arg(long, requires = "campaign")]
+    summary: bool,
+
+    /// Start with the dice described by the unfolded net in this file, instead of a blank dice.
+    /// The net is laid out as a cross, seen from above with the top of the board facing up:
+    /// the front face on the first row, the left, top and right faces on the second row, the back
+    /// face on the third row and the bottom face on the fourth row. Empty squares are marked with
+    /// `.`, and unknown values with `?`.
+    #[arg(long, value_name = "PATH")]
+    die_net: Option<PathBuf>,
+
+    /// Start with the dice described as it sits on a table, instead of a blank dice: a
+    /// comma-separated list of `side=value` pairs, such as `top=1,north=5,east=3`. The side is
+    /// `top`, `bottom`, or the compass direction it faces, `north` being toward the top of the
+    /// board and `east` toward its right. Sides which are not listed are unknown.
+    #[arg(long, value_name = "SIDES", conflicts_with = "die_net")]
+    die_physical: Option<String>,
+
+    /// Write the turns of the solution to this CSV file: direction, position reached, top of the
+    /// dice, score and cell value of each turn.
+    #[arg(long, value_name = "PATH")]
+    turns_csv: Option<PathBuf>,
+
+    /// Draw the journey of the solution on the board as an SVG document in this file.
+    #[arg(long, value_name = "PATH")]
+    journey_svg: Option<PathBuf>,
+
+    /// Animate the journey drawn with `--journey-svg`, the dice rolling from cell to cell with its
+    /// top updating as it lands.
+    #[arg(long, requires = "journey_svg")]
+    animate_svg: bool,
+
+    /// Export the decision points of the solution to this file, along with the sibling branches
+    /// left at each of them and why they were pruned, rather than the whole search tree.
+    #[arg(long, value_name = "PATH")]
+    ancestry: Option<PathBuf>,
+
+    /// Format of the file written with `--ancestry`.
+    #[arg(long, value_enum, default_value_t = AncestryFormatArg::Json, requires = "ancestry")]
+    ancestry_format: AncestryFormatArg,
+
+    /// Append a row with the metrics of the search to this CSV ledger, creating it if needed:
+    /// timestamp, version, board hash, strategy, time, nodes expanded and answer.
+    #[arg(long, value_name = "PATH", conflicts_with = "anytime")]
+    ledger: Option<PathBuf>,
+
+    /// If no solution is found, report the journey explored by the search which comes the closest
+    /// to one on this metric, to see how close a draft board comes to being solvable.
+    #[arg(long, value_enum, value_name = "METRIC")]
+    partial_credit: Option<PartialCreditArg>,
+
+    /// Warm-start the search from the moves of a previous solution, e.g. of a slightly different
+    /// board: they are replayed first, and if they break, the journeys continuing their longest
+    /// valid prefix are searched first.
+    #[arg(long, value_name = "MOVES", conflicts_with = "anytime")]
+    warm_start: Option<String>,
+
+    /// Report the resources used by the solve to stderr: the peak resident set size where the
+    /// platform reports it, the allocations when built with the `count-allocations` feature, the
+    /// peak frontier size, and the steps stored by the solver.
+    #[arg(long, conflicts_with = "anytime")]
+    report_resources: bool,
+
+    /// Write a compact binary log of every step generated, expanded or pruned by the search to this
+    /// file.
+    #[arg(long, value_name = "PATH")]
+    record_search: Option<PathBuf>,
+
+    /// Maximum size of the search log, in bytes.
+    #[arg(long, value_name = "BYTES", default_value_t = 64 << 20, requires = "record_search")]
+    record_limit: u64,
+
+    /// Write NDJSON events describing the progress of the search to this file, or to stdout with
+    /// `-`. The report of the solve then goes to stderr, so that stdout only holds events.
+    #[arg(long, value_name = "PATH")]
+    events: Option<PathBuf>,
+
+    /// Report the progress of the search to stderr every N expanded steps, or every T
+    /// milliseconds or seconds (e.g. `100000`, `500ms` or `2s`).
+    #[arg(long, value_name = "N|T", value_parser = parse_progress_interval)]
+    progress: Option<ProgressInterval>,
+
+    /// Write the report of the solve to `answer.txt` in this directory instead of stdout, and
+    /// resolve the relative paths of the turns CSV, journey SVG, search log and events in it. The
+    /// directory is created if needed.
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Keep searching after the first solution, reporting every improvement on the objective
+    /// until the state space is exhausted or the time limit is hit.
+    #[arg(long)]
+    anytime: bool,
+
+    /// What makes a solution better than another, in anytime mode. This is an ordered list of
+    /// criteria, where ties on a criterion are broken by the next one.
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "turns")]
+    objective: Vec<CriterionArg>,
+
+    /// Time limit of the anytime mode, in seconds.
+    #[arg(long, default_value_t = 10)]
+    time_limit: u64,
+}
+
+fn parse_weight(weight: &str) -> Result<f64, String> {
+    match weight.parse::<f64>() {
+        Ok(weight) if weight.is_finite() && weight > 0.0 => Ok(weight),
+        _ => Err(format!("`{}` is not a positive number", weight)),
+    }
+}
+
+fn parse_progress_interval(interval: &str) -> Result<ProgressInterval, String> {
+    let invalid = || format!("`{}` is not a number of steps, nor a duration", interval);
+    if let Some(millis) = interval.strip_suffix("ms") {
+        millis
+            .parse()
+            .map(|millis| ProgressInterval::Time(Duration::from_millis(millis)))
+            .map_err(|_| invalid())
+    } else if let Some(secs) = interval.strip_suffix('s') {
+        secs.parse()
+            .map(|secs| ProgressInterval::Time(Duration::from_secs(secs)))
+            .map_err(|_| invalid())
+    } else {
+        match interval.parse() {
+            Ok(nodes) if nodes > 0 => Ok(ProgressInterval::Nodes(nodes)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn parse_size_range(range: &str) -> Result<RangeInclusive<usize>, String> {
+    let invalid = || format!("`{}` is not a `lo..hi` range of sizes", range);
+    let (min, max) = range.split_once("..").ok_or_else(invalid)?;
+    let min = min.trim().parse::<usize>().map_err(|_| invalid())?;
+    let max = max.trim().parse::<usize>().map_err(|_| invalid())?;
+    if min == 0 || min > max {
+        return Err(format!("`{}` is an empty range of sizes", range));
+    }
+
+    Ok(min..=max)
+}
+
+fn parse_face_range(range: &str) -> Result<FaceRange, String> {
+    let invalid = || format!("`{}` is not a `lo..hi` range", range);
+    let (min, max) = range.split_once("..").ok_or_else(invalid)?;
+    let min = min.trim().parse::<i16>().map_err(|_| invalid())?;
+    let max = max.trim().parse::<i16>().map_err(|_| invalid())?;
+    if min > max {
+        return Err(format!("`{}` is an empty range", range));
+    }
+
+    Ok(FaceRange { min, max })
+}
+
+fn parse_final_score(final_score: &str) -> Result<FinalScore, String> {
+    match final_score {
+        "end-cell" => Ok(FinalScore::EndCellValue),
+        _ => match final_score.parse::<i16>() {
+            Ok(target) => Ok(FinalScore::Target(target)),
+            Err(_) => Err(format!(
+                "`{}` is neither `end-cell` nor a valid score",
+                final_score
+            )),
+        },
+    }
+}
+
+fn parse_faces(faces: &str) -> Result<Arc<[i16]>, String> {
+    let faces = faces
+        .split(',')
+        .map(|face| match face.trim().parse::<i16>() {
+            Ok(face) => Ok(face),
+            Err(_) => Err(format!("`{}` is not a valid face value", face)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(faces.into())
+}
+
+fn parse_multipliers(multipliers: &str) -> Result<Multiplier, String> {
+    match multipliers {
+        "fibonacci" => return Ok(Multiplier::Fibonacci),
+        "primes" => return Ok(Multiplier::Primes),
+        _ => {}
+    }
+
+    let sequence = fs::read_to_string(multipliers)
+        .map_err(|error| format!("cannot read the multipliers in {}: {}", multipliers, error))?
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|multiplier| !multiplier.is_empty())
+        .map(|multiplier| match multiplier.parse::<i16>() {
+            Ok(multiplier) => Ok(multiplier),
+            Err(_) => Err(format!("`{}` is not a valid multiplier", multiplier)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if sequence.is_empty() {
+        return Err(format!("{} does not list any multiplier", multipliers));
+    }
+
+    Ok(Multiplier::Sequence(sequence.into()))
+}
+
+/// Resolves the path of a file to write, relative to the output directory if any.
+fn output_path(output_dir: Option<&Path>, path: PathBuf) -> PathBuf {
+    match output_dir {
+        Some(output_dir) => output_dir.join(path),
+        None => path,
+    }
+}
+
+/// Writes the report of a solve to `answer.txt` in the output directory if any, or else to stdout,
+/// unless stdout is taken by the NDJSON events, in which case the report goes to stderr. Progress
+/// and diagnostics always go to stderr, so that stdout only holds machine-readable output.
+fn write_report(output_dir: Option<&Path>, events_on_stdout: bool, report: &str) {
+    let written = match output_dir {
+        Some(output_dir) => fs::write(output_dir.join("answer.txt"), format!("{}\n", report)),
+        None if events_on_stdout => writeln!(io::stderr(), "{}", report),
+        None => writeln!(io::stdout(), "{}", report),
+    };
+    if let Err(error) = written {
+        eprintln!("Cannot write the report: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn parse_cell_edit(edit: &str) -> Result<(Square, RangeInclusive<i16>), String> {
+    let (position, values) = edit
+        .split_once('=')
+        .ok_or_else(|| format!("`{}` is not a `cell=value` edit", edit))?;
+
+    Ok((parse_square(position)?, parse_edit_values(values)?))
+}
+
+/// Returns the positions of the given cells on the board, exiting if a square is beyond its edges.
+fn resolve_squares(squares: Vec<Square>, board: &Board) -> Vec<Position> {
+    squares
+        .into_iter()
+        .map(|square| {
+            square.resolve(board).unwrap_or_else(|| {
+                eprintln!("{} is not a square of the board.", square);
+                std::process::exit(1);
+            })
+        })
+        .collect()
 }
 
 fn main() {
     let args = Args::parse();
-    match Solver::new().solve() {
-        Solution::Found(sum_unvisited_cells, explanation) => {
-            println!(
-                "The sum of values in the unvisited cells is {}.",
-                sum_unvisited_cells
+    let board = match &args.board {
+        Some(path) => match fs::File::open(path)
+            .map_err(|error| error.to_string())
+            .and_then(|file| Board::from_reader(io::BufReader::new(file)))
+        {
+            Ok(board) => board,
+            Err(error) => {
+                eprintln!("Cannot read the board in {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        },
+        None => Board::new(),
+    };
+    let notation = match args.notation {
+        NotationArg::RowCol => Notation::RowCol,
+        NotationArg::Chess => board.chess_notation(),
+    };
+    let strategy = match args.strategy {
+        StrategyArg::Bfs => Strategy::Bfs,
+        StrategyArg::Astar => Strategy::AStar {
+            weight: args.weight,
+        },
+        StrategyArg::Greedy => Strategy::Greedy,
+    };
+
+    let multiplier = match (args.descending_from, args.multipliers) {
+        (Some(from), _) => Multiplier::Descending { from },
+        (None, Some(multiplier)) => multiplier,
+        (None, None) => Multiplier::Ascending,
+    };
+
+    let arithmetic = match args.modulus {
+        Some(modulus) => Arithmetic::Modular { modulus },
+        None => Arithmetic::Integer,
+    };
+
+    let mut builder = Solver::builder()
+        .board(board.clone())
+        .strategy(strategy)
+        .multiplier(multiplier)
+        .arithmetic(arithmetic)
+        .recurrence(match args.recurrence {
+            RecurrenceArg::Affine => Recurrence::Affine,
+            RecurrenceArg::Divisibility => Recurrence::Divisibility,
+            RecurrenceArg::Multiplicative => Recurrence::Multiplicative {
+                initial: args.initial_score,
+            },
+        })
+        .scoring(match args.scoring {
+            ScoringArg::Top => Scoring::Top,
+            ScoringArg::Bottom => Scoring::Bottom,
+            ScoringArg::VisibleSum => Scoring::VisibleSum,
+        })
+        .verification(args.verify)
+        .notation(notation);
+    // Known faces are checked against the constraints on faces, which only apply to inferred ones
+    // during the search.
+    let face_rules = Rules {
+        face_range: args.face_range,
+        opposite_sum: args.opposite_sum,
+        allowed_faces: args.faces.clone(),
+        distinct_faces: args.distinct_faces,
+        ..Rules::default()
+    };
+    let check_known_faces = |dice: Dice| face_rules.check_known_faces(&dice).map(|()| dice);
+    if let Some(path) = args.die_net {
+        let dice = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|net| parse_net(&net))
+            .and_then(check_known_faces);
+        match dice {
+            Ok(dice) => builder = builder.dice(dice),
+            Err(error) => {
+                eprintln!("Cannot read the dice net in {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(description) = args.die_physical {
+        match parse_physical(&description).and_then(check_known_faces) {
+            Ok(dice) => builder = builder.dice(dice),
+            Err(error) => {
+                eprintln!("Cannot read the dice `{}`: {}", description, error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if args.isometric {
+        builder = builder.explanation_style(ExplanationStyle::Isometric);
+    }
+    if args.accessible {
+        builder = builder.explanation_style(ExplanationStyle::Accessible);
+    }
+    if let Some(net_format) = args.net {
+        builder = builder.net_format(match net_format {
+            NetFormatArg::Text => NetFormat::Text,
+            NetFormatArg::Svg => NetFormat::Svg,
+        });
+    }
+    if let Some(face_range) = args.face_range {
+        builder = builder.face_range(face_range);
+    }
+    if let Some(opposite_sum) = args.opposite_sum {
+        builder = builder.opposite_sum(opposite_sum);
+    }
+    if let Some(faces) = args.faces {
+        builder = builder.allowed_faces(faces);
+    }
+    if args.distinct_faces {
+        builder = builder.distinct_faces();
+    }
+    if let Some(final_score) = args.final_score {
+        builder = builder.final_score(final_score);
+    }
+    if let Some(max_depth) = args.max_depth {
+        builder = builder.max_depth(max_depth);
+    }
+    // Board statistics depend on the rules, so they are only computed once those are known.
+    if let Some(Command::Analyze {
+        board_stats: true, ..
+    }) = args.command
+    {
+        let stats = BoardStats::from_board(&board, builder.rules());
+        println!("{}", stats.describe(notation));
+        return;
+    }
+    if let Some(max_revisits) = args.max_revisits {
+        builder = builder.max_revisits(max_revisits);
+    }
+    if let Some(max_straight) = args.max_straight {
+        builder = builder.max_straight(max_straight);
+    }
+    // Avoided cells and waypoints are cells of the board given, so campaigns do without them.
+    let mut solver_builder = builder.clone();
+    if !args.avoid.is_empty() {
+        solver_builder = solver_builder.avoided_cells(resolve_squares(args.avoid, &board));
+        if solver_builder.clone().build().is_none() {
+            eprintln!("Avoided cells must be cells of the board.");
+            std::process::exit(1);
+        }
+    }
+    if !args.via.is_empty() {
+        let order = match args.via_order {
+            WaypointOrderArg::Any => WaypointOrder::Any,
+            WaypointOrderArg::Given => WaypointOrder::Given,
+        };
+        solver_builder = solver_builder.waypoints(resolve_squares(args.via, &board), order);
+        if solver_builder.clone().build().is_none() {
+            eprintln!(
+                "Waypoints must be cells of the board, and there can be at most {} of them.",
+                MAX_WAYPOINTS
+            );
+            std::process::exit(1);
+        }
+    }
+    match args.command {
+        Some(Command::CheckUnique { bound, threads }) => {
+            return check_unique(&solver_builder, &board, bound, threads)
+        }
+        Some(Command::TryStarts { cells }) => {
+            return try_starts(
+                &solver_builder,
+                &board,
+                resolve_squares(cells, &board),
+                notation,
+            )
+        }
+        Some(Command::Solutions { count, state }) => {
+            return page_solutions(&solver_builder, count, &state)
+        }
+        Some(Command::Verify {
+            signature,
+            moves,
+            relative,
+            bound,
+        }) => {
+            return verify(
+                &solver_builder,
+                &board,
+                signature.as_deref(),
+                moves.as_deref(),
+                relative,
+                bound,
+            )
+        }
+        Some(Command::ConvertMoves { moves, to }) => return convert_moves(&moves, to),
+        Some(Command::Teach) => return teach_solution(&solver_builder, &board, notation),
+        Some(Command::WhyForced) => return why_forced(&solver_builder),
+        Some(Command::Analyze {
+            endpoints_matrix: true,
+            ..
+        }) => return endpoints_matrix(&solver_builder, &board, args.max_depth, notation),
+        Some(Command::Analyze {
+            what_if,
+            bound,
+            board_stats: false,
+            ..
+        }) => {
+            let what_if: Vec<CellEdit> = what_if
+                .into_iter()
+                .map(|(square, values)| CellEdit {
+                    position: resolve_squares(vec![square], &board)[0],
+                    values,
+                })
+                .collect();
+            return analyze(&solver_builder, &board, &what_if, bound, notation);
+        }
+        Some(Command::DeadEnds { svg }) => return dead_ends(&solver_builder, &board, svg),
+        Some(Command::FuzzSolve {
+            iterations,
+            size_range,
+            seed,
+        }) => return fuzz(iterations, size_range, seed),
+        Some(Command::Decode { link }) => return decode(&link),
+        Some(Command::SearchReplay {
+            log,
+            animate,
+            first_reached,
+            alive_at_depth,
+        }) => {
+            let first_reached =
+                first_reached.map(|square| resolve_squares(vec![square], &board)[0]);
+            return search_replay(&log, animate, first_reached, alive_at_depth, notation);
+        }
+        Some(Command::Analyze {
+            board_stats: true, ..
+        })
+        | None => {}
+    }
+
+    let output_dir = args.output_dir.as_deref();
+    if let Some(output_dir) = output_dir {
+        if let Err(error) = fs::create_dir_all(output_dir) {
+            eprintln!("Cannot create {}: {}", output_dir.display(), error);
+            std::process::exit(1);
+        }
+    }
+    let events_on_stdout = args.events.as_deref() == Some(Path::new("-"));
+
+    if let Some(path) = args.campaign {
+        let boards = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|campaign| parse_campaign(&campaign));
+        match boards {
+            Ok(boards) => {
+                let report = if args.summary {
+                    CampaignSummary::solve(builder, boards).map(|summary| summary.describe())
+                } else {
+                    describe_campaign(builder, boards)
+                };
+                let Some(report) = report else {
+                    eprintln!("The solver cannot be built for every board of the campaign.");
+                    std::process::exit(1);
+                };
+                return write_report(output_dir, events_on_stdout, &report);
+            }
+            Err(error) => {
+                eprintln!("Cannot read the campaign in {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut solver = build_solver(&solver_builder);
+    if let Some(path) = args.turns_csv {
+        let path = output_path(output_dir, path);
+        match File::create(&path) {
+            Ok(file) => solver = solver.set_turns_csv(Box::new(file)),
+            Err(error) => {
+                eprintln!("Cannot create {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = args.journey_svg {
+        let path = output_path(output_dir, path);
+        match File::create(&path) {
+            Ok(file) => solver = solver.set_journey_svg(Box::new(file), args.animate_svg),
+            Err(error) => {
+                eprintln!("Cannot create {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = args.ancestry {
+        let path = output_path(output_dir, path);
+        let format = match args.ancestry_format {
+            AncestryFormatArg::Json => AncestryFormat::Json,
+            AncestryFormatArg::Dot => AncestryFormat::Dot,
+        };
+        match File::create(&path) {
+            Ok(file) => solver = solver.set_ancestry(Box::new(file), format),
+            Err(error) => {
+                eprintln!("Cannot create {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = args.record_search {
+        let path = output_path(output_dir, path);
+        match File::create(&path) {
+            Ok(file) => {
+                let writer = Box::new(io::BufWriter::new(file));
+                solver = solver.set_recorder(SearchRecorder::new(writer, args.record_limit));
+            }
+            Err(error) => {
+                eprintln!("Cannot create {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = args.events {
+        let writer: Box<dyn io::Write + Send> = if events_on_stdout {
+            Box::new(io::stdout())
+        } else {
+            let path = output_path(output_dir, path);
+            match File::create(&path) {
+                Ok(file) => Box::new(file),
+                Err(error) => {
+                    eprintln!("Cannot create {}: {}", path.display(), error);
+                    std::process::exit(1);
+                }
+            }
+        };
+        solver = solver.set_events(EventLog::new(writer));
+    }
+    if let Some(metric) = args.partial_credit {
+        solver = solver.set_partial_credit(match metric {
+            PartialCreditArg::Closest => PartialCredit::ClosestToEnd,
+            PartialCreditArg::Cells => PartialCredit::MostCells,
+            PartialCreditArg::Score => PartialCredit::HighestScore,
+        });
+    }
+    if let Some(moves) = &args.warm_start {
+        solver = solver.set_warm_start(moves).unwrap_or_else(|| {
+            eprintln!("The moves to warm-start from must be written with U, R, D and L.");
+            std::process::exit(1);
+        });
+    }
+    if let Some(interval) = args.progress {
+        solver = solver.set_progress(ProgressReporter::new(interval, |report| {
+            eprintln!(
+                "Expanded {} steps in {:.1?}, with {} waiting to be explored.",
+                report.stats.expanded, report.elapsed, report.frontier
+            )
+        }));
+    }
+    let solution = if args.anytime {
+        let criteria = args
+            .objective
+            .iter()
+            .map(|criterion| match criterion {
+                CriterionArg::Turns => Criterion::Turns,
+                CriterionArg::UnvisitedSum => Criterion::UnvisitedSum,
+                CriterionArg::Moves => Criterion::Moves,
+            })
+            .collect();
+        solver.solve_anytime(
+            &Objective::new(criteria),
+            Duration::from_secs(args.time_limit),
+            |improvement| {
+                if let Solution::Found(report) = improvement {
+                    eprintln!(
+                        "Found a solution where the sum of values in the unvisited cells is {}.",
+                        report.unvisited_sum
+                    );
+                }
+            },
+        )
+    } else {
+        let start = Instant::now();
+        let (solution, stats) = solver.solve_with_stats();
+        if args.report_resources {
+            eprintln!(
+                "{}",
+                ResourceUsage::measure(&stats, allocations::count()).describe()
             );
+        }
+        if let Some(path) = args.ledger {
+            let entry = LedgerEntry {
+                board_hash: compute_board_hash(&board),
+                strategy,
+                elapsed: start.elapsed(),
+                nodes: stats.expanded,
+                answer: match &solution {
+                    Solution::Found(report) => Some(report.unvisited_sum),
+                    Solution::NotFound(..) => None,
+                },
+            };
+            if let Err(error) = entry.append_to(&path) {
+                eprintln!("Cannot append to the ledger {}: {}", path.display(), error);
+            }
+        }
+        solution
+    };
+
+    if let Solution::Found(solution_report) = &solution {
+        for (output, error) in &solution_report.output_errors {
+            eprintln!("Cannot write {}: {}", output, error);
+        }
+    }
+
+    let mut report = Vec::new();
+    match solution {
+        solution if args.format == FormatArg::Json => {
+            let mut document = solution.to_json();
+            if let (Some(StatsScope::All), Solution::Found(..)) = (args.stats, &solution) {
+                let (stats, count) =
+                    gather_stats_across_solutions(&solver_builder, args.stats_bound);
+                document["stats_across_solutions"] = json!({
+                    "solutions": count,
+                    "truncated": count as u64 == args.stats_bound,
+                    "move_stats": stats.to_json(),
+                });
+            }
+            report.push(document.to_string());
+        }
+        Solution::Found(solution_report) if args.format == FormatArg::Submission => {
+            let content = match args.submit {
+                Some(SubmitArg::Answer) => SubmissionContent::Answer,
+                Some(SubmitArg::AnswerAndPath) => SubmissionContent::AnswerAndPath,
+                None => SubmissionContent::for_board(&board),
+            };
+            report.push(format_submission(
+                &board,
+                content,
+                solution_report.unvisited_sum,
+                &solution_report.moves_as_letters(),
+                notation,
+            ));
+        }
+        Solution::Found(solution_report) => {
+            let moves = solution_report.moves_as_letters();
+            report.push(format!(
+                "The sum of values in the unvisited cells is {}.",
+                solution_report.unvisited_sum
+            ));
+            report.push(format!(
+                "The signature of this solution is {}.",
+                compute_signature(&board, &moves)
+            ));
+            match args.stats {
+                Some(StatsScope::Solution) => report.push(MoveStats::from_moves(&moves).describe()),
+                Some(StatsScope::All) => report.push(describe_stats_across_all_solutions(
+                    &solver_builder,
+                    args.stats_bound,
+                )),
+                None => {}
+            }
+            if args.share_link {
+                match encode_share_link(&board, Some(&moves)) {
+                    Ok(link) => {
+                        report.push(format!("Share this board and its solution with {}", link));
+                        if args.qr {
+                            report.push(describe_qr_code(&link));
+                        }
+                    }
+                    Err(error) => report.push(format!("Cannot share the solution: {}.", error)),
+                }
+            }
 
             if args.explain {
-                println!("{}", explanation);
+                report.push(solution_report.to_string());
+            }
+        }
+        Solution::NotFound(reason, stats, partial_journey) => {
+            report.push(format!(
+                "Oops, no solution found, as {}. The search expanded {} steps.",
+                reason, stats.expanded
+            ));
+            if let Some(partial_journey) = partial_journey {
+                report.push(partial_journey.describe(notation));
+            }
+            if args.share_link {
+                // Without moves, a board can always be shared.
+                let link = encode_share_link(&board, None).unwrap_or_default();
+                report.push(format!("Share this board with {}", link));
+                if args.qr {
+                    report.push(describe_qr_code(&link));
+                }
+            }
+        }
+    }
+    write_report(output_dir, events_on_stdout, &report.join("\n"));
+}
+
+/// Builds a solver from a builder which avoided cells and waypoints were checked to fit the board.
+fn build_solver(builder: &SolverBuilder) -> Solver {
+    builder.clone().build().unwrap_or_else(|| {
+        eprintln!("The avoided cells and waypoints do not fit the board.");
+        std::process::exit(1);
+    })
+}
+
+fn describe_qr_code(link: &str) -> String {
+    draw_qr_code(link)
+        .unwrap_or_else(|| "The share link is too long to fit in a QR code.".to_string())
+}
+
+fn describe_campaign(builder: SolverBuilder, boards: Vec<Board>) -> Option<String> {
+    let board_count = boards.len();
+    let mut lines = Vec::new();
+    let mut total = 0;
+    for (index, solution) in solve_campaign(builder, boards)?.into_iter().enumerate() {
+        match solution {
+            Solution::Found(report) => {
+                lines.push(format!(
+                    "Board {}: the sum of values in the unvisited cells is {} ({}), ending with the dice {}.",
+                    index + 1,
+                    report.unvisited_sum,
+                    report.moves_as_letters(),
+                    report.final_dice()
+                ));
+                total += report.unvisited_sum as i64;
+            }
+            Solution::NotFound(reason, ..) => {
+                lines.push(format!(
+                    "Board {}: no solution found, as {}, so the campaign stops after {} of {} boards.",
+                    index + 1,
+                    reason,
+                    index,
+                    board_count
+                ));
+                return Some(lines.join("\n"));
+            }
+        }
+    }
+
+    lines.push(format!(
+        "The sums of values in the unvisited cells of the {} boards total {}.",
+        board_count, total
+    ));
+    Some(lines.join("\n"))
+}
+
+/// Gathers the statistics of the solutions of the board, stopping once the bound is reached.
+/// Returns them with the number of solutions they cover.
+fn gather_stats_across_solutions(builder: &SolverBuilder, bound: u64) -> (MoveStats, usize) {
+    let mut stats = MoveStats::default();
+    let mut count = 0;
+    for solution in build_solver(builder).find_solutions(bound as usize) {
+        if let Solution::Found(report) = solution {
+            stats.merge(&MoveStats::from_moves(&report.moves_as_letters()));
+            count += 1;
+        }
+    }
+
+    (stats, count)
+}
+
+fn describe_stats_across_all_solutions(builder: &SolverBuilder, bound: u64) -> String {
+    let (stats, count) = gather_stats_across_solutions(builder, bound);
+    if count as u64 == bound {
+        format!(
+            "Across the first {} solution(s) of the board, as gathering stopped at the bound:\n{}",
+            count,
+            stats.describe()
+        )
+    } else {
+        format!(
+            "Across the {} solution(s) of the board:\n{}",
+            count,
+            stats.describe()
+        )
+    }
+}
+
+fn check_unique(builder: &SolverBuilder, board: &Board, bound: u64, threads: Option<u64>) {
+    let bound = bound as usize;
+    let make_solver = || build_solver(builder);
+    let solutions = match threads {
+        Some(threads) => match find_solutions_in_parallel(make_solver, bound, threads as usize) {
+            Ok(solutions) => solutions,
+            Err(_) => {
+                eprintln!("The solver failed unexpectedly.");
+                std::process::exit(1);
+            }
+        },
+        None => make_solver().find_solutions(bound),
+    };
+    match solutions.len() {
+        count if count == bound => println!("The board has at least {} solution(s).", count),
+        0 => println!("The board has no solution."),
+        1 => println!("The board has exactly one solution."),
+        count => println!("The board has {} solutions.", count),
+    }
+
+    if let Some(Solution::Found(report)) = solutions.get(1) {
+        println!(
+            "Here is a second solution, where the sum of values in the unvisited cells is {}.",
+            report.unvisited_sum
+        );
+        println!(
+            "Its signature is {}.",
+            compute_signature(board, &report.moves_as_letters())
+        );
+        println!("{}", report);
+    }
+}
+
+fn why_forced(builder: &SolverBuilder) {
+    match build_solver(builder).explain_forced_moves() {
+        Ok(explanation) => println!("{}", explanation),
+        Err(error) => {
+            println!("Cannot explain why the solution is forced, as {}.", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn analyze(
+    builder: &SolverBuilder,
+    board: &Board,
+    what_if: &[CellEdit],
+    bound: u64,
+    notation: Notation,
+) {
+    match analyze_what_if(builder, board, what_if, bound as usize, notation) {
+        Ok(table) => println!("{}", table),
+        Err(error) => {
+            eprintln!("Cannot analyze the edits: {}.", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn endpoints_matrix(
+    builder: &SolverBuilder,
+    board: &Board,
+    max_depth: Option<i16>,
+    notation: Notation,
+) {
+    let cells = board.rows() * board.cols();
+    let max_depth = max_depth.unwrap_or(cells.min(i16::MAX as usize) as i16);
+    println!(
+        "{}",
+        compute_endpoints_matrix(builder, board, max_depth, notation)
+    );
+}
+
+fn dead_ends(builder: &SolverBuilder, board: &Board, svg: bool) {
+    let depths = build_solver(builder).map_dead_ends();
+    if svg {
+        println!("{}", draw_dead_end_map_svg(board, &depths));
+    } else {
+        println!("{}", draw_dead_end_map(board, &depths));
+    }
+}
+
+fn try_starts(
+    builder: &SolverBuilder,
+    board: &Board,
+    mut cells: Vec<Position>,
+    notation: Notation,
+) {
+    if cells.is_empty() {
+        cells = board.positions().collect();
+    }
+
+    // Start cells are solved on a thread per available core.
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let solutions = solve_each_in_parallel(
+        &cells,
+        |&position| builder.clone().build()?.set_start(position),
+        threads,
+    );
+    for (position, solution) in cells.into_iter().zip(solutions) {
+        match solution {
+            Some(Ok(Solution::Found(report))) => println!(
+                "Starting from {}, the sum of values in the unvisited cells is {}.",
+                notation.format(position),
+                report.unvisited_sum
+            ),
+            Some(Ok(Solution::NotFound(reason, ..))) => {
+                println!(
+                    "Starting from {}, no solution found, as {}.",
+                    notation.format(position),
+                    reason
+                )
+            }
+            Some(Err(_)) => eprintln!(
+                "Starting from {}, the solver failed unexpectedly.",
+                notation.format(position)
+            ),
+            None => println!("{} is not on the board.", notation.format(position)),
+        }
+    }
+}
+
+fn page_solutions(builder: &SolverBuilder, count: usize, state_path: &Path) {
+    let mut solver = if state_path.exists() {
+        let resumed = fs::read_to_string(state_path)
+            .map_err(|error| error.to_string())
+            .and_then(|state| serde_json::from_str(&state).map_err(|error| error.to_string()))
+            .and_then(|state| build_solver(builder).resume_enumeration(&state));
+        match resumed {
+            Ok(solver) => solver,
+            Err(error) => {
+                eprintln!(
+                    "Cannot resume the enumeration from {}: {}",
+                    state_path.display(),
+                    error
+                );
+                std::process::exit(1);
+            }
+        }
+    } else {
+        build_solver(builder)
+    };
+
+    for _ in 0..count {
+        match solver.next_solution() {
+            Some(Solution::Found(report)) => println!(
+                "Solution {}: the sum of values in the unvisited cells is {} ({}).",
+                solver.solutions_enumerated(),
+                report.unvisited_sum,
+                report.moves_as_letters()
+            ),
+            _ => {
+                println!(
+                    "There are no more solutions, {} in total.",
+                    solver.solutions_enumerated()
+                );
+                break;
+            }
+        }
+    }
+
+    if let Err(error) = fs::write(state_path, solver.enumeration_state().to_string()) {
+        eprintln!("Cannot write {}: {}", state_path.display(), error);
+        std::process::exit(1);
+    }
+}
+
+fn fuzz(iterations: u64, size_range: RangeInclusive<usize>, seed: Option<u64>) {
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    match fuzz_solve(iterations, size_range, seed) {
+        Ok(summary) => println!(
+            "Fuzzed {} boards from seed {}: {} solved, {} without solution, and {} rejected.",
+            iterations, seed, summary.solved, summary.unsolved, summary.rejected
+        ),
+        Err(failure) => {
+            println!(
+                "Failure on a {0}x{0} board: {1}. Reproduce it with `die-agony fuzz-solve --iterations 1 --seed {2} --size-range {0}..{0}`.",
+                failure.size, failure.reason, failure.seed
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn teach_solution(builder: &SolverBuilder, board: &Board, notation: Notation) {
+    // Lessons explain the deductions of the original rules only.
+    if *builder.rules() != Rules::default() {
+        eprintln!("Only solutions under the rules of the puzzle can be taught.");
+        std::process::exit(1);
+    }
+    let Solution::Found(report) = build_solver(builder).solve() else {
+        eprintln!("The board has no solution to teach.");
+        std::process::exit(1);
+    };
+    if let Err(error) = teach(
+        board,
+        &report.moves_as_letters(),
+        notation,
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+    ) {
+        eprintln!("Cannot teach the solution: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn verify(
+    builder: &SolverBuilder,
+    board: &Board,
+    signature: Option<&str>,
+    moves: Option<&str>,
+    relative: bool,
+    bound: u64,
+) {
+    let moves = match moves.map(|moves| {
+        let moves = moves.trim().to_ascii_uppercase();
+        match relative {
+            true => to_absolute_moves(&moves),
+            false => Ok(moves),
+        }
+    }) {
+        Some(Ok(moves)) => Some(moves),
+        Some(Err(error)) => {
+            eprintln!("Cannot read the moves: {}", error);
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    let solver = build_solver(builder);
+    // Moves are replayed as is, while a signature can only be matched by enumerating solutions,
+    // until one of them matches or the bound is reached.
+    let mut enumerated = 0;
+    let matching_report = match (&moves, signature) {
+        (Some(moves), _) => solver.replay(moves),
+        (None, Some(signature)) => {
+            let mut solver = solver;
+            std::iter::from_fn(|| solver.next_solution())
+                .take(bound as usize)
+                .inspect(|_| enumerated += 1)
+                .find_map(|solution| match solution {
+                    Solution::Found(report)
+                        if compute_signature(board, &report.moves_as_letters())
+                            .eq_ignore_ascii_case(signature.trim()) =>
+                    {
+                        Some(report)
+                    }
+                    _ => None,
+                })
+        }
+        (None, None) => None,
+    };
+
+    let (matching, not_matching) = match moves {
+        Some(_) => ("moves form", "moves do not form"),
+        None => ("signature matches", "signature does not match"),
+    };
+    match matching_report {
+        Some(report) => println!(
+            "The {} a solution, where the sum of values in the unvisited cells is {}.",
+            matching, report.unvisited_sum
+        ),
+        None if moves.is_none() && enumerated == bound => {
+            println!(
+                "The {} any of the first {} solution(s) of the board, after which the check gave \
+                 up.",
+                not_matching, bound
+            );
+            std::process::exit(1);
+        }
+        None => {
+            println!("The {} any solution of the board.", not_matching);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn convert_moves(moves: &str, to: MoveNotationArg) {
+    let moves = moves.trim().to_ascii_uppercase();
+    let converted = match to {
+        MoveNotationArg::Absolute => to_absolute_moves(&moves),
+        MoveNotationArg::Relative => to_relative_moves(&moves),
+    };
+    match converted {
+        Ok(converted) => println!("{}", converted),
+        Err(error) => {
+            eprintln!("Cannot convert the moves: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn decode(link: &str) {
+    let (board, moves) = match decode_share_link(link) {
+        Ok(decoded) => decoded,
+        Err(error) => {
+            eprintln!("Cannot decode the share link: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    println!("The board is:");
+    for row in 0..board.rows() {
+        let values: Vec<String> = (0..board.cols())
+            .map(|col| format!("{:>5}", board.get_cell_at((row, col)).unwrap().get_value()))
+            .collect();
+        println!("{}", values.join(" "));
+    }
+    println!(
+        "The end cell is {}, and the dice moves with the `{}` model.",
+        Notation::RowCol.format(board.end()),
+        board.movement().name()
+    );
+    match moves {
+        Some(moves) => println!(
+            "The solution moves {}, and its signature is {}.",
+            moves,
+            compute_signature(&board, &moves)
+        ),
+        None => println!("There is no solution in the share link."),
+    }
+}
+
+fn search_replay(
+    path: &PathBuf,
+    animate: bool,
+    first_reached: Option<Position>,
+    alive_at_depth: Option<i16>,
+    notation: Notation,
+) {
+    let log = match fs::read(path)
+        .map_err(|error| error.to_string())
+        .and_then(|bytes| SearchLog::parse(&bytes))
+    {
+        Ok(log) => log,
+        Err(error) => {
+            eprintln!(
+                "Cannot read the search log in {}: {}",
+                path.display(),
+                error
+            );
+            std::process::exit(1);
+        }
+    };
+
+    if animate {
+        for (index, record) in log.records().iter().enumerate() {
+            match record {
+                Record::Generated {
+                    step,
+                    previous,
+                    turn,
+                    position,
+                    top,
+                } => println!(
+                    "#{}: step {} reaches {} on turn {} with top {}, from step {}.",
+                    index,
+                    step,
+                    notation.format(*position),
+                    turn,
+                    top.map_or("?".to_string(), |top| top.to_string()),
+                    previous.map_or("-".to_string(), |previous| previous.to_string())
+                ),
+                Record::Expanded { step } | Record::Pruned { step } => {
+                    let action = if matches!(record, Record::Expanded { .. }) {
+                        "expanded"
+                    } else {
+                        "pruned"
+                    };
+                    match log.generation_of(*step) {
+                        Some(Record::Generated { turn, position, .. }) => println!(
+                            "#{}: step {}, at {} on turn {}, is {}.",
+                            index,
+                            step,
+                            notation.format(*position),
+                            turn,
+                            action
+                        ),
+                        _ => println!("#{}: step {} is {}.", index, step, action),
+                    }
+                }
+                Record::Truncated => println!("#{}: the log was truncated here.", index),
             }
         }
-        Solution::NotFound => println!("Oops, no solution found."),
+    }
+
+    let (expanded, pruned) = log.count_expanded_and_pruned();
+    println!(
+        "The log holds {} records: {} steps expanded and {} pruned.",
+        log.records().len(),
+        expanded,
+        pruned
+    );
+    if log.is_truncated() {
+        println!("The log was truncated, so the search went on past its last record.");
+    }
+
+    if let Some(position) = first_reached {
+        match log.first_reaching(position) {
+            Some((index, Record::Generated { step, turn, .. })) => println!(
+                "{} was first reached by step {} on turn {}, at record #{}.",
+                notation.format(position),
+                step,
+                turn,
+                index
+            ),
+            _ => println!("{} was never reached.", notation.format(position)),
+        }
+    }
+
+    if let Some(depth) = alive_at_depth {
+        println!(
+            "{} journeys reached depth {}.",
+            log.count_alive_at_depth(depth),
+            depth
+        );
     }
 }
@@ -0,0 +1,176 @@
+use crate::dice::{Dice, Face};
+
+/// Shape of an unfolded dice, as seen from above with the top of the board facing up. `#` marks
+/// the squares holding a face, which are, row by row: the front, then the left, top and right,
+/// then the back, and finally the bottom, folded under the back.
+const NET_SHAPE: [&str; 4] = [".#.", "###", ".#.", ".#."];
+
+/// Parses a dice from its unfolded net. The net is a block of whitespace-separated tokens laid out
+/// like `NET_SHAPE`, where `.` marks an empty square, and each face holds either an integer or
+/// `?` when its value is unknown. For instance:
+///
+/// ```text
+/// .  -9  .
+/// 9   9  -3
+/// .   5  .
+/// .   7  .
+/// ```
+pub fn parse_net(net: &str) -> Result<Dice, String> {
+    let rows: Vec<Vec<&str>> = net
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|tokens| !tokens.is_empty())
+        .collect();
+    if rows.len() != NET_SHAPE.len() || rows.iter().any(|tokens| tokens.len() != 3) {
+        return Err(format!(
+            "a dice net must have {} rows of 3 squares, shaped as `{}`",
+            NET_SHAPE.len(),
+            NET_SHAPE.join(" / ")
+        ));
+    }
+
+    let mut faces = Vec::with_capacity(6);
+    for (row, (tokens, shape)) in rows.iter().zip(NET_SHAPE).enumerate() {
+        for (col, (token, square)) in tokens.iter().zip(shape.chars()).enumerate() {
+            match (square, *token) {
+                ('.', ".") => {}
+                ('.', _) => {
+                    return Err(format!(
+                        "row {} column {} of the dice net must be empty, but holds `{}`",
+                        row + 1,
+                        col + 1,
+                        token
+                    ))
+                }
+                (_, ".") => {
+                    return Err(format!(
+                        "row {} column {} of the dice net must hold a face",
+                        row + 1,
+                        col + 1
+                    ))
+                }
+                (_, "?") => faces.push(None),
+                (_, value) => faces.push(Some(
+                    value
+                        .parse::<i16>()
+                        .map_err(|_| format!("`{}` is not a valid face value", value))?,
+                )),
+            }
+        }
+    }
+
+    // Faces were read row by row: front, left, top, right, back and bottom.
+    let net_faces = [
+        Face::Front,
+        Face::Left,
+        Face::Top,
+        Face::Right,
+        Face::Back,
+        Face::Bottom,
+    ];
+    Ok(net_faces
+        .into_iter()
+        .zip(faces)
+        .fold(Dice::default(), |dice, (face, value)| match value {
+            Some(value) => dice.set_face(face, value),
+            None => dice,
+        }))
+}
+
+/// Parses a dice from the way people read a die sitting on a table: a comma-separated list of
+/// `side=value` pairs, where the side is `top` or `bottom`, or the compass direction it faces,
+/// `north` being toward the top of the board and `east` toward its right. Sides which are not
+/// listed are unknown. For instance, `top=1,north=5,east=3`.
+pub fn parse_physical(description: &str) -> Result<Dice, String> {
+    let mut dice = Dice::default();
+    let mut described = Vec::new();
+    for pair in description.split(',').map(str::trim) {
+        let (side, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` is not a `side=value` pair", pair))?;
+        let face = match side.trim().to_ascii_lowercase().as_str() {
+            "top" => Face::Top,
+            "bottom" => Face::Bottom,
+            "north" => Face::Front,
+            "south" => Face::Back,
+            "east" => Face::Right,
+            "west" => Face::Left,
+            _ => {
+                return Err(format!(
+                    "`{}` is not a side, which is one of top, bottom, north, south, east and west",
+                    side.trim()
+                ))
+            }
+        };
+        if described.contains(&face) {
+            return Err(format!("the {} side is described twice", side.trim()));
+        }
+        described.push(face);
+
+        match value.trim() {
+            "?" => {}
+            value => {
+                let value = value
+                    .parse::<i16>()
+                    .map_err(|_| format!("`{}` is not a valid face value", value))?;
+                dice = dice.set_face(face, value);
+            }
+        }
+    }
+
+    Ok(dice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_net_places_each_face() {
+        let dice = parse_net(
+            "
+            .  -9  .
+            9   ?  -3
+            .   5  .
+            .   7  .
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            dice,
+            Dice::from_sides([None, Some(7), Some(9), Some(-3), Some(-9), Some(5)])
+        );
+    }
+
+    #[test]
+    fn parse_net_rejects_other_shapes() {
+        assert!(parse_net(". 1 .\n2 3 4\n. 5 .").is_err());
+        assert!(parse_net(". 1 .\n2 3 4\n. 5 .\n6 . .").is_err());
+        assert!(parse_net(". 1 .\n2 3 4\n. 5 .\n. . .").is_err());
+        assert!(parse_net(". 1 .\n2 3 4 5\n. 5 .\n. 6 .").is_err());
+        assert!(parse_net(". 1 .\n2 x 4\n. 5 .\n. 6 .").is_err());
+    }
+
+    #[test]
+    fn parse_physical_maps_compass_directions_to_sides() {
+        let dice = parse_physical("top=1, north=5,east=3,South=?").unwrap();
+        assert_eq!(
+            dice,
+            Dice::from_sides([Some(1), None, None, Some(3), Some(5), None])
+        );
+        assert_eq!(
+            parse_physical("bottom=6,west=4,south=2").unwrap(),
+            Dice::from_sides([None, Some(6), Some(4), None, None, Some(2)])
+        );
+    }
+
+    #[test]
+    fn parse_physical_rejects_invalid_descriptions() {
+        assert!(parse_physical("").is_err());
+        assert!(parse_physical("top").is_err());
+        assert!(parse_physical("up=1").is_err());
+        assert!(parse_physical("top=x").is_err());
+        assert!(parse_physical("top=1,top=2").is_err());
+    }
+}
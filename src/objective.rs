@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+/// Enumerates the criteria on which a solution can be judged. For all of them, lower is better.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Criterion {
+    /// Number of turns taken to reach the end cell.
+    Turns,
+    /// Sum of values in the unvisited cells.
+    UnvisitedSum,
+    /// Moves of the solution written as a string (e.g. `URRD`), compared lexicographically.
+    Moves,
+}
+
+/// Ordered list of criteria making a solution better than another. Solutions are compared on the
+/// first criterion, and ties are broken by the following ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Objective {
+    criteria: Vec<Criterion>,
+}
+
+/// Holds everything a solution can be judged on.
+#[derive(Debug)]
+pub(crate) struct SolutionKey {
+    pub turns: i16,
+    pub unvisited_sum: i32,
+    pub moves: String,
+}
+
+impl Objective {
+    pub fn new(criteria: Vec<Criterion>) -> Self {
+        Self { criteria }
+    }
+
+    /// Compares two solutions, `Ordering::Less` meaning that the first one is better.
+    pub(crate) fn compare(&self, a: &SolutionKey, b: &SolutionKey) -> Ordering {
+        self.criteria
+            .iter()
+            .map(|criterion| match criterion {
+                Criterion::Turns => a.turns.cmp(&b.turns),
+                Criterion::UnvisitedSum => a.unvisited_sum.cmp(&b.unvisited_sum),
+                Criterion::Moves => a.moves.cmp(&b.moves),
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Returns whether a journey that cannot reach the end cell in fewer than `min_turns` turns
+    /// can be discarded, given the best solution found so far.
+    pub(crate) fn can_prune(&self, min_turns: i16, best: &SolutionKey) -> bool {
+        match self.criteria.first() {
+            // If turns are the only criterion, a tie cannot improve on the best solution.
+            Some(Criterion::Turns) if self.criteria.len() == 1 => min_turns >= best.turns,
+            Some(Criterion::Turns) => min_turns > best.turns,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_key(turns: i16, unvisited_sum: i32, moves: &str) -> SolutionKey {
+        SolutionKey {
+            turns,
+            unvisited_sum,
+            moves: moves.to_string(),
+        }
+    }
+
+    #[test]
+    fn compare_breaks_ties_in_order() {
+        let objective = Objective::new(vec![
+            Criterion::Turns,
+            Criterion::UnvisitedSum,
+            Criterion::Moves,
+        ]);
+
+        let a = create_key(4, 100, "URUR");
+        assert_eq!(
+            objective.compare(&a, &create_key(6, 10, "UURR")),
+            Ordering::Less
+        );
+        assert_eq!(
+            objective.compare(&a, &create_key(4, 10, "UURR")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            objective.compare(&a, &create_key(4, 100, "UURR")),
+            Ordering::Less
+        );
+        assert_eq!(
+            objective.compare(&a, &create_key(4, 100, "URUR")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn compare_ignores_unlisted_criteria() {
+        let objective = Objective::new(vec![Criterion::UnvisitedSum]);
+        assert_eq!(
+            objective.compare(&create_key(4, 100, "URUR"), &create_key(8, 100, "LLLL")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn can_prune_only_when_turns_come_first() {
+        let best = create_key(4, 100, "URUR");
+
+        let objective = Objective::new(vec![Criterion::Turns]);
+        assert!(objective.can_prune(4, &best));
+        assert!(!objective.can_prune(3, &best));
+
+        let objective = Objective::new(vec![Criterion::Turns, Criterion::UnvisitedSum]);
+        assert!(!objective.can_prune(4, &best));
+        assert!(objective.can_prune(5, &best));
+
+        let objective = Objective::new(vec![Criterion::UnvisitedSum, Criterion::Turns]);
+        assert!(!objective.can_prune(100, &best));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::board::{Notation, Position};
+
+/// Enumerates the metrics the journey coming closest to a solution can be chosen on, when the
+/// search finds none.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PartialCredit {
+    /// Fewest movements left to reach the end cell, ignoring the rules.
+    ClosestToEnd,
+    /// Most distinct cells visited.
+    MostCells,
+    /// Highest score reached.
+    HighestScore,
+}
+
+/// Journey explored by a search which found no solution, coming the closest to one on some
+/// metric.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialJourney {
+    /// Moves of the journey written as a string (e.g. `URRD`).
+    pub moves: String,
+    /// Cell the journey ends on.
+    pub position: Position,
+    /// Fewest movements left from there to the end cell.
+    pub distance_to_end: usize,
+    /// Distinct cells visited, including the start cell.
+    pub cells_visited: usize,
+    /// Score once the dice lands on the last cell.
+    pub score: i32,
+}
+
+impl PartialCredit {
+    /// Returns whether the first journey comes closer to a solution than the second one on this
+    /// metric. Ties are broken by the other metrics, in the order they are enumerated.
+    pub fn prefers(self, a: &PartialJourney, b: &PartialJourney) -> bool {
+        let closest = b.distance_to_end.cmp(&a.distance_to_end);
+        let most_cells = a.cells_visited.cmp(&b.cells_visited);
+        let highest_score = a.score.cmp(&b.score);
+        let orderings = match self {
+            Self::ClosestToEnd => [closest, most_cells, highest_score],
+            Self::MostCells => [most_cells, closest, highest_score],
+            Self::HighestScore => [highest_score, closest, most_cells],
+        };
+
+        orderings
+            .into_iter()
+            .find(|ordering| ordering.is_ne())
+            .is_some_and(|ordering| ordering.is_gt())
+    }
+}
+
+impl PartialJourney {
+    /// Describes the journey in a sentence, with its last cell in the given notation.
+    pub fn describe(&self, notation: Notation) -> String {
+        let moves = match self.moves.as_str() {
+            "" => "without moving".to_string(),
+            moves => format!("moving {}", moves),
+        };
+        format!(
+            "The closest journey, {}, ends on {} after {} turn(s), {} movement(s) away from the end \
+             cell, having visited {} cell(s) and scored {}.",
+            moves,
+            notation.format(self.position),
+            self.moves.len(),
+            self.distance_to_end,
+            self.cells_visited,
+            self.score
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn prefers_on_the_metric_then_the_others() {
+        let near = PartialJourney {
+            moves: "URRD".to_string(),
+            position: (4, 2),
+            distance_to_end: 6,
+            cells_visited: 5,
+            score: 32,
+        };
+        let long = PartialJourney {
+            moves: "UUUUUDDDD".to_string(),
+            position: (4, 0),
+            distance_to_end: 9,
+            cells_visited: 6,
+            score: 32,
+        };
+
+        assert!(PartialCredit::ClosestToEnd.prefers(&near, &long));
+        assert!(PartialCredit::MostCells.prefers(&long, &near));
+        // Both scored the same, so the journey closest to the end cell wins.
+        assert!(PartialCredit::HighestScore.prefers(&near, &long));
+        assert!(!PartialCredit::HighestScore.prefers(&near, &near));
+    }
+
+    #[test]
+    fn describe_lists_the_metrics() {
+        let journey = PartialJourney {
+            moves: String::new(),
+            position: (5, 0),
+            distance_to_end: 10,
+            cells_visited: 1,
+            score: 0,
+        };
+        assert_eq!(
+            journey.describe(Board::new().chess_notation()),
+            "The closest journey, without moving, ends on a1 after 0 turn(s), 10 movement(s) away \
+             from the end cell, having visited 1 cell(s) and scored 0."
+        );
+    }
+}
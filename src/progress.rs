@@ -0,0 +1,139 @@
+use crate::solver::SearchStats;
+
+use std::time::{Duration, Instant};
+
+/// How many expansions go by between two readings of the clock, when progress is reported
+/// periodically. Reading the clock is cheap, but not free on the hot path of the search.
+const CLOCK_READING_PERIOD: u64 = 64;
+
+/// Enumerates how often the progress of a search can be reported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProgressInterval {
+    /// Every given number of steps expanded.
+    Nodes(u64),
+    /// Every given duration, give or take a few expansions.
+    Time(Duration),
+}
+
+/// Snapshot of the progress of a search.
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressReport {
+    /// Counters of the work done so far.
+    pub stats: SearchStats,
+    /// Steps waiting to be explored.
+    pub frontier: usize,
+    /// Time elapsed since the reporter was created.
+    pub elapsed: Duration,
+}
+
+/// Calls a callback with the progress of a search at a configured interval, so that embedders such
+/// as GUIs get regular updates, without the search paying for a callback on every expansion.
+pub struct ProgressReporter {
+    interval: ProgressInterval,
+    callback: Box<dyn FnMut(&ProgressReport) + Send>,
+    start: Instant,
+    /// Steps expanded when progress was last reported.
+    last_expanded: u64,
+    /// Time elapsed when progress was last reported.
+    last_elapsed: Duration,
+}
+
+impl ProgressReporter {
+    pub fn new(
+        interval: ProgressInterval,
+        callback: impl FnMut(&ProgressReport) + Send + 'static,
+    ) -> Self {
+        Self {
+            interval,
+            callback: Box::new(callback),
+            start: Instant::now(),
+            last_expanded: 0,
+            last_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Takes note of an expansion, reporting the progress of the search if it is due.
+    pub fn observe(&mut self, stats: SearchStats, frontier: usize) {
+        let elapsed = match self.interval {
+            ProgressInterval::Nodes(nodes) => {
+                if stats.expanded < self.last_expanded.saturating_add(nodes.max(1)) {
+                    return;
+                }
+                self.start.elapsed()
+            }
+            ProgressInterval::Time(period) => {
+                if !stats.expanded.is_multiple_of(CLOCK_READING_PERIOD) {
+                    return;
+                }
+                let elapsed = self.start.elapsed();
+                if elapsed < self.last_elapsed + period {
+                    return;
+                }
+                elapsed
+            }
+        };
+
+        self.last_expanded = stats.expanded;
+        self.last_elapsed = elapsed;
+        (self.callback)(&ProgressReport {
+            stats,
+            frontier,
+            elapsed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    fn stats(expanded: u64) -> SearchStats {
+        SearchStats {
+            expanded,
+            ..SearchStats::default()
+        }
+    }
+
+    #[test]
+    fn reports_every_n_nodes() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let mut reporter = ProgressReporter::new(ProgressInterval::Nodes(10), {
+            let reported = reported.clone();
+            move |report| reported.lock().unwrap().push(report.stats.expanded)
+        });
+        for expanded in 1..=35 {
+            reporter.observe(stats(expanded), 0);
+        }
+
+        assert_eq!(*reported.lock().unwrap(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn reports_at_most_once_per_period() {
+        let reported = Arc::new(Mutex::new(0));
+        let mut reporter =
+            ProgressReporter::new(ProgressInterval::Time(Duration::from_secs(3600)), {
+                let reported = reported.clone();
+                move |_| *reported.lock().unwrap() += 1
+            });
+        for expanded in 1..=1000 {
+            reporter.observe(stats(expanded), 0);
+        }
+        assert_eq!(*reported.lock().unwrap(), 0);
+
+        let mut reporter = ProgressReporter::new(ProgressInterval::Time(Duration::ZERO), {
+            let reported = reported.clone();
+            move |_| *reported.lock().unwrap() += 1
+        });
+        for expanded in 1..=1000 {
+            reporter.observe(stats(expanded), 0);
+        }
+        // The clock is only read every so often.
+        assert_eq!(
+            *reported.lock().unwrap(),
+            1000 / CLOCK_READING_PERIOD as usize
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Compact binary log of a search, recording every step generated, expanded or pruned, so that the
+//! search can be analyzed offline without running it again.
+//!
+//! The log starts with the `DASL` magic bytes and a version byte, followed by records, each starting
+//! with a tag byte. Integers are big-endian.
+//! - `G` (generated): step (u32), previous step (u32, `u32::MAX` for the first step), turn (u16),
+//!   row (u8), column (u8), top of the dice (i16, `i16::MIN` if unknown).
+//! - `E` (expanded): step (u32).
+//! - `P` (pruned): step (u32).
+//! - `T` (truncated): the size limit was hit, and no more records follow.
+
+use crate::board::Position;
+
+use std::io::Write;
+
+const MAGIC: &[u8; 4] = b"DASL";
+const VERSION: u8 = 1;
+
+const GENERATED: u8 = b'G';
+const EXPANDED: u8 = b'E';
+const PRUNED: u8 = b'P';
+const TRUNCATED: u8 = b'T';
+
+/// Writes the log of a search, until it reaches a size limit.
+pub struct SearchRecorder {
+    writer: Box<dyn Write + Send>,
+    /// Maximum size of the log, in bytes.
+    limit: u64,
+    written: u64,
+    truncated: bool,
+}
+
+impl SearchRecorder {
+    /// Starts a log on the given writer, which will not grow past `limit` bytes.
+    pub fn new(writer: Box<dyn Write + Send>, limit: u64) -> Self {
+        let mut recorder = Self {
+            writer,
+            limit,
+            written: 0,
+            truncated: false,
+        };
+        let mut header = [0; 5];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        recorder.write(&header);
+
+        recorder
+    }
+
+    /// Records a step created by the search.
+    pub fn generated(
+        &mut self,
+        step: usize,
+        previous: Option<usize>,
+        turn: i16,
+        (row, col): Position,
+        top: Option<i16>,
+    ) {
+        let mut record = [0; 15];
+        record[0] = GENERATED;
+        record[1..5].copy_from_slice(&(step as u32).to_be_bytes());
+        record[5..9].copy_from_slice(&previous.map_or(u32::MAX, |step| step as u32).to_be_bytes());
+        record[9..11].copy_from_slice(&(turn as u16).to_be_bytes());
+        record[11] = row as u8;
+        record[12] = col as u8;
+        record[13..15].copy_from_slice(&top.unwrap_or(i16::MIN).to_be_bytes());
+        self.write(&record);
+    }
+
+    /// Records a step popped from the frontier and expanded.
+    pub fn expanded(&mut self, step: usize) {
+        self.write_step(EXPANDED, step);
+    }
+
+    /// Records a step discarded without expansion.
+    pub fn pruned(&mut self, step: usize) {
+        self.write_step(PRUNED, step);
+    }
+
+    /// Writes any buffered record.
+    pub fn flush(&mut self) {
+        let _ = self.writer.flush();
+    }
+
+    fn write_step(&mut self, tag: u8, step: usize) {
+        let mut record = [0; 5];
+        record[0] = tag;
+        record[1..].copy_from_slice(&(step as u32).to_be_bytes());
+        self.write(&record);
+    }
+
+    fn write(&mut self, record: &[u8]) {
+        if self.truncated {
+            return;
+        }
+
+        // Keep room for the truncation marker.
+        if self.written + record.len() as u64 + 1 > self.limit {
+            self.truncated = true;
+            let _ = self.writer.write_all(&[TRUNCATED]);
+            return;
+        }
+
+        // The log is best effort: failing to write it must not interrupt the search.
+        if self.writer.write_all(record).is_ok() {
+            self.written += record.len() as u64;
+        }
+    }
+}
+
+/// A record of a search log.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Record {
+    Generated {
+        step: usize,
+        previous: Option<usize>,
+        turn: i16,
+        position: Position,
+        top: Option<i16>,
+    },
+    Expanded {
+        step: usize,
+    },
+    Pruned {
+        step: usize,
+    },
+    /// The log was cut short by its size limit.
+    Truncated,
+}
+
+/// A search log read back, to answer questions about the search without running it again.
+pub struct SearchLog {
+    records: Vec<Record>,
+    /// For each step, the record that generated it, if it was logged.
+    generated_by: Vec<Option<usize>>,
+}
+
+impl SearchLog {
+    /// Parses a log written by a `SearchRecorder`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let bytes = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or("this is not a search log")?;
+        let (&version, mut bytes) = bytes.split_first().ok_or("the search log is truncated")?;
+        if version != VERSION {
+            return Err(format!("unsupported search log version {}", version));
+        }
+
+        let mut records = Vec::new();
+        let mut generated_by = Vec::new();
+        while let Some((&tag, rest)) = bytes.split_first() {
+            let length = match tag {
+                GENERATED => 14,
+                EXPANDED | PRUNED => 4,
+                TRUNCATED => 0,
+                _ => return Err(format!("unknown record `{}`", tag as char)),
+            };
+            let (fields, rest) = rest
+                .split_at_checked(length)
+                .ok_or("the last record of the search log is truncated")?;
+            let u32_at = |i: usize| {
+                u32::from_be_bytes([fields[i], fields[i + 1], fields[i + 2], fields[i + 3]])
+            };
+            let record = match tag {
+                GENERATED => {
+                    let step = u32_at(0) as usize;
+                    let top = i16::from_be_bytes([fields[12], fields[13]]);
+                    if generated_by.len() <= step {
+                        generated_by.resize(step + 1, None);
+                    }
+                    generated_by[step] = Some(records.len());
+                    Record::Generated {
+                        step,
+                        previous: Some(u32_at(4))
+                            .filter(|&previous| previous != u32::MAX)
+                            .map(|previous| previous as usize),
+                        turn: u16::from_be_bytes([fields[8], fields[9]]) as i16,
+                        position: (fields[10] as usize, fields[11] as usize),
+                        top: (top != i16::MIN).then_some(top),
+                    }
+                }
+                EXPANDED => Record::Expanded {
+                    step: u32_at(0) as usize,
+                },
+                PRUNED => Record::Pruned {
+                    step: u32_at(0) as usize,
+                },
+                _ => Record::Truncated,
+            };
+            records.push(record);
+            bytes = rest;
+        }
+
+        Ok(Self {
+            records,
+            generated_by,
+        })
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Returns whether the log was cut short by its size limit.
+    pub fn is_truncated(&self) -> bool {
+        self.records.last() == Some(&Record::Truncated)
+    }
+
+    /// Returns the record that generated the given step, if it was logged.
+    pub fn generation_of(&self, step: usize) -> Option<&Record> {
+        self.generated_by
+            .get(step)
+            .copied()
+            .flatten()
+            .map(|index| &self.records[index])
+    }
+
+    /// Returns the index of the first record reaching the given position, along with that record.
+    pub fn first_reaching(&self, position: Position) -> Option<(usize, &Record)> {
+        self.records.iter().enumerate().find(|(_, record)| {
+            matches!(record, Record::Generated { position: reached, .. } if *reached == position)
+        })
+    }
+
+    /// Counts the journeys which reached the given depth, i.e. lasted that many turns.
+    pub fn count_alive_at_depth(&self, depth: i16) -> usize {
+        self.records
+            .iter()
+            .filter(|record| matches!(record, Record::Generated { turn, .. } if *turn == depth))
+            .count()
+    }
+
+    /// Counts the steps which were expanded, and the ones which were pruned.
+    pub fn count_expanded_and_pruned(&self) -> (usize, usize) {
+        self.records
+            .iter()
+            .fold((0, 0), |(expanded, pruned), record| match record {
+                Record::Expanded { .. } => (expanded + 1, pruned),
+                Record::Pruned { .. } => (expanded, pruned + 1),
+                _ => (expanded, pruned),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    /// Writer keeping what is written in memory, even after the recorder is done with it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn recorder_writes_records() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = SearchRecorder::new(Box::new(buffer.clone()), 1024);
+        recorder.generated(0, None, 0, (5, 0), None);
+        recorder.expanded(0);
+        recorder.generated(1, Some(0), 1, (4, 0), Some(5));
+        recorder.pruned(1);
+
+        let log = buffer.0.lock().unwrap();
+        assert_eq!(&log[..5], b"DASL\x01");
+        assert_eq!(
+            &log[5..20],
+            &[b'G', 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 5, 0, 0x80, 0]
+        );
+        assert_eq!(&log[20..25], &[b'E', 0, 0, 0, 0]);
+        assert_eq!(
+            &log[25..40],
+            &[b'G', 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 4, 0, 0, 5]
+        );
+        assert_eq!(&log[40..], &[b'P', 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn recorder_stops_at_the_limit() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = SearchRecorder::new(Box::new(buffer.clone()), 16);
+        recorder.expanded(0);
+        recorder.expanded(1);
+        recorder.expanded(2);
+
+        let log = buffer.0.lock().unwrap();
+        // The header and two records fit, along with the truncation marker.
+        assert_eq!(log.len(), 5 + 5 + 5 + 1);
+        assert_eq!(log[log.len() - 1], b'T');
+    }
+
+    #[test]
+    fn search_log_answers_queries() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = SearchRecorder::new(Box::new(buffer.clone()), 1024);
+        recorder.generated(0, None, 0, (5, 0), None);
+        recorder.expanded(0);
+        recorder.generated(1, Some(0), 1, (4, 0), Some(5));
+        recorder.generated(2, Some(0), 1, (5, 1), Some(-2));
+        recorder.pruned(2);
+        recorder.expanded(1);
+        recorder.generated(3, Some(1), 2, (4, 1), Some(3));
+
+        let log = SearchLog::parse(&buffer.0.lock().unwrap()).unwrap();
+        assert_eq!(log.records().len(), 7);
+        assert!(!log.is_truncated());
+        assert_eq!(
+            log.generation_of(2),
+            Some(&Record::Generated {
+                step: 2,
+                previous: Some(0),
+                turn: 1,
+                position: (5, 1),
+                top: Some(-2)
+            })
+        );
+        assert_eq!(log.first_reaching((4, 1)).map(|(index, _)| index), Some(6));
+        assert_eq!(log.first_reaching((0, 0)), None);
+        assert_eq!(log.count_alive_at_depth(1), 2);
+        assert_eq!(log.count_alive_at_depth(3), 0);
+        assert_eq!(log.count_expanded_and_pruned(), (2, 1));
+    }
+
+    #[test]
+    fn search_log_rejects_invalid_logs() {
+        assert!(SearchLog::parse(b"").is_err());
+        assert!(SearchLog::parse(b"DASL\x02").is_err());
+        assert!(SearchLog::parse(b"DASL\x01X").is_err());
+        assert!(SearchLog::parse(b"DASL\x01E\x00").is_err());
+
+        let log = SearchLog::parse(b"DASL\x01E\x00\x00\x00\x01T").unwrap();
+        assert!(log.is_truncated());
+    }
+}
@@ -0,0 +1,553 @@
+use crate::{
+    board::{Board, Position},
+    dice::{Dice, Face},
+};
+
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// Width of a square of the text net, which fits any `i16`.
+const NET_SQUARE_WIDTH: usize = 7;
+
+/// Draws the dice in pseudo-3D, as seen from the bottom of the board: the visible faces are its top,
+/// the side facing the bottom of the board, and the side facing the right of the board.
+/// Unknown values are shown as `?`.
+pub fn draw_isometric_dice(dice: &Dice) -> String {
+    let top = format_value(dice.get_face(Face::Top), 9);
+    let front = format_value(dice.get_face(Face::Back), 9);
+    let right = format_value(dice.get_face(Face::Right), 3);
+
+    [
+        "      ┌─────────┐".to_string(),
+        "     ╱         ╱│".to_string(),
+        format!("    ╱{}╱ │", top),
+        "   ╱         ╱  │".to_string(),
+        "  ┌─────────┐   │".to_string(),
+        format!("  │         │{}┘", right),
+        format!("  │{}│  ╱", front),
+        "  │         │ ╱".to_string(),
+        "  └─────────┘╱".to_string(),
+    ]
+    .join("\n")
+}
+
+/// Draws the unfolded net of the dice as text, ready to be cut out and folded. The net is seen
+/// from above with the top of the board facing up: the top face is in the middle, surrounded by
+/// the sides facing each direction, and the bottom face hangs below the back.
+pub fn draw_net(dice: &Dice) -> String {
+    let square = |value| format!("|{}|", format_value(value, NET_SQUARE_WIDTH));
+    let edge = format!("+{}+", "-".repeat(NET_SQUARE_WIDTH));
+    let margin = " ".repeat(NET_SQUARE_WIDTH + 1);
+    let full_edge = format!("+{0}+{0}+{0}+", "-".repeat(NET_SQUARE_WIDTH));
+
+    [
+        format!("{}{}", margin, edge),
+        format!("{}{}", margin, square(dice.get_face(Face::Front))),
+        full_edge.clone(),
+        format!(
+            "|{}|{}|{}|",
+            format_value(dice.get_face(Face::Left), NET_SQUARE_WIDTH),
+            format_value(dice.get_face(Face::Top), NET_SQUARE_WIDTH),
+            format_value(dice.get_face(Face::Right), NET_SQUARE_WIDTH)
+        ),
+        full_edge,
+        format!("{}{}", margin, square(dice.get_face(Face::Back))),
+        format!("{}{}", margin, edge),
+        format!("{}{}", margin, square(dice.get_face(Face::Bottom))),
+        format!("{}{}", margin, edge),
+    ]
+    .join("\n")
+}
+
+/// Draws the same net as `draw_net` as an SVG document. The outline is a solid line to cut along,
+/// and the edges between faces are dashed lines to fold along.
+pub fn draw_net_svg(dice: &Dice) -> String {
+    const SIZE: usize = 60;
+    // Faces of the net, by column and row of their square.
+    let faces = [
+        (1, 0, dice.get_face(Face::Front)),
+        (0, 1, dice.get_face(Face::Left)),
+        (1, 1, dice.get_face(Face::Top)),
+        (2, 1, dice.get_face(Face::Right)),
+        (1, 2, dice.get_face(Face::Back)),
+        (1, 3, dice.get_face(Face::Bottom)),
+    ];
+    // Corners of the outline, in squares, going clockwise from the top left corner of the front face.
+    let outline = [
+        (1, 0),
+        (2, 0),
+        (2, 1),
+        (3, 1),
+        (3, 2),
+        (2, 2),
+        (2, 4),
+        (1, 4),
+        (1, 2),
+        (0, 2),
+        (0, 1),
+        (1, 1),
+    ];
+    // Edges shared by two faces, in squares.
+    let folds = [
+        ((1, 1), (2, 1)),
+        ((1, 1), (1, 2)),
+        ((2, 1), (2, 2)),
+        ((1, 2), (2, 2)),
+        ((1, 3), (2, 3)),
+    ];
+
+    let mut svg = vec![format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="-1 -1 {0} {1}">"#,
+        3 * SIZE + 2,
+        4 * SIZE + 2
+    )];
+    let points: Vec<String> = outline
+        .iter()
+        .map(|(x, y)| format!("{},{}", x * SIZE, y * SIZE))
+        .collect();
+    svg.push(format!(
+        r#"  <polygon points="{}" fill="white" stroke="black"/>"#,
+        points.join(" ")
+    ));
+    for ((x1, y1), (x2, y2)) in folds {
+        svg.push(format!(
+            r#"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-dasharray="4 4"/>"#,
+            x1 * SIZE,
+            y1 * SIZE,
+            x2 * SIZE,
+            y2 * SIZE
+        ));
+    }
+    for (x, y, value) in faces {
+        svg.push(format!(
+            r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="20" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            x * SIZE + SIZE / 2,
+            y * SIZE + SIZE / 2,
+            value.map_or("?".to_string(), |value| value.to_string())
+        ));
+    }
+    svg.push("</svg>".to_string());
+
+    svg.join("\n")
+}
+
+/// Draws the journey of the dice on the board as an SVG document, given the cell visited and the top
+/// of the dice after each turn, starting with the start cell. The path of the journey is drawn over
+/// the board, and the dice sits on its last cell. When animated, the dice rolls from cell to cell
+/// instead, its top updating as it lands, and the animation loops after a pause on the last cell.
+/// The animation uses SMIL, which browsers play without any script.
+pub fn draw_journey_svg(
+    board: &Board,
+    frames: &[(Position, Option<i16>)],
+    animated: bool,
+) -> String {
+    const SIZE: usize = 60;
+    // Duration of each turn of the animation, in seconds.
+    const TURN_DURATION: f64 = 0.5;
+    let center = |(row, col): Position| (col * SIZE + SIZE / 2, row * SIZE + SIZE / 2);
+    // The top of the dice, which is hidden until shown by the given animation, if any.
+    let top_text = |(x, y): (usize, usize), top: Option<i16>, animation: Option<String>| {
+        format!(
+            r#"    <text x="{}" y="{}" font-family="sans-serif" font-size="18" font-weight="bold" text-anchor="middle" dominant-baseline="central"{}>{}{}</text>"#,
+            x,
+            y,
+            if animation.is_some() {
+                r#" visibility="hidden""#
+            } else {
+                ""
+            },
+            top.map_or("?".to_string(), |top| top.to_string()),
+            animation.unwrap_or_default()
+        )
+    };
+
+    let positions: Vec<Position> = board.positions().collect();
+    let width = positions.iter().map(|&(_, col)| col + 1).max().unwrap_or(0) * SIZE;
+    let height = positions.iter().map(|&(row, _)| row + 1).max().unwrap_or(0) * SIZE;
+    let mut svg = vec![format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="-1 -1 {0} {1}">"#,
+        width + 2,
+        height + 2
+    )];
+    for cell in board.cells() {
+        let position = *cell.get_position();
+        let (x, y) = center(position);
+        let annotation = board.annotation(position);
+        svg.push(format!(
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="black"/>"#,
+            x - SIZE / 2,
+            y - SIZE / 2,
+            SIZE,
+            SIZE,
+            annotation
+                .and_then(|annotation| annotation.color.as_deref())
+                .unwrap_or("white")
+        ));
+        svg.push(format!(
+            r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="12" fill="gray">{}</text>"#,
+            x - SIZE / 2 + 4,
+            y - SIZE / 2 + 14,
+            cell.get_value()
+        ));
+        if let Some(label) = board.label(position) {
+            svg.push(format!(
+                r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="9" text-anchor="middle">{}</text>"#,
+                x,
+                y + SIZE / 2 - 4,
+                escape_xml(label)
+            ));
+        }
+    }
+    let Some(&(last_position, last_top)) = frames.last() else {
+        svg.push("</svg>".to_string());
+        return svg.join("\n");
+    };
+
+    let points: Vec<String> = frames
+        .iter()
+        .map(|&(position, _)| {
+            let (x, y) = center(position);
+            format!("{},{}", x, y)
+        })
+        .collect();
+    svg.push(format!(
+        r#"  <polyline points="{}" fill="none" stroke="steelblue" stroke-width="3" stroke-opacity="0.5"/>"#,
+        points.join(" ")
+    ));
+
+    let dice_square = |(x, y): (usize, usize)| {
+        format!(
+            r#"    <rect x="{}" y="{}" width="{2}" height="{2}" rx="6" fill="ivory" stroke="black" stroke-width="2"/>"#,
+            x - SIZE / 3,
+            y - SIZE / 3,
+            2 * SIZE / 3
+        )
+    };
+    if !animated {
+        svg.push("  <g>".to_string());
+        svg.push(dice_square(center(last_position)));
+        svg.push(top_text(center(last_position), last_top, None));
+        svg.push("  </g>".to_string());
+        svg.push("</svg>".to_string());
+        return svg.join("\n");
+    }
+
+    // The dice is drawn on the start cell and translated to the other cells. Frame `i` is shown
+    // from `i / n` to `(i + 1) / n` of the animation, the last frame lasting until its end.
+    let frame_count = frames.len();
+    let key_time = |frame: usize| format!("{:.4}", frame as f64 / frame_count as f64);
+    let duration = format!("{}s", frame_count as f64 * TURN_DURATION);
+    let origin = center(frames[0].0);
+    let translations: Vec<String> = frames
+        .iter()
+        .chain([&frames[frame_count - 1]])
+        .map(|&(position, _)| {
+            let (x, y) = center(position);
+            format!(
+                "{},{}",
+                x as i64 - origin.0 as i64,
+                y as i64 - origin.1 as i64
+            )
+        })
+        .collect();
+
+    let key_times: Vec<String> = (0..frame_count)
+        .map(key_time)
+        .chain(["1".to_string()])
+        .collect();
+    svg.push("  <g>".to_string());
+    svg.push(format!(
+        r#"    <animateTransform attributeName="transform" type="translate" values="{}" keyTimes="{}" dur="{}" repeatCount="indefinite"/>"#,
+        translations.join(";"),
+        key_times.join(";"),
+        duration
+    ));
+    svg.push(dice_square(origin));
+    for (frame, &(_, top)) in frames.iter().enumerate() {
+        let (values, key_times) = match frame {
+            0 => ("visible;hidden".to_string(), format!("0;{}", key_time(1))),
+            frame if frame + 1 == frame_count => (
+                "hidden;visible".to_string(),
+                format!("0;{}", key_time(frame)),
+            ),
+            frame => (
+                "hidden;visible;hidden".to_string(),
+                format!("0;{};{}", key_time(frame), key_time(frame + 1)),
+            ),
+        };
+        let animation = format!(
+            r#"<animate attributeName="visibility" values="{}" keyTimes="{}" calcMode="discrete" dur="{}" repeatCount="indefinite"/>"#,
+            values, key_times, duration
+        );
+        svg.push(top_text(origin, top, Some(animation)));
+    }
+    svg.push("  </g>".to_string());
+    svg.push("</svg>".to_string());
+
+    svg.join("\n")
+}
+
+/// Escapes the characters of the text which have a meaning in XML, so that it can be written in an
+/// SVG document.
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Width of the light margin around QR codes, in modules, as the standard requires.
+const QR_QUIET_ZONE: i32 = 4;
+
+/// Draws the text as a QR code for terminals, two rows of modules per line with half blocks.
+/// Light modules are drawn and dark ones left blank, so that the code reads on the dark background
+/// of most terminals. Returns `None` if the text is too long to fit in a QR code.
+pub fn draw_qr_code(text: &str) -> Option<String> {
+    let code = QrCode::encode_text(text, QrCodeEcc::Medium).ok()?;
+    let light = |x, y| !code.get_module(x, y);
+    let range = -QR_QUIET_ZONE..code.size() + QR_QUIET_ZONE;
+    let lines: Vec<String> = range
+        .clone()
+        .step_by(2)
+        .map(|y| {
+            range
+                .clone()
+                .map(|x| match (light(x, y), light(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+/// Draws the board as text, writing in each cell the last turn on which a journey which died out
+/// visited it, given for each cell indexed like `Board::index_of`, or `.` if none did.
+pub fn draw_dead_end_map(board: &Board, depths: &[Option<i16>]) -> String {
+    let mut map = String::new();
+    for cell in board.cells() {
+        if cell.get_position().1 > 0 {
+            map.push(' ');
+        } else if !map.is_empty() {
+            map.push('\n');
+        }
+        let depth = depths[board.index_of(&cell)];
+        map.push_str(&format!(
+            "{:>4}",
+            depth.map_or(".".to_string(), |depth| depth.to_string())
+        ));
+    }
+
+    map
+}
+
+/// Draws the same map as `draw_dead_end_map` as an SVG document, where cells are shaded from
+/// white to dark red as journeys die out on them later in the search. Cells on which no journey
+/// died out are gray.
+pub fn draw_dead_end_map_svg(board: &Board, depths: &[Option<i16>]) -> String {
+    const SIZE: usize = 60;
+    let deepest = depths.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let positions: Vec<Position> = board.positions().collect();
+    let width = positions.iter().map(|&(_, col)| col + 1).max().unwrap_or(0) * SIZE;
+    let height = positions.iter().map(|&(row, _)| row + 1).max().unwrap_or(0) * SIZE;
+
+    let mut svg = vec![format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="-1 -1 {0} {1}">"#,
+        width + 2,
+        height + 2
+    )];
+    for cell in board.cells() {
+        let position = *cell.get_position();
+        let depth = depths[board.index_of(&cell)];
+        let fill = match depth {
+            Some(depth) => {
+                // The lightness goes from 100% for the first turn down to 30% for the deepest.
+                let lightness = 100 - 70 * depth.max(0) as i32 / deepest as i32;
+                format!("hsl(0, 80%, {}%)", lightness)
+            }
+            None => "lightgray".to_string(),
+        };
+        let (x, y) = (position.1 * SIZE, position.0 * SIZE);
+        svg.push(format!(
+            r#"  <rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}" stroke="black"/>"#,
+            x, y, SIZE, fill
+        ));
+        svg.push(format!(
+            r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="12" fill="dimgray">{}</text>"#,
+            x + 4,
+            y + 14,
+            cell.get_value()
+        ));
+        if let Some(depth) = depth {
+            svg.push(format!(
+                r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="18" font-weight="bold" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                x + SIZE / 2,
+                y + SIZE / 2 + 6,
+                depth
+            ));
+        }
+    }
+    svg.push("</svg>".to_string());
+
+    svg.join("\n")
+}
+
+/// Centers the value in the given width, replacing it with `…` if it does not fit.
+fn format_value(value: Option<i16>, width: usize) -> String {
+    let value = value.map_or("?".to_string(), |value| value.to_string());
+    if value.chars().count() > width {
+        format!("{:^width$}", "…")
+    } else {
+        format!("{:^width$}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_isometric_dice_shows_visible_faces() {
+        // The top is known, and rolling down moves it to the side facing the bottom of the board.
+        let dice = Dice::default()
+            .set_face(Face::Top, 5)
+            .roll_down()
+            .set_face(Face::Top, -9);
+        let dice = dice.roll_left().set_face(Face::Top, 1234).roll_right();
+
+        assert_eq!(
+            draw_isometric_dice(&dice),
+            [
+                "      ┌─────────┐",
+                "     ╱         ╱│",
+                "    ╱   -9    ╱ │",
+                "   ╱         ╱  │",
+                "  ┌─────────┐   │",
+                "  │         │ … ┘",
+                "  │    5    │  ╱",
+                "  │         │ ╱",
+                "  └─────────┘╱",
+            ]
+            .join("\n")
+        );
+
+        assert!(draw_isometric_dice(&Dice::default()).contains("  │    ?    │  ╱"));
+    }
+
+    #[test]
+    fn draw_net_shows_every_face() {
+        let dice = Dice::from_sides([Some(9), Some(7), Some(9), Some(-3), Some(-9), None]);
+        assert_eq!(
+            draw_net(&dice),
+            [
+                "        +-------+",
+                "        |  -9   |",
+                "+-------+-------+-------+",
+                "|   9   |   9   |  -3   |",
+                "+-------+-------+-------+",
+                "        |   ?   |",
+                "        +-------+",
+                "        |   7   |",
+                "        +-------+",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn draw_net_svg_labels_every_face() {
+        let dice = Dice::from_sides([Some(9), Some(7), Some(9), Some(-3), Some(-9), None]);
+        let svg = draw_net_svg(&dice);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<text ").count(), 6);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 5);
+        for value in [">9<", ">7<", ">-3<", ">-9<", ">?<"] {
+            assert!(svg.contains(value), "{} is missing", value);
+        }
+    }
+
+    #[test]
+    fn draw_journey_svg_moves_the_dice_when_animated() {
+        let board = Board::new();
+        let frames = [((5, 0), None), ((4, 0), Some(-7)), ((4, 1), Some(3))];
+
+        let svg = draw_journey_svg(&board, &frames, false);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>"));
+        assert!(!svg.contains("<animate"));
+        assert_eq!(svg.matches("<polyline ").count(), 1);
+        assert!(svg.contains(">3</text>"));
+
+        let svg = draw_journey_svg(&board, &frames, true);
+        assert_eq!(svg.matches("<animateTransform ").count(), 1);
+        assert!(svg.contains(r#"values="0,0;0,-60;60,-60;60,-60""#));
+        assert!(svg.contains(r#"keyTimes="0.0000;0.3333;0.6667;1""#));
+        // The top is updated on each cell, starting unknown on the start cell.
+        assert_eq!(
+            svg.matches(r#"<animate attributeName="visibility""#)
+                .count(),
+            3
+        );
+        for top in [">?<", ">-7<", ">3<"] {
+            assert!(svg.contains(top), "{} is missing", top);
+        }
+    }
+
+    #[test]
+    fn draw_journey_svg_shows_the_annotations_of_cells() {
+        let board = Board::from_reader(
+            "label: a1 <start> & co\ncolor: a1 #fc0\ncolor: 0,1 gold\n1 2\n3 4\n".as_bytes(),
+        )
+        .unwrap();
+        let svg = draw_journey_svg(&board, &[((1, 0), None)], false);
+        assert!(svg.contains(r##"fill="#fc0""##));
+        assert!(svg.contains(r#"fill="gold""#));
+        assert_eq!(svg.matches(r#"fill="white""#).count(), 2);
+        assert!(svg.contains(">&lt;start&gt; &amp; co</text>"));
+    }
+
+    #[test]
+    fn draw_qr_code_fits_two_rows_of_modules_per_line() {
+        // The smallest codes are 21 modules wide, framed by the quiet zone.
+        let code = draw_qr_code("HELLO").unwrap();
+        let lines: Vec<&str> = code.lines().collect();
+        assert_eq!(lines.len(), 15);
+        assert!(lines.iter().all(|line| line.chars().count() == 29));
+        assert!(lines[..2].iter().all(|line| line.chars().all(|c| c == '█')));
+        // The finder pattern in the top-left corner is a dark ring, here its first two rows.
+        let finder: String = lines[2].chars().skip(4).take(7).collect();
+        assert_eq!(finder, " ▄▄▄▄▄ ");
+
+        assert_eq!(draw_qr_code(&"A".repeat(5000)), None);
+    }
+
+    #[test]
+    fn draw_dead_end_map_shows_the_depth_of_each_cell() {
+        let board = Board::new();
+        let mut depths = vec![None; 36];
+        depths[board.index_of(&board.start_cell())] = Some(0);
+        depths[0] = Some(12);
+
+        let map = draw_dead_end_map(&board, &depths);
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "  12    .    .    .    .    .");
+        assert_eq!(lines[5], "   0    .    .    .    .    .");
+
+        let svg = draw_dead_end_map_svg(&board, &depths);
+        assert_eq!(svg.matches("<rect ").count(), 36);
+        assert_eq!(svg.matches(r#"fill="lightgray""#).count(), 34);
+        assert!(svg.contains(r#"fill="hsl(0, 80%, 30%)""#));
+        assert!(svg.contains(r#"fill="hsl(0, 80%, 100%)""#));
+    }
+}
@@ -0,0 +1,546 @@
+use crate::{
+    board::{Board, Cell, Notation, Position},
+    dice::{Dice, Face},
+    direction::Direction,
+    render::{draw_isometric_dice, draw_net, draw_net_svg},
+    rules::{Recurrence, Rules},
+    solver::{ExplanationError, ExplanationStyle, NetFormat, SolverOutput},
+    stats::MoveStats,
+    turn::Turn,
+};
+
+use serde_json::{json, Value};
+use std::{fmt, io, sync::Arc};
+use strum::IntoEnumIterator;
+
+/// Describes a solution turn by turn: the moves of the dice, its disposition and the score after
+/// each of them, and the cells it visited. Displaying the report explains the solution, in the
+/// style the solver was configured with.
+///
+/// Only the moves and the initial dice are stored. Everything else is worked out from them on the
+/// board shared with the solver, when asked for, so that enumerating many solutions does not pay
+/// for explaining those never printed.
+#[derive(Clone)]
+pub struct SolutionReport {
+    /// The directions the dice rolled in, in order.
+    pub moves: Vec<Direction>,
+    /// Disposition of the dice before the first turn, inferred from the scores of the journey.
+    pub initial_dice: Dice,
+    /// Sum of the values of the cells never visited.
+    pub unvisited_sum: i32,
+    /// Errors hit writing the files asked for about the solution, if any.
+    pub output_errors: Vec<(SolverOutput, Arc<io::Error>)>,
+    /// The board the solution was found on.
+    pub(crate) board: Arc<Board>,
+    /// The cell the journey started on.
+    pub(crate) start_cell: Cell,
+    pub(crate) rules: Rules,
+    pub(crate) settings: ExplanationSettings,
+}
+
+/// How the solver was configured to explain solutions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ExplanationSettings {
+    pub style: ExplanationStyle,
+    pub net_format: Option<NetFormat>,
+    pub verification: bool,
+    pub notation: Notation,
+}
+
+/// A turn of a solution, as it is explained.
+struct TurnRecord {
+    /// Turn number, from 1.
+    turn: usize,
+    direction: Direction,
+    from: Cell,
+    to: Cell,
+    /// Disposition of the dice after rolling.
+    dice: Dice,
+    /// The value scored by the dice, unknown if it lands on any cell.
+    scored_value: Option<i16>,
+    multiplier: i16,
+    /// Scores before and after the turn.
+    score: i16,
+    new_score: i16,
+}
+
+impl SolutionReport {
+    /// Returns the moves written as a string, e.g. `URRD`.
+    pub fn moves_as_letters(&self) -> String {
+        self.moves
+            .iter()
+            .map(|direction| direction.letter())
+            .collect()
+    }
+
+    /// Lists the positions visited in order, from the start cell to the end cell.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells().map(|cell| *cell.get_position())
+    }
+
+    /// Lists the disposition of the dice after each turn. Faces which never scored are still
+    /// unknown.
+    pub fn dice(&self) -> impl Iterator<Item = Dice> + '_ {
+        self.moves
+            .iter()
+            .scan(self.initial_dice, |dice, &direction| {
+                *dice = dice.roll_in(direction);
+                Some(*dice)
+            })
+    }
+
+    /// Lists the score after each turn. The list stops short if the rules the solution was found
+    /// with define no multiplier for some turn, which only happens if it does not follow them.
+    pub fn scores(&self) -> impl Iterator<Item = i16> + '_ {
+        self.turns().map(|record| record.new_score)
+    }
+
+    /// Returns the disposition of the dice at the end of the journey.
+    pub fn final_dice(&self) -> Dice {
+        self.dice().last().unwrap_or(self.initial_dice)
+    }
+
+    /// Describes the solution as JSON: its moves, the positions visited as `[row, column]` pairs,
+    /// the top of the dice and the score after each turn, and the faces of the initial dice, `null`
+    /// for those which can hold any value.
+    pub fn to_json(&self) -> Value {
+        let faces: serde_json::Map<String, Value> = Face::iter()
+            .map(|face| {
+                (
+                    face.name().to_string(),
+                    json!(self.initial_dice.get_face(face)),
+                )
+            })
+            .collect();
+        json!({
+            "unvisited_sum": self.unvisited_sum,
+            "moves": self.moves_as_letters(),
+            "path": self.positions().collect::<Vec<_>>(),
+            "tops": self.dice().map(|dice| dice.get_face(Face::Top)).collect::<Vec<_>>(),
+            "scores": self.scores().collect::<Vec<_>>(),
+            "initial_dice": faces,
+            "move_stats": MoveStats::from_moves(&self.moves_as_letters()).to_json(),
+        })
+    }
+
+    /// Lists the cells visited in order, from the start cell to the end cell.
+    fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        let moved = self.moves.iter().scan(self.start_cell, |cell, &direction| {
+            *cell = self.board.move_in(cell, direction)?;
+            Some(*cell)
+        });
+        std::iter::once(self.start_cell).chain(moved)
+    }
+
+    /// Follows the journey turn by turn, working out the score of each turn. Stops at the first
+    /// turn the rules define no multiplier for.
+    fn turns(&self) -> impl Iterator<Item = TurnRecord> + '_ {
+        let initial_score = self.rules.initial_score(self.start_cell.get_value());
+        self.moves
+            .iter()
+            .zip(self.cells().zip(self.cells().skip(1)))
+            .zip(self.dice())
+            .enumerate()
+            .scan(
+                initial_score,
+                move |score, (turn, ((&direction, (from, to)), dice))| {
+                    let multiplier = turn_multiplier(&self.rules, turn + 1).ok()?;
+                    // Only values landing on any cell can remain unknown, and they do not change
+                    // the score.
+                    let scored_value = self.rules.scoring.scored_value(&dice);
+                    let record = TurnRecord {
+                        turn: turn + 1,
+                        direction,
+                        from,
+                        to,
+                        dice,
+                        scored_value,
+                        multiplier,
+                        score: *score,
+                        new_score: self.rules.next_score(
+                            *score,
+                            multiplier,
+                            scored_value.unwrap_or_default(),
+                        ),
+                    };
+                    *score = record.new_score;
+                    Some(record)
+                },
+            )
+    }
+
+    /// Explains the journey with one line per turn, after the initial dice.
+    fn explain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = &self.rules;
+        let ExplanationSettings {
+            style,
+            net_format,
+            verification,
+            ..
+        } = self.settings;
+        write!(
+            f,
+            "We started with the following dice: {:?}",
+            self.initial_dice
+        )?;
+        if !self.initial_dice.is_fully_known() {
+            write!(
+                f,
+                " ({} face(s) never scored, so they can hold any value)",
+                self.initial_dice.unknown_face_count()
+            )?;
+        }
+        match net_format {
+            Some(NetFormat::Text) => write!(f, "\n{}", draw_net(&self.initial_dice))?,
+            Some(NetFormat::Svg) => write!(f, "\n{}", draw_net_svg(&self.initial_dice))?,
+            None => {}
+        }
+        if style == ExplanationStyle::Isometric {
+            write!(f, "\n{}", draw_isometric_dice(&self.initial_dice))?;
+        }
+
+        for record in self.turns() {
+            let TurnRecord {
+                score, new_score, ..
+            } = record;
+            let cell_value = match self.board.label(*record.to.get_position()) {
+                Some(label) => format!("{}, {}", record.to.get_value(), label),
+                None => record.to.get_value().to_string(),
+            };
+            let value = record
+                .scored_value
+                .map_or("?".to_string(), |value| value.to_string());
+            let score_change = match rules.recurrence {
+                Recurrence::Affine => format!(
+                    "Score was {}, now is `{} + ({} x {}) = {}` (cell value = {})",
+                    score, score, record.multiplier, value, new_score, cell_value
+                ),
+                Recurrence::Divisibility => format!(
+                    "Score was {}, now is {} (cell value = {}, divisible by {})",
+                    score, new_score, cell_value, value
+                ),
+                Recurrence::Multiplicative { .. } => format!(
+                    "Score was {}, now is `{} x {} = {}` (cell value = {})",
+                    score, score, value, new_score, cell_value
+                ),
+            };
+            write!(
+                f,
+                "\nTurn {} we rolled the dice {:?} ({}={}). {}. The dice is now: {}.",
+                record.turn,
+                record.direction,
+                rules.scoring.name(),
+                value,
+                score_change,
+                record.dice
+            )?;
+            if style == ExplanationStyle::Isometric {
+                write!(f, "\n{}", draw_isometric_dice(&record.dice))?;
+            }
+        }
+
+        if verification {
+            write!(f, "\n{}", self.verification_table())?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists, for each turn, the value of the cell reached next to the score computed on that turn,
+    /// with a check mark when the dice lands on it. When scores match cell values, the table ends
+    /// with a checksum, comparing the sum of the scores with the sum of the cell values.
+    fn verification_table(&self) -> String {
+        let rules = &self.rules;
+        let mut table = vec![
+            "Turn | Cell value | Score | Check".to_string(),
+            "-----|------------|-------|------".to_string(),
+        ];
+        let mut scores_sum = 0;
+        let mut cell_values_sum = 0;
+        for record in self.turns() {
+            let cell_value = record.to.get_value();
+            let lands = record.scored_value.map_or(
+                rules.lands_with_any_value(record.score, record.multiplier, cell_value),
+                |value| rules.lands(record.score, record.multiplier, value, cell_value),
+            );
+            table.push(format!(
+                "{:>4} | {:>10} | {:>5} | {}",
+                record.turn,
+                cell_value,
+                record.new_score,
+                check_mark(lands)
+            ));
+            scores_sum += record.new_score as i64;
+            cell_values_sum += rules.arithmetic.reduce(cell_value as i32) as i64;
+        }
+        if rules.recurrence != Recurrence::Divisibility {
+            table.push(format!(
+                "Checksum: the scores sum up to {}, and the cell values to {} {}",
+                scores_sum,
+                cell_values_sum,
+                check_mark(scores_sum == cell_values_sum)
+            ));
+        }
+
+        table.join("\n")
+    }
+
+    /// Describes the position like `describe_position` does, followed by the label of its cell.
+    fn describe_cell(&self, position: Position) -> String {
+        let description = describe_position(position, self.settings.notation);
+        match self.board.label(position) {
+            Some(label) => format!("{} ({})", description, label),
+            None => description,
+        }
+    }
+
+    /// Explains the journey in full sentences, one per turn.
+    fn explain_accessibly(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = &self.rules;
+        let dice = self.initial_dice;
+        write!(
+            f,
+            "The dice starts on {}, with {} on top, {} on the bottom, {} on the left side, {} on the right side, {} on the side facing up the board, and {} on the side facing down the board.",
+            self.describe_cell(*self.start_cell.get_position()),
+            describe_face(dice.get_face(Face::Top)),
+            describe_face(dice.get_face(Face::Bottom)),
+            describe_face(dice.get_face(Face::Left)),
+            describe_face(dice.get_face(Face::Right)),
+            describe_face(dice.get_face(Face::Front)),
+            describe_face(dice.get_face(Face::Back)),
+        )?;
+
+        let mut last_cell = self.start_cell;
+        for record in self.turns() {
+            let TurnRecord {
+                score,
+                new_score,
+                scored_value,
+                ..
+            } = record;
+            let cell_value = record.to.get_value();
+            let score_change = match rules.recurrence {
+                Recurrence::Affine => format!(
+                    "The score goes from {} to {} plus {} times {}, which is {}. The cell value is {}.",
+                    describe_number(score),
+                    describe_number(score),
+                    describe_number(record.multiplier),
+                    describe_face(scored_value),
+                    describe_number(new_score),
+                    describe_number(cell_value),
+                ),
+                Recurrence::Divisibility => format!(
+                    "The score goes from {} to {}. The cell value is {}, which is divisible by {}.",
+                    describe_number(score),
+                    describe_number(new_score),
+                    describe_number(cell_value),
+                    describe_face(scored_value),
+                ),
+                Recurrence::Multiplicative { .. } => format!(
+                    "The score goes from {} to {} times {}, which is {}. The cell value is {}.",
+                    describe_number(score),
+                    describe_number(score),
+                    describe_face(scored_value),
+                    describe_number(new_score),
+                    describe_number(cell_value),
+                ),
+            };
+            write!(
+                f,
+                "\nTurn {}: from {}, roll {} to {}. The {} of the dice shows {}. {}",
+                record.turn,
+                self.describe_cell(*record.from.get_position()),
+                describe_direction(record.direction),
+                self.describe_cell(*record.to.get_position()),
+                rules.scoring.name(),
+                describe_face(scored_value),
+                score_change,
+            )?;
+            last_cell = record.to;
+        }
+
+        write!(
+            f,
+            "\nThe dice reached {} after {} turns.",
+            self.describe_cell(*last_cell.get_position()),
+            self.moves.len()
+        )
+    }
+}
+
+/// Explains the solution, one line per turn, in the style the solver was configured with.
+impl fmt::Display for SolutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Check every turn before writing anything, so that the explanation is not cut short.
+        for turn in 1..=self.moves.len() {
+            if let Err(error) = turn_multiplier(&self.rules, turn) {
+                return write!(f, "This journey cannot be explained: {}.", error);
+            }
+        }
+
+        match self.settings.style {
+            ExplanationStyle::Accessible => self.explain_accessibly(f),
+            ExplanationStyle::Text | ExplanationStyle::Isometric => self.explain(f),
+        }
+    }
+}
+
+/// Returns the multiplier of the given turn of a journey, starting at 1.
+pub(crate) fn turn_multiplier(rules: &Rules, turn: usize) -> Result<i16, ExplanationError> {
+    i16::try_from(turn)
+        .ok()
+        .and_then(|turn| rules.multiplier.at(Turn::from(turn)))
+        .ok_or(ExplanationError::NoMultiplier { turn })
+}
+
+/// Marks whether a verification passed.
+fn check_mark(passed: bool) -> char {
+    if passed {
+        '✓'
+    } else {
+        '✗'
+    }
+}
+
+/// Describes a position with 1-based row and column numbers, e.g. `row 6 column 1`, or as a square
+/// in chess notation, e.g. `square a1`.
+pub(crate) fn describe_position(position: Position, notation: Notation) -> String {
+    match notation {
+        Notation::RowCol => format!("row {} column {}", position.0 + 1, position.1 + 1),
+        Notation::Chess { .. } => format!("square {}", notation.format(position)),
+    }
+}
+
+/// Describes a direction as a word, e.g. `up`.
+pub(crate) fn describe_direction(direction: Direction) -> &'static str {
+    match direction {
+        Direction::UP => "up",
+        Direction::RIGHT => "right",
+        Direction::DOWN => "down",
+        Direction::LEFT => "left",
+    }
+}
+
+/// Spells out the sign of a number, so that it is not read as a dash.
+fn describe_number(number: i16) -> String {
+    if number < 0 {
+        format!("minus {}", number.unsigned_abs())
+    } else {
+        number.to_string()
+    }
+}
+
+/// Describes the value of a face, which might be unknown.
+fn describe_face(value: Option<i16>) -> String {
+    value.map_or_else(|| "an unknown value".to_string(), describe_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Annotation,
+        rules::Multiplier,
+        solver::{Solution, Solver},
+    };
+
+    fn solve_puzzle() -> SolutionReport {
+        let Solution::Found(report) = Solver::new().solve() else {
+            panic!("the puzzle board should be solved");
+        };
+        report
+    }
+
+    #[test]
+    fn report_lists_every_turn() {
+        let report = solve_puzzle();
+        let board = Board::new();
+        let positions: Vec<Position> = report.positions().collect();
+        let dice: Vec<Dice> = report.dice().collect();
+        let scores: Vec<i16> = report.scores().collect();
+
+        assert_eq!(report.moves.len(), 32);
+        assert_eq!(dice.len(), 32);
+        assert_eq!(scores.len(), 32);
+        assert_eq!(positions.len(), 33);
+        assert_eq!(positions.first(), Some(&(5, 0)));
+        assert_eq!(positions.last(), Some(&(0, 5)));
+        assert_eq!(
+            report.moves_as_letters(),
+            "URRDLUUUUURDLLDRRDRDDRRUULLUURRU"
+        );
+        assert_eq!(report.unvisited_sum, 1935);
+
+        // Under the default rules, each score is the value of the cell landed on.
+        for (score, position) in scores.iter().zip(&positions[1..]) {
+            assert_eq!(*score, board.get_cell_at(*position).unwrap().get_value());
+        }
+        assert_eq!(report.initial_dice.roll_in(report.moves[0]), dice[0]);
+        assert_eq!(report.final_dice(), dice[31]);
+    }
+
+    #[test]
+    fn explanations_name_labelled_cells() {
+        let board = Board::new()
+            .annotate(
+                (4, 0),
+                Annotation {
+                    label: Some("the first step".to_string()),
+                    color: None,
+                },
+            )
+            .unwrap();
+        let solve = |style| {
+            let Solution::Found(report) = Solver::builder()
+                .board(board.clone())
+                .explanation_style(style)
+                .build()
+                .unwrap()
+                .solve()
+            else {
+                panic!("the puzzle board should be solved");
+            };
+            report.to_string()
+        };
+
+        let text = solve(ExplanationStyle::Text);
+        assert!(text.contains("(cell value = 5, the first step)"));
+        assert_eq!(text.matches("the first step").count(), 1);
+        let accessible = solve(ExplanationStyle::Accessible);
+        assert!(accessible
+            .contains("Turn 1: from row 6 column 1, roll up to row 5 column 1 (the first step)."));
+        assert!(accessible.contains("Turn 2: from row 5 column 1 (the first step), roll right"));
+    }
+
+    #[test]
+    fn json_follows_the_journey() {
+        let document = solve_puzzle().to_json();
+
+        assert_eq!(document["unvisited_sum"], 1935);
+        assert_eq!(document["moves"], "URRDLUUUUURDLLDRRDRDDRRUULLUURRU");
+        assert_eq!(document["path"][0], json!([5, 0]));
+        assert_eq!(document["path"][32], json!([0, 5]));
+        assert_eq!(document["tops"][0], 5);
+        assert_eq!(document["scores"][31], 732);
+        assert_eq!(
+            document["initial_dice"],
+            json!({ "top": 9, "bottom": 7, "left": 9, "right": -3, "front": -9, "back": 5 })
+        );
+        assert_eq!(
+            document["move_stats"],
+            MoveStats::from_moves("URRDLUUUUURDLLDRRDRDDRRUULLUURRU").to_json()
+        );
+    }
+
+    #[test]
+    fn report_without_multipliers_cannot_be_explained() {
+        let mut report = solve_puzzle();
+        report.rules.multiplier = Multiplier::Sequence(Arc::from([1, 2]));
+
+        assert_eq!(report.scores().count(), 2);
+        assert_eq!(
+            report.to_string(),
+            "This journey cannot be explained: the rules define no multiplier for turn 3."
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::solver::SearchStats;
+
+use std::fs;
+
+/// Resources used by a solve, e.g. to plan the capacity of a server running solves.
+#[derive(Clone, Copy, Debug)]
+pub struct ResourceUsage {
+    /// Peak resident set size of the process, in bytes, where the platform reports it.
+    pub peak_rss: Option<u64>,
+    /// Allocations made by the process, when the binary counts them.
+    pub allocations: Option<u64>,
+    /// Most steps waiting in the frontier at once.
+    pub peak_frontier: u64,
+    /// Steps stored in the arena of the solver.
+    pub stored: u64,
+}
+
+impl ResourceUsage {
+    /// Measures the resources used by the process so far, and by the search with these counters.
+    /// Allocations can only be counted by the global allocator of the binary, which reports them
+    /// here if it does.
+    pub fn measure(stats: &SearchStats, allocations: Option<u64>) -> Self {
+        Self {
+            peak_rss: peak_rss(),
+            allocations,
+            peak_frontier: stats.peak_frontier,
+            stored: stats.stored,
+        }
+    }
+
+    /// Describes the resources used, one per line.
+    pub fn describe(&self) -> String {
+        [
+            format!(
+                "Peak resident set size: {}.",
+                self.peak_rss
+                    .map_or("unavailable".to_string(), |bytes| format!(
+                        "{} KiB",
+                        bytes >> 10
+                    ))
+            ),
+            format!(
+                "Allocations: {}.",
+                self.allocations.map_or(
+                    "not counted, build with `--features count-allocations`".to_string(),
+                    |allocations| allocations.to_string()
+                )
+            ),
+            format!("Peak frontier size: {} steps.", self.peak_frontier),
+            format!(
+                "Steps stored: {}, as the arena keeps every step generated, without deduplication.",
+                self.stored
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/// Reads the peak resident set size of the process, which only Linux reports, in `/proc`.
+fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kibibytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kibibytes << 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    #[test]
+    fn resource_usage_reports_the_search() {
+        let (_, stats) = Solver::new().solve_with_stats();
+        let usage = ResourceUsage::measure(&stats, None);
+        assert!(usage.peak_frontier > 0);
+        assert!(usage.stored > usage.peak_frontier);
+        assert!(usage.allocations.is_none());
+        assert_eq!(ResourceUsage::measure(&stats, Some(7)).allocations, Some(7));
+        if cfg!(target_os = "linux") {
+            assert!(usage.peak_rss.is_some_and(|bytes| bytes > 0));
+        }
+
+        let description = usage.describe();
+        assert_eq!(description.lines().count(), 4);
+        assert!(description.contains(&format!(
+            "Peak frontier size: {} steps.",
+            usage.peak_frontier
+        )));
+    }
+}
@@ -0,0 +1,899 @@
+use crate::{
+    dice::{Dice, Face},
+    turn::Turn,
+};
+
+use std::sync::{Arc, OnceLock};
+
+/// Enumerates how the multiplier applied to the top of the dice evolves from turn to turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Multiplier {
+    /// The multiplier is the turn number: 1, 2, 3, and so on. This is the rule of the original puzzle.
+    #[default]
+    Ascending,
+    /// The multiplier counts down from the given value: N, N-1, ..., 1. A journey therefore cannot
+    /// last more than N turns.
+    Descending { from: i16 },
+    /// The multiplier follows the Fibonacci sequence: 1, 1, 2, 3, 5, and so on, until it does not
+    /// fit in an `i16`.
+    Fibonacci,
+    /// The multiplier is the n-th prime number on turn n: 2, 3, 5, 7, and so on, until it does not
+    /// fit in an `i16`.
+    Primes,
+    /// The multiplier follows the given sequence, one value per turn. A journey therefore cannot
+    /// last more turns than the sequence holds values.
+    Sequence(Arc<[i16]>),
+}
+
+/// Enumerates how scores are compared to cell values.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Arithmetic {
+    /// The dice lands on a cell when the score equals its value. This is the rule of the original puzzle.
+    #[default]
+    Integer,
+    /// The score is maintained modulo the given value, and the dice lands on a cell when the score is
+    /// congruent to its value. Inferred tops are residues, between 0 and the modulus (excluded).
+    Modular { modulus: i16 },
+}
+
+/// Enumerates how the score evolves from turn to turn, and when the dice lands on a cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Recurrence {
+    /// The scored value, times the multiplier, is added to the score, and the dice lands on a cell
+    /// when the score matches its value. This is the rule of the original puzzle.
+    #[default]
+    Affine,
+    /// The dice lands on a cell when its value is divisible by the scored value, and the score
+    /// just counts the turns. The multiplier only limits how many turns a journey can last, and
+    /// values are compared as integers, whichever the arithmetic.
+    Divisibility,
+    /// The score, starting from the given value, is multiplied by the scored value, and the dice
+    /// lands on a cell when the score matches its value. The multiplier only limits how many turns
+    /// a journey can last.
+    Multiplicative { initial: i16 },
+}
+
+/// Enumerates what the score must be once the dice lands on the end cell, for the journey to be
+/// a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FinalScore {
+    /// Landing on the end cell suffices. This is the rule of the original puzzle.
+    #[default]
+    Any,
+    /// The final score must equal the value of the end cell, even when landing does not compare
+    /// them as integers.
+    EndCellValue,
+    /// The final score must equal the given target.
+    Target(i16),
+}
+
+/// Enumerates which value of the dice is multiplied and added to the score on each turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Scoring {
+    /// The value on top of the dice. This is the rule of the original puzzle.
+    #[default]
+    Top,
+    /// The value on the face touching the board.
+    Bottom,
+    /// The sum of the values on the five faces not touching the board.
+    VisibleSum,
+}
+
+/// Faces of the dice which are not touching the board.
+const VISIBLE_FACES: [Face; 5] = [Face::Top, Face::Left, Face::Right, Face::Front, Face::Back];
+
+/// Range of values the faces of the dice can hold, both ends included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceRange {
+    pub min: i16,
+    pub max: i16,
+}
+
+/// Groups the rules of the puzzle that can be changed to solve variants of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rules {
+    pub multiplier: Multiplier,
+    pub arithmetic: Arithmetic,
+    pub recurrence: Recurrence,
+    pub scoring: Scoring,
+    /// If set, journeys inferring a face value outside of this range are discarded.
+    pub face_range: Option<FaceRange>,
+    /// If set, opposite faces of the dice must sum to this value, e.g. 7 for a standard dice.
+    /// Inferring a face then infers its opposite face as well.
+    pub opposite_sum: Option<i16>,
+    /// If set, journeys inferring a face value which is not one of these are discarded.
+    pub allowed_faces: Option<Arc<[i16]>>,
+    /// Whether journeys inferring a face value already held by another face are discarded.
+    pub distinct_faces: bool,
+    pub final_score: FinalScore,
+}
+
+impl FaceRange {
+    pub fn contains(&self, value: i16) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+impl Rules {
+    /// Returns the score before the first turn, for a journey starting on a cell with the given
+    /// value.
+    pub fn initial_score(&self, start_value: i16) -> i16 {
+        match self.recurrence {
+            Recurrence::Affine => start_value,
+            Recurrence::Divisibility => 0,
+            Recurrence::Multiplicative { initial } => initial,
+        }
+    }
+
+    /// Returns the score after a turn with the given multiplier, where the dice scores the given
+    /// value, as it should be displayed.
+    pub fn next_score(&self, score: i16, multiplier: i16, value: i16) -> i16 {
+        match self.recurrence {
+            Recurrence::Affine => {
+                self.arithmetic
+                    .reduce(score as i32 + multiplier as i32 * value as i32) as i16
+            }
+            Recurrence::Divisibility => score.saturating_add(1),
+            Recurrence::Multiplicative { .. } => {
+                self.arithmetic.reduce(score as i32 * value as i32) as i16
+            }
+        }
+    }
+
+    /// Returns whether the dice, scoring the given value on a turn with the given multiplier from
+    /// a cell with the given score, lands on a cell with the given value.
+    pub fn lands(&self, score: i16, multiplier: i16, value: i16, cell_value: i16) -> bool {
+        match self.recurrence {
+            Recurrence::Affine => self.arithmetic.lands(score, multiplier, value, cell_value),
+            // Zero is divisible by anything, but divides nothing else.
+            Recurrence::Divisibility => match value {
+                0 => cell_value == 0,
+                _ => cell_value as i32 % value as i32 == 0,
+            },
+            // Multiplying the score is adding it to 0, with the score as the multiplier.
+            Recurrence::Multiplicative { .. } => self.arithmetic.lands(0, score, value, cell_value),
+        }
+    }
+
+    /// Returns whether the dice lands on a cell with the given value whichever the value it
+    /// scores on a turn with the given multiplier, from a cell with the given score, in which case
+    /// there is nothing to infer. With modular arithmetic, all the residues are inferred instead.
+    pub fn lands_with_any_value(&self, score: i16, multiplier: i16, cell_value: i16) -> bool {
+        let integers = self.arithmetic.modulus().is_none();
+        match self.recurrence {
+            Recurrence::Affine => integers && multiplier == 0 && score == cell_value,
+            Recurrence::Divisibility => cell_value == 0,
+            Recurrence::Multiplicative { .. } => integers && score == 0 && cell_value == 0,
+        }
+    }
+
+    /// Infers all the values an unknown scored value can take, such that the dice lands on a cell
+    /// with the given value. Cells on which any value lands are not covered.
+    pub fn infer_values(&self, score: i16, multiplier: i16, cell_value: i16) -> CandidateTops {
+        match self.recurrence {
+            Recurrence::Affine => self.arithmetic.infer_tops(score, multiplier, cell_value),
+            Recurrence::Divisibility => CandidateTops::divisors(cell_value),
+            // Once the score is 0, it stays 0 whichever the value, so there is nothing to divide by.
+            Recurrence::Multiplicative { .. }
+                if self.arithmetic.modulus().is_none() && score == 0 =>
+            {
+                CandidateTops::none()
+            }
+            // The value must be the exact quotient of the cell value by the score, or solve the
+            // matching linear congruence.
+            Recurrence::Multiplicative { .. } => self.arithmetic.infer_tops(0, score, cell_value),
+        }
+    }
+
+    /// Returns the score once the dice lands on a cell with the given value, after the given number
+    /// of turns, at least one.
+    pub fn landed_score(&self, turns: Turn, cell_value: i16) -> i32 {
+        match self.recurrence {
+            Recurrence::Divisibility => turns.into(),
+            // The score matches the value of the cell landed on, up to the arithmetic.
+            Recurrence::Affine | Recurrence::Multiplicative { .. } => {
+                self.arithmetic.reduce(cell_value as i32)
+            }
+        }
+    }
+
+    /// Returns whether a journey landing on the end cell, with the given value, after the given
+    /// number of turns ends with the required final score.
+    pub fn accepts_final_score(&self, turns: Turn, end_cell_value: i16) -> bool {
+        let final_score = self.landed_score(turns, end_cell_value);
+        match self.final_score {
+            FinalScore::Any => true,
+            FinalScore::EndCellValue => final_score == end_cell_value as i32,
+            FinalScore::Target(target) => final_score == target as i32,
+        }
+    }
+
+    /// Returns whether some value of the dice lands on a cell with the given value, from a cell
+    /// with the given score.
+    pub fn can_land(&self, score: i16, multiplier: i16, cell_value: i16) -> bool {
+        self.lands_with_any_value(score, multiplier, cell_value)
+            || self
+                .infer_values(score, multiplier, cell_value)
+                .next()
+                .is_some()
+    }
+
+    /// Sets the unknown values of the dice so that it scores the given value, and returns the dice,
+    /// unless the inferred values break the constraints on faces.
+    pub fn set_inferred_score(&self, dice: Dice, value: i16) -> Option<Dice> {
+        match self.scoring {
+            Scoring::Top => self.set_inferred_face(dice, Face::Top, value),
+            Scoring::Bottom => self.set_inferred_face(dice, Face::Bottom, value),
+            // The value can only be inferred when a single visible face is unknown, as it is then
+            // the only value completing the sum.
+            Scoring::VisibleSum => {
+                let mut unknown_faces = VISIBLE_FACES
+                    .into_iter()
+                    .filter(|&face| dice.get_face(face).is_none());
+                let (Some(unknown_face), None) = (unknown_faces.next(), unknown_faces.next())
+                else {
+                    return None;
+                };
+                let known_sum: i32 = VISIBLE_FACES
+                    .into_iter()
+                    .filter_map(|face| dice.get_face(face))
+                    .map(i32::from)
+                    .sum();
+                let face_value = i16::try_from(value as i32 - known_sum).ok()?;
+                self.set_inferred_face(dice, unknown_face, face_value)
+            }
+        }
+    }
+
+    /// Sets a value inferred for the given face of the dice, and returns the dice, unless the value
+    /// breaks the constraints on faces. Constraints are checked as soon as a value is inferred, with
+    /// the other faces as they are known so far, so that invalid dice are pruned as early as
+    /// possible.
+    pub fn set_inferred_face(&self, dice: Dice, face: Face, value: i16) -> Option<Dice> {
+        let dice = self.set_allowed_face(dice, face, value)?;
+        let Some(opposite_sum) = self.opposite_sum else {
+            return Some(dice);
+        };
+
+        let opposite_value = opposite_sum as i32 - value as i32;
+        match dice.get_face(face.opposite()) {
+            Some(known_value) => (known_value as i32 == opposite_value).then_some(dice),
+            None => {
+                let opposite_value = i16::try_from(opposite_value).ok()?;
+                self.set_allowed_face(dice, face.opposite(), opposite_value)
+            }
+        }
+    }
+
+    /// Sets the value of the given face of the dice, and returns the dice, unless the value is not
+    /// allowed on its own, or is already held by another face while faces must be distinct.
+    fn set_allowed_face(&self, dice: Dice, face: Face, value: i16) -> Option<Dice> {
+        let allowed = self
+            .face_range
+            .is_none_or(|face_range| face_range.contains(value))
+            && self
+                .allowed_faces
+                .as_ref()
+                .is_none_or(|allowed_faces| allowed_faces.contains(&value));
+        let repeated = self.distinct_faces
+            && dice
+                .known_faces()
+                .any(|(other_face, other_value)| other_face != face && other_value == value);
+
+        (allowed && !repeated).then(|| dice.set_face(face, value))
+    }
+
+    /// Checks that the known faces of the given dice follow the constraints on faces, which are
+    /// otherwise only checked on inferred values.
+    pub fn check_known_faces(&self, dice: &Dice) -> Result<(), String> {
+        for (face, value) in dice.known_faces() {
+            if let Some(face_range) = self.face_range.filter(|range| !range.contains(value)) {
+                return Err(format!(
+                    "the {} face holds {}, outside of the face range {}..{}",
+                    face.name(),
+                    value,
+                    face_range.min,
+                    face_range.max
+                ));
+            }
+            if self
+                .allowed_faces
+                .as_ref()
+                .is_some_and(|allowed_faces| !allowed_faces.contains(&value))
+            {
+                return Err(format!(
+                    "the {} face holds {}, which is not an allowed face value",
+                    face.name(),
+                    value
+                ));
+            }
+            if let Some((other_face, _)) = dice
+                .known_faces()
+                .filter(|_| self.distinct_faces)
+                .find(|&(other_face, other_value)| other_face != face && other_value == value)
+            {
+                return Err(format!(
+                    "the {} and {} faces both hold {}, while faces must be distinct",
+                    face.name(),
+                    other_face.name(),
+                    value
+                ));
+            }
+            let opposite_value = dice.get_face(face.opposite());
+            if let (Some(opposite_sum), Some(opposite_value)) = (self.opposite_sum, opposite_value)
+            {
+                if value as i32 + opposite_value as i32 != opposite_sum as i32 {
+                    return Err(format!(
+                        "the {} and {} faces hold {} and {}, which do not sum to {}",
+                        face.name(),
+                        face.opposite().name(),
+                        value,
+                        opposite_value,
+                        opposite_sum
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Scoring {
+    /// Returns the name of the value scored, as written in explanations.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::VisibleSum => "visible sum",
+        }
+    }
+
+    /// Returns the value the dice scores with, if known. Sums which do not fit in an `i16` are
+    /// not known either, and cannot be inferred.
+    pub fn scored_value(self, dice: &Dice) -> Option<i16> {
+        match self {
+            Self::Top => dice.get_face(Face::Top),
+            Self::Bottom => dice.get_face(Face::Bottom),
+            Self::VisibleSum => VISIBLE_FACES
+                .into_iter()
+                .map(|face| dice.get_face(face).map(i32::from))
+                .sum::<Option<i32>>()
+                .and_then(|sum| i16::try_from(sum).ok()),
+        }
+    }
+}
+
+impl Multiplier {
+    /// Returns the multiplier to apply on the given turn, starting at 1.
+    /// Returns `None` if no movement is allowed on that turn.
+    pub fn at(&self, turn: Turn) -> Option<i16> {
+        match self {
+            Self::Ascending => Some(turn.into()),
+            Self::Descending { from } => i16::try_from(*from as i32 - i32::from(turn) + 1)
+                .ok()
+                .filter(|&multiplier| multiplier > 0),
+            Self::Fibonacci => {
+                let (mut current, mut next) = (1i32, 1i32);
+                for _ in 1..i16::from(turn) {
+                    (current, next) = (next, current + next);
+                    if current > i16::MAX as i32 {
+                        return None;
+                    }
+                }
+                Some(current as i16)
+            }
+            Self::Primes => primes().get(turn.index()?).copied(),
+            Self::Sequence(multipliers) => multipliers.get(turn.index()?).copied(),
+        }
+    }
+}
+
+impl Arithmetic {
+    /// Returns the representative of the given score, as it should be displayed.
+    pub fn reduce(&self, score: i32) -> i32 {
+        match self.modulus() {
+            None => score,
+            Some(modulus) => score.rem_euclid(modulus),
+        }
+    }
+
+    /// Returns the modulus scores are maintained modulo, if any. Congruences modulo `-M` are the
+    /// same as modulo `M`, and integers modulo 0 are just integers.
+    fn modulus(&self) -> Option<i32> {
+        match *self {
+            Self::Integer => None,
+            Self::Modular { modulus } => {
+                Some((modulus as i32).abs()).filter(|&modulus| modulus != 0)
+            }
+        }
+    }
+
+    /// Returns whether rolling a dice with the given top, from a cell with the given score,
+    /// lands on a cell with the given value.
+    pub fn lands(&self, score: i16, multiplier: i16, top: i16, cell_value: i16) -> bool {
+        let new_score = score as i32 + multiplier as i32 * top as i32;
+        self.reduce(new_score - cell_value as i32) == 0
+    }
+
+    /// Infers all the values an unknown top can take, such that rolling the dice from a cell with
+    /// the given score lands on a cell with the given value.
+    pub fn infer_tops(&self, score: i16, multiplier: i16, cell_value: i16) -> CandidateTops {
+        let score_diff = cell_value as i32 - score as i32;
+        let multiplier = multiplier as i32;
+        match self.modulus() {
+            // The top must be the exact quotient of the score difference by the multiplier. With a
+            // multiplier of 0, the top does not matter, so there is nothing to infer.
+            None => {
+                if multiplier == 0 || score_diff % multiplier != 0 {
+                    return CandidateTops::none();
+                }
+
+                CandidateTops::progression(score_diff / multiplier, 0, 1)
+            }
+            // The top must solve the linear congruence `multiplier x top = score_diff (mod modulus)`.
+            // With `g = gcd(multiplier, modulus)`, there are either no solutions, or exactly `g` of
+            // them, spaced by `modulus / g`.
+            Some(modulus) => {
+                let multiplier = multiplier.rem_euclid(modulus);
+                let score_diff = score_diff.rem_euclid(modulus);
+                let g = gcd(multiplier, modulus);
+                if score_diff % g != 0 {
+                    return CandidateTops::none();
+                }
+
+                let reduced_modulus = modulus / g;
+                let inverse = modular_inverse(multiplier / g, reduced_modulus);
+                let first = (score_diff / g * inverse).rem_euclid(reduced_modulus);
+                CandidateTops::progression(first, reduced_modulus, g)
+            }
+        }
+    }
+}
+
+/// Iterates over the values the top of the dice can take, which either form an arithmetic
+/// progression, or are the divisors of a value.
+pub enum CandidateTops {
+    Progression {
+        next: i32,
+        step: i32,
+        remaining: i32,
+    },
+    /// Divisors of the value, in increasing magnitude, each positive one followed by its opposite.
+    Divisors {
+        value: i32,
+        next: i32,
+        negative: bool,
+    },
+}
+
+impl CandidateTops {
+    pub fn none() -> Self {
+        Self::progression(0, 0, 0)
+    }
+
+    pub fn only(top: i16) -> Self {
+        Self::progression(top as i32, 0, 1)
+    }
+
+    fn progression(first: i32, step: i32, count: i32) -> Self {
+        Self::Progression {
+            next: first,
+            step,
+            remaining: count,
+        }
+    }
+
+    /// Lists the divisors of the given value, which must not be 0.
+    fn divisors(value: i16) -> Self {
+        Self::Divisors {
+            value: (value as i32).abs(),
+            next: 1,
+            negative: false,
+        }
+    }
+}
+
+impl Iterator for CandidateTops {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        match self {
+            Self::Progression {
+                next,
+                step,
+                remaining,
+            } => {
+                while *remaining > 0 {
+                    let top = *next;
+                    *next += *step;
+                    *remaining -= 1;
+
+                    // Tops that do not fit on the dice are skipped.
+                    if let Ok(top) = i16::try_from(top) {
+                        return Some(top);
+                    }
+                }
+
+                None
+            }
+            Self::Divisors {
+                value,
+                next,
+                negative,
+            } => {
+                while *next <= *value {
+                    let top = if *negative {
+                        let divisor = *next;
+                        *negative = false;
+                        *next += 1;
+                        -divisor
+                    } else if *value % *next == 0 {
+                        *negative = true;
+                        *next
+                    } else {
+                        *next += 1;
+                        continue;
+                    };
+
+                    // Tops that do not fit on the dice are skipped.
+                    if let Ok(top) = i16::try_from(top) {
+                        return Some(top);
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// Lists the prime numbers which fit in an `i16`, sieved once on first use, as multipliers are
+/// looked up on every expansion.
+fn primes() -> &'static [i16] {
+    static PRIMES: OnceLock<Vec<i16>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let max = i16::MAX as usize;
+        let mut composite = vec![false; max + 1];
+        let mut primes = Vec::new();
+        for n in 2..=max {
+            if !composite[n] {
+                primes.push(n as i16);
+                (n * n..=max)
+                    .step_by(n)
+                    .for_each(|multiple| composite[multiple] = true);
+            }
+        }
+        primes
+    })
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Returns the inverse of `a` modulo `modulus`, where both are coprime.
+fn modular_inverse(a: i32, modulus: i32) -> i32 {
+    // Extended Euclidean algorithm, only tracking the coefficient of `a`.
+    let (mut old_r, mut r) = (a, modulus);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+
+    old_s.rem_euclid(modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_inferred_face_checks_the_face_range() {
+        let rules = Rules {
+            face_range: Some(FaceRange { min: -9, max: 9 }),
+            ..Rules::default()
+        };
+        let dice = rules
+            .set_inferred_face(Dice::default(), Face::Left, 9)
+            .unwrap();
+        assert_eq!(dice.get_face(Face::Left), Some(9));
+        assert!(rules.set_inferred_face(dice, Face::Top, 10).is_none());
+
+        assert!(Rules::default()
+            .set_inferred_face(dice, Face::Top, 10)
+            .is_some());
+    }
+
+    #[test]
+    fn set_inferred_face_infers_the_opposite_face() {
+        let rules = Rules {
+            face_range: Some(FaceRange { min: 0, max: 5 }),
+            opposite_sum: Some(5),
+            ..Rules::default()
+        };
+        let dice = rules
+            .set_inferred_face(Dice::default(), Face::Top, 1)
+            .unwrap();
+        assert_eq!(dice.get_face(Face::Bottom), Some(4));
+        // The opposite face would be -1, outside of the face range.
+        assert!(rules
+            .set_inferred_face(Dice::default(), Face::Left, 6)
+            .is_none());
+
+        // Once known, the opposite face must complete the sum.
+        let dice = Dice::default().set_face(Face::Front, 2);
+        assert!(rules.set_inferred_face(dice, Face::Back, 2).is_none());
+        assert!(rules.set_inferred_face(dice, Face::Back, 3).is_some());
+    }
+
+    #[test]
+    fn set_inferred_face_checks_the_allowed_faces() {
+        let rules = Rules {
+            allowed_faces: Some(Arc::from([1, 2, 3])),
+            distinct_faces: true,
+            ..Rules::default()
+        };
+        let dice = rules
+            .set_inferred_face(Dice::default(), Face::Top, 1)
+            .unwrap();
+        assert!(rules.set_inferred_face(dice, Face::Left, 4).is_none());
+        // The top already holds 1.
+        assert!(rules.set_inferred_face(dice, Face::Left, 1).is_none());
+        assert!(rules.set_inferred_face(dice, Face::Left, 2).is_some());
+
+        let repeating_rules = Rules {
+            distinct_faces: false,
+            ..rules
+        };
+        assert!(repeating_rules
+            .set_inferred_face(dice, Face::Left, 1)
+            .is_some());
+    }
+
+    #[test]
+    fn check_known_faces_reports_the_broken_constraint() {
+        let dice = Dice::from_sides([Some(1), Some(1), None, None, None, None]);
+        assert!(Rules::default().check_known_faces(&dice).is_ok());
+
+        let rules = Rules {
+            distinct_faces: true,
+            ..Rules::default()
+        };
+        assert_eq!(
+            rules.check_known_faces(&dice),
+            Err("the top and bottom faces both hold 1, while faces must be distinct".to_string())
+        );
+        let rules = Rules {
+            opposite_sum: Some(7),
+            ..Rules::default()
+        };
+        assert_eq!(
+            rules.check_known_faces(&dice),
+            Err("the top and bottom faces hold 1 and 1, which do not sum to 7".to_string())
+        );
+    }
+
+    #[test]
+    fn final_score_depends_on_the_recurrence() {
+        let rules = Rules {
+            arithmetic: Arithmetic::Modular { modulus: 7 },
+            final_score: FinalScore::EndCellValue,
+            ..Rules::default()
+        };
+        // The final score is the residue of the end cell value.
+        assert_eq!(rules.landed_score(Turn::from(10), 9), 2);
+        assert!(!rules.accepts_final_score(Turn::from(10), 9));
+        assert!(rules.accepts_final_score(Turn::from(10), 6));
+
+        let rules = Rules {
+            recurrence: Recurrence::Divisibility,
+            final_score: FinalScore::Target(12),
+            ..Rules::default()
+        };
+        // The score counts the turns.
+        assert!(rules.accepts_final_score(Turn::from(12), 60));
+        assert!(!rules.accepts_final_score(Turn::from(11), 60));
+        assert!(Rules::default().accepts_final_score(Turn::from(11), 60));
+    }
+
+    #[test]
+    fn bottom_scoring_uses_the_face_touching_the_board() {
+        let rules = Rules {
+            scoring: Scoring::Bottom,
+            ..Rules::default()
+        };
+        let dice = rules.set_inferred_score(Dice::default(), 4).unwrap();
+        assert_eq!(dice.get_face(Face::Bottom), Some(4));
+        assert_eq!(dice.get_face(Face::Top), None);
+        assert_eq!(rules.scoring.scored_value(&dice), Some(4));
+        assert_eq!(Scoring::Top.scored_value(&dice), None);
+    }
+
+    #[test]
+    fn visible_sum_scoring_infers_the_only_unknown_visible_face() {
+        let rules = Rules {
+            scoring: Scoring::VisibleSum,
+            ..Rules::default()
+        };
+        let dice = Dice::from_sides([Some(1), None, Some(2), Some(3), Some(4), None]);
+        assert_eq!(rules.scoring.scored_value(&dice), None);
+
+        let dice = rules.set_inferred_score(dice, 15).unwrap();
+        assert_eq!(dice.get_face(Face::Back), Some(5));
+        assert_eq!(dice.get_face(Face::Bottom), None);
+        assert_eq!(rules.scoring.scored_value(&dice), Some(15));
+
+        // With two unknown visible faces, the value cannot be distributed uniquely.
+        assert!(rules.set_inferred_score(Dice::default(), 15).is_none());
+    }
+
+    #[test]
+    fn divisibility_lands_on_multiples_of_the_scored_value() {
+        let rules = Rules {
+            recurrence: Recurrence::Divisibility,
+            ..Rules::default()
+        };
+        assert!(rules.lands(0, 3, 4, 12));
+        assert!(rules.lands(0, 3, -4, 12));
+        assert!(!rules.lands(0, 3, 5, 12));
+        assert!(rules.lands(0, 3, 0, 0));
+        assert!(!rules.lands(0, 3, 0, 12));
+        assert!(rules.lands_with_any_value(0, 3, 0));
+        assert!(!rules.lands_with_any_value(0, 3, 12));
+
+        assert_eq!(
+            rules.infer_values(0, 3, -12).collect::<Vec<_>>(),
+            vec![1, -1, 2, -2, 3, -3, 4, -4, 6, -6, 12, -12]
+        );
+        assert_eq!(rules.infer_values(0, 3, i16::MIN).last(), Some(i16::MIN));
+        assert_eq!(rules.initial_score(15), 0);
+        assert_eq!(rules.next_score(4, 3, 12), 5);
+        assert!(rules.can_land(0, 3, 0));
+    }
+
+    #[test]
+    fn multiplicative_recurrence_infers_the_exact_quotient() {
+        let rules = Rules {
+            recurrence: Recurrence::Multiplicative { initial: 1 },
+            ..Rules::default()
+        };
+        assert_eq!(rules.initial_score(15), 1);
+        assert_eq!(rules.next_score(-4, 3, 6), -24);
+        assert!(rules.lands(-4, 3, 6, -24));
+        assert!(!rules.lands(-4, 3, 5, -24));
+        assert_eq!(rules.infer_values(-4, 3, -24).collect::<Vec<_>>(), vec![6]);
+        assert_eq!(rules.infer_values(5, 3, 24).count(), 0);
+        assert_eq!(rules.infer_values(5, 3, 0).collect::<Vec<_>>(), vec![0]);
+
+        // A score of 0 can only land on 0, with any value.
+        assert!(rules.lands_with_any_value(0, 3, 0));
+        assert!(!rules.lands_with_any_value(5, 3, 0));
+        assert!(!rules.can_land(0, 3, 24));
+        assert!(rules.can_land(0, 3, 0));
+
+        let rules = Rules {
+            arithmetic: Arithmetic::Modular { modulus: 10 },
+            ..rules
+        };
+        // 4 x value = 6 (mod 10) has two solutions.
+        assert_eq!(rules.infer_values(4, 3, 6).collect::<Vec<_>>(), vec![4, 9]);
+        assert!(!rules.lands_with_any_value(0, 3, 0));
+        assert_eq!(rules.infer_values(10, 3, 20).count(), 10);
+    }
+
+    #[test]
+    fn ascending_multiplier_is_the_turn() {
+        let multiplier = Multiplier::default();
+        assert_eq!(multiplier, Multiplier::Ascending);
+        assert_eq!(multiplier.at(Turn::from(1)), Some(1));
+        assert_eq!(multiplier.at(Turn::from(32)), Some(32));
+    }
+
+    #[test]
+    fn descending_multiplier_counts_down() {
+        let multiplier = Multiplier::Descending { from: 3 };
+        assert_eq!(multiplier.at(Turn::from(1)), Some(3));
+        assert_eq!(multiplier.at(Turn::from(2)), Some(2));
+        assert_eq!(multiplier.at(Turn::from(3)), Some(1));
+        assert_eq!(multiplier.at(Turn::from(4)), None);
+        assert_eq!(multiplier.at(Turn::from(5)), None);
+    }
+
+    #[test]
+    fn fibonacci_multiplier_stops_when_it_overflows() {
+        let multiplier = Multiplier::Fibonacci;
+        let multipliers: Vec<_> = (1..=8)
+            .map_while(|turn| multiplier.at(Turn::from(turn)))
+            .collect();
+        assert_eq!(multipliers, vec![1, 1, 2, 3, 5, 8, 13, 21]);
+        assert_eq!(multiplier.at(Turn::from(23)), Some(28657));
+        assert_eq!(multiplier.at(Turn::from(24)), None);
+    }
+
+    #[test]
+    fn primes_multiplier_lists_the_primes() {
+        let multiplier = Multiplier::Primes;
+        let multipliers: Vec<_> = (1..=8)
+            .map_while(|turn| multiplier.at(Turn::from(turn)))
+            .collect();
+        assert_eq!(multipliers, vec![2, 3, 5, 7, 11, 13, 17, 19]);
+        assert_eq!(multiplier.at(Turn::from(32)), Some(131));
+        // The largest prime fitting in an `i16` is the 3512th one.
+        assert_eq!(multiplier.at(Turn::from(3512)), Some(32749));
+        assert_eq!(multiplier.at(Turn::from(3513)), None);
+    }
+
+    #[test]
+    fn sequence_multiplier_runs_out_with_the_sequence() {
+        let multiplier = Multiplier::Sequence(Arc::from([3, -1, 4]));
+        assert_eq!(multiplier.at(Turn::from(1)), Some(3));
+        assert_eq!(multiplier.at(Turn::from(2)), Some(-1));
+        assert_eq!(multiplier.at(Turn::from(3)), Some(4));
+        assert_eq!(multiplier.at(Turn::from(4)), None);
+    }
+
+    #[test]
+    fn integer_arithmetic_infers_the_exact_quotient() {
+        let arithmetic = Arithmetic::Integer;
+        assert_eq!(arithmetic.infer_tops(5, 2, 23).collect::<Vec<_>>(), vec![9]);
+        assert_eq!(
+            arithmetic.infer_tops(23, 3, -4).collect::<Vec<_>>(),
+            vec![-9]
+        );
+        assert_eq!(arithmetic.infer_tops(5, 2, 24).count(), 0);
+
+        assert!(arithmetic.lands(5, 2, 9, 23));
+        assert!(!arithmetic.lands(5, 2, 8, 23));
+    }
+
+    #[test]
+    fn modular_arithmetic_infers_all_residues() {
+        let arithmetic = Arithmetic::Modular { modulus: 10 };
+
+        // 4 x top = 6 (mod 10) has two solutions.
+        assert_eq!(
+            arithmetic.infer_tops(0, 4, 6).collect::<Vec<_>>(),
+            vec![4, 9]
+        );
+        // 4 x top = 5 (mod 10) has none.
+        assert_eq!(arithmetic.infer_tops(0, 4, 5).count(), 0);
+        // 3 x top = 7 (mod 10) has exactly one.
+        assert_eq!(arithmetic.infer_tops(0, 3, 7).collect::<Vec<_>>(), vec![9]);
+        // 10 x top = 20 (mod 10) is always true.
+        assert_eq!(arithmetic.infer_tops(5, 10, 25).count(), 10);
+        // Negative scores and values are handled too: 3 x top = -5 - 12 (mod 10).
+        assert_eq!(
+            arithmetic.infer_tops(12, 3, -5).collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        assert!(arithmetic.lands(0, 4, 9, 16));
+        assert!(arithmetic.lands(12, 3, 1, -5));
+        assert!(!arithmetic.lands(0, 4, 8, 16));
+        assert_eq!(arithmetic.reduce(-3), 7);
+    }
+
+    #[test]
+    fn modular_inverse_works() {
+        assert_eq!(modular_inverse(3, 10), 7);
+        assert_eq!(modular_inverse(2, 5), 3);
+        assert_eq!(modular_inverse(0, 1), 0);
+    }
+
+    #[test]
+    fn face_range_includes_both_ends() {
+        let range = FaceRange { min: -9, max: 9 };
+        assert!(range.contains(-9));
+        assert!(range.contains(0));
+        assert!(range.contains(9));
+        assert!(!range.contains(-10));
+        assert!(!range.contains(10));
+    }
+}
@@ -0,0 +1,50 @@
+use serde_json::{json, Value};
+
+/// Version of the JSON documents written by the solver, bumped whenever a field changes meaning or
+/// is removed, so that downstream parsers can tell which layout they are reading.
+pub const JSON_FORMAT_VERSION: u64 = 1;
+
+/// Returns the given JSON object with a `format_version` field in front of its own fields.
+/// Fields are written in the order they are inserted, so the layout of each document is the one
+/// spelled out where it is built.
+pub fn versioned(document: Value) -> Value {
+    let mut versioned = json!({ "format_version": JSON_FORMAT_VERSION });
+    if let (Value::Object(versioned), Value::Object(fields)) = (&mut versioned, document) {
+        versioned.extend(fields);
+    }
+
+    versioned
+}
+
+/// Checks that a JSON document read back was written with the current format version.
+pub fn check_format_version(document: &Value) -> Result<(), String> {
+    match document["format_version"].as_u64() {
+        Some(JSON_FORMAT_VERSION) => Ok(()),
+        Some(version) => Err(format!("unsupported format version {}", version)),
+        None => Err("the document has no format version".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versioned_documents_start_with_the_version() {
+        let document = versioned(json!({ "zebra": 1, "apple": [2, 3] }));
+        assert_eq!(
+            document.to_string(),
+            r#"{"format_version":1,"zebra":1,"apple":[2,3]}"#
+        );
+        assert!(check_format_version(&document).is_ok());
+    }
+
+    #[test]
+    fn check_format_version_rejects_other_versions() {
+        assert_eq!(
+            check_format_version(&json!({ "format_version": 0 })),
+            Err("unsupported format version 0".to_string())
+        );
+        assert!(check_format_version(&json!({})).is_err());
+    }
+}
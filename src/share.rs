@@ -0,0 +1,187 @@
+use crate::{
+    board::{Board, Movement},
+    direction::Direction,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use strum::IntoEnumIterator;
+
+/// Version of the encoding, stored in the first byte so that it can evolve.
+const SHARE_LINK_VERSION: u8 = 1;
+
+/// Encodes a board, and optionally the moves of a solution (e.g. `URRD`), into a compact URL-safe
+/// string. The encoding holds a version byte, the number of rows and columns of the board, the row
+/// and column of the end cell and the movement model on a byte each, the values of the board row
+/// by row as big-endian 16-bit integers, and then, if there are moves, their count as a big-endian
+/// 16-bit integer and the moves themselves, packed 4 to a byte. Returns an error if a letter is
+/// not a move, or if there are too many moves to count on 16 bits.
+pub fn encode_share_link(board: &Board, moves: Option<&str>) -> Result<String, String> {
+    let (end_row, end_col) = board.end();
+    let movement = match board.movement() {
+        Movement::Orthogonal => 0,
+        Movement::OrthogonalWrap => 1,
+    };
+    let mut bytes = vec![
+        SHARE_LINK_VERSION,
+        board.rows() as u8,
+        board.cols() as u8,
+        end_row as u8,
+        end_col as u8,
+        movement,
+    ];
+    for cell in board.cells() {
+        bytes.extend(cell.get_value().to_be_bytes());
+    }
+
+    if let Some(moves) = moves {
+        let directions: Vec<u8> = moves
+            .chars()
+            .map(|letter| {
+                Direction::from_letter(letter)
+                    .map(|direction| direction as u8)
+                    .ok_or_else(|| format!("{} is not a move", letter))
+            })
+            .collect::<Result<_, _>>()?;
+        let count = u16::try_from(directions.len())
+            .map_err(|_| format!("{} moves are too many to share", directions.len()))?;
+        bytes.extend(count.to_be_bytes());
+        for chunk in directions.chunks(4) {
+            bytes.push(
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (i, direction)| byte | direction << (6 - 2 * i)),
+            );
+        }
+    }
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Decodes a string produced by `encode_share_link` into the board, and the moves if any.
+pub fn decode_share_link(link: &str) -> Result<(Board, Option<String>), String> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(link.trim())
+        .map_err(|error| format!("this is not a share link: {}", error))?;
+    let (&version, bytes) = bytes.split_first().ok_or("the share link is empty")?;
+    if version != SHARE_LINK_VERSION {
+        return Err(format!("unsupported share link version {}", version));
+    }
+    let [rows, cols, end_row, end_col, movement, bytes @ ..] = bytes else {
+        return Err("the share link has no dimensions".to_string());
+    };
+    let (rows, cols) = (*rows as usize, *cols as usize);
+
+    let (values, bytes) = bytes
+        .split_at_checked(2 * rows * cols)
+        .ok_or("the share link is missing values of the board")?;
+    let rows: Vec<Vec<i64>> = values
+        .chunks(2)
+        .map(|value| i16::from_be_bytes([value[0], value[1]]) as i64)
+        .collect::<Vec<_>>()
+        .chunks(cols.max(1))
+        .map(<[i64]>::to_vec)
+        .collect();
+    let board = Board::try_from(rows)
+        .map_err(|error| error.to_string())?
+        .set_end((*end_row as usize, *end_col as usize))
+        .ok_or("the end cell of the share link is not on the board")?
+        .set_movement(match movement {
+            0 => Movement::Orthogonal,
+            1 => Movement::OrthogonalWrap,
+            movement => return Err(format!("unsupported movement {}", movement)),
+        });
+
+    if bytes.is_empty() {
+        return Ok((board, None));
+    }
+    let (count, packed) = bytes
+        .split_at_checked(2)
+        .ok_or("the share link has a truncated number of moves")?;
+    let count = u16::from_be_bytes([count[0], count[1]]) as usize;
+    if packed.len() != count.div_ceil(4) {
+        return Err(format!(
+            "the share link should hold {} moves, but holds {} bytes of them",
+            count,
+            packed.len()
+        ));
+    }
+    let directions: Vec<Direction> = Direction::iter().collect();
+    let moves = (0..count)
+        .map(|i| directions[(packed[i / 4] >> (6 - 2 * (i % 4)) & 0b11) as usize].letter())
+        .collect();
+
+    Ok((board, Some(moves)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_link_round_trips() {
+        let board = Board::new();
+        for moves in [None, Some(""), Some("U"), Some("URDLU"), Some("URRDLLURRD")] {
+            let link = encode_share_link(&board, moves).unwrap();
+            assert!(link
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+            let (decoded_board, decoded_moves) = decode_share_link(&link).unwrap();
+            assert_eq!(decoded_board.canonical_form(), board.canonical_form());
+            assert_eq!(decoded_moves.as_deref(), moves);
+        }
+    }
+
+    #[test]
+    fn share_link_holds_the_dimensions_of_the_board() {
+        let board = Board::from_rows([[1, -2, 3], [4, 5, 6]]);
+        let link = encode_share_link(&board, None).unwrap();
+        let (decoded_board, _) = decode_share_link(&link).unwrap();
+        assert_eq!((decoded_board.rows(), decoded_board.cols()), (2, 3));
+        assert_eq!(decoded_board.canonical_form(), "1,-2,3;4,5,6");
+    }
+
+    #[test]
+    fn share_link_holds_the_end_cell_and_the_movement() {
+        let board = Board::from_rows([[1, -2, 3], [4, 5, 6]])
+            .set_end((1, 2))
+            .unwrap()
+            .set_movement(Movement::OrthogonalWrap);
+        let link = encode_share_link(&board, Some("LU")).unwrap();
+        let (decoded_board, decoded_moves) = decode_share_link(&link).unwrap();
+        assert_eq!(decoded_board.canonical_form(), board.canonical_form());
+        assert_eq!(decoded_board.end(), (1, 2));
+        assert_eq!(decoded_board.movement(), Movement::OrthogonalWrap);
+        assert_eq!(decoded_moves.as_deref(), Some("LU"));
+    }
+
+    #[test]
+    fn decode_share_link_rejects_invalid_links() {
+        assert!(decode_share_link("").is_err());
+        assert!(decode_share_link("not base64!").is_err());
+        assert!(decode_share_link("AQAA").is_err());
+        // Links of unknown versions cannot be decoded.
+        assert!(decode_share_link(&URL_SAFE_NO_PAD.encode([2, 1, 2, 0, 1, 0, 2])).is_err());
+        // An end cell off the board, or an unknown movement model, is rejected.
+        assert!(
+            decode_share_link(&URL_SAFE_NO_PAD.encode([1, 1, 2, 1, 0, 0, 0, 1, 0, 2])).is_err()
+        );
+        assert!(
+            decode_share_link(&URL_SAFE_NO_PAD.encode([1, 1, 2, 0, 1, 2, 0, 1, 0, 2])).is_err()
+        );
+
+        let link = encode_share_link(&Board::new(), Some("URDL")).unwrap();
+        // Dropping the last move byte leaves a count of moves without the moves.
+        assert!(decode_share_link(&link[..link.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn encode_share_link_rejects_moves_it_cannot_hold() {
+        assert!(encode_share_link(&Board::new(), Some("URDX")).is_err());
+        let moves = "U".repeat(u16::MAX as usize + 1);
+        assert!(encode_share_link(&Board::new(), Some(&moves)).is_err());
+        let moves = "U".repeat(u16::MAX as usize);
+        assert!(encode_share_link(&Board::new(), Some(&moves)).is_ok());
+    }
+}
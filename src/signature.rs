@@ -0,0 +1,71 @@
+use crate::board::Board;
+
+/// Computes a short signature identifying a solution to a board, so that people can compare their
+/// answers without exchanging them in full. The signature is a hash of the canonical form of the
+/// board and of the moves of the solution, written as 16 hexadecimal digits.
+pub fn compute_signature(board: &Board, moves: &str) -> String {
+    format!(
+        "{:016x}",
+        fnv1a(format!("{}\n{}", board.canonical_form(), moves).as_bytes())
+    )
+}
+
+/// Computes a short hash identifying a board, written as 16 hexadecimal digits, e.g. to track the
+/// performance of the solver on it across runs.
+pub fn compute_board_hash(board: &Board) -> String {
+    format!("{:016x}", fnv1a(board.canonical_form().as_bytes()))
+}
+
+/// 64-bit FNV-1a hash. Unlike the hashers of the standard library, it is guaranteed to remain the
+/// same across Rust versions and platforms, which signatures rely on.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Movement;
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn signature_depends_on_the_board_and_the_moves() {
+        let board = Board::new();
+        let signature = compute_signature(&board, "URRD");
+        assert_eq!(signature.len(), 16);
+        assert_eq!(signature, compute_signature(&Board::new(), "URRD"));
+
+        assert_ne!(signature, compute_signature(&board, "URRU"));
+        assert_ne!(
+            signature,
+            compute_signature(&Board::from_rows([[0; 6]; 6]), "URRD")
+        );
+    }
+
+    #[test]
+    fn board_hash_depends_on_the_whole_board() {
+        let hash = compute_board_hash(&Board::new());
+        assert_eq!(hash.len(), 16);
+        assert_eq!(hash, compute_board_hash(&Board::new()));
+        assert_ne!(hash, compute_board_hash(&Board::from_rows([[0; 6]; 6])));
+        assert_ne!(
+            hash,
+            compute_board_hash(&Board::new().set_movement(Movement::OrthogonalWrap))
+        );
+        assert_ne!(
+            hash,
+            compute_board_hash(&Board::new().set_end((0, 0)).unwrap())
+        );
+    }
+}
@@ -1,308 +1,3867 @@
 use crate::{
-    board::{Board, Cell},
-    dice::Dice,
+    ancestry::{export_ancestry, AncestryFormat, Branch, Decision},
+    board::{Board, Cell, Notation, Position},
+    dice::{Dice, Face},
     direction::Direction,
+    events::EventLog,
+    frontier::{Frontier, Strategy},
+    invariants::{Invariants, Unsolvable},
+    objective::{Objective, SolutionKey},
+    partial::{PartialCredit, PartialJourney},
+    progress::ProgressReporter,
+    record::SearchRecorder,
+    render::draw_journey_svg,
+    report::{
+        describe_direction, describe_position, turn_multiplier, ExplanationSettings, SolutionReport,
+    },
+    rules::{
+        Arithmetic, CandidateTops, FaceRange, FinalScore, Multiplier, Recurrence, Rules, Scoring,
+    },
+    schema::{check_format_version, versioned},
+    signature::compute_board_hash,
+    stats::MoveStats,
+    turn::Turn,
 };
 
-use std::collections::{HashSet, VecDeque};
+use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt,
+    io::{self, Write},
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use strum::IntoEnumIterator;
 
 /// Represents a candidate solution to the puzzle. The candidate might not have reached the end cell,
-/// and might not have not a full knowledge of what values the dice has. Each valid movement
-/// creates a new distinct journey, from which we can then potentially create other journeys.
+/// and might not have not a full knowledge of what values the dice has. Journeys are rebuilt from
+/// the solver's arena of steps only when needed, e.g. to explain a solution.
+///
+/// A journey is never empty: it holds its start cell apart from its movements, so that its visited
+/// cells always include a first and a last one, and no empty journey can be built.
 #[derive(Debug)]
 struct Journey {
     /// Current disposition of the dice, which values might be partially known.
     dice: Dice,
-    /// How many rotations have been applied to the dice throughout this journey.
-    turn: i16,
-    /// The visited cells are ordered movement by movement, the last of which being
-    /// the current cell being visited. This list can never be empty!
-    visited_cells: Vec<Cell>,
+    /// The cell the journey started on.
+    start_cell: Cell,
+    /// The directions the dice rolled in, movement by movement, as recorded while searching, each
+    /// with the cell it landed on. The last of them is the current cell being visited, unless the
+    /// dice has not moved yet.
+    movements: Vec<(Direction, Cell)>,
 }
 
 impl Journey {
-    pub fn get_last_visited_cell(&self) -> &Cell {
-        self.visited_cells
-            .last()
-            .expect("A journey must have visited at least one cell.")
-    }
-
-    fn explain(&self) -> String {
-        // First, go backwards through the visited cells. This will help us list the dice movements,
-        // and figure out the initial configuration of the dice.
-        let mut last_visited_cell = self.get_last_visited_cell();
-        let mut dice_movements = Vec::new();
-        let mut dice = self.dice.clone();
-        for second_to_last_visited_cell in self.visited_cells.iter().rev().skip(1) {
-            let (second_to_last_row, second_to_last_col) =
-                second_to_last_visited_cell.get_position();
-            let (last_row, last_col) = last_visited_cell.get_position();
-
-            // Here, we note the dice movement going from cell n-1 to cell n, and apply the opposite
-            // roll to that movement, such that we move the dice from cell n to cell n-1.
-            if second_to_last_row < last_row && second_to_last_col == last_col {
-                dice_movements.push(Direction::DOWN);
-                dice = dice.roll_up();
-            } else if second_to_last_row > last_row && second_to_last_col == last_col {
-                dice_movements.push(Direction::UP);
-                dice = dice.roll_down();
-            } else if second_to_last_col < last_col && second_to_last_row == last_row {
-                dice_movements.push(Direction::RIGHT);
-                dice = dice.roll_left();
-            } else if second_to_last_col > last_col && second_to_last_row == last_row {
-                dice_movements.push(Direction::LEFT);
-                dice = dice.roll_right();
-            } else {
-                panic!(
-                    "dice has to move orthogonally, but got ({},{}) ({},{})",
-                    second_to_last_row, second_to_last_col, last_row, last_col
-                );
-            }
-
-            last_visited_cell = second_to_last_visited_cell;
+    /// Figures out the initial configuration of the dice, by going backwards through the movements
+    /// and applying the opposite roll to each of them.
+    fn initial_dice(&self) -> Dice {
+        let mut dice = self.dice;
+        for (dice_movement, _) in self.movements.iter().rev() {
+            dice = dice.roll_in(dice_movement.opposite());
         }
 
-        let mut explanation = Vec::new();
-        explanation.push(format!("We started with the following dice: {:?}", dice));
+        dice
+    }
+
+    /// Lists the visited cells in order, from the start cell to the current cell.
+    fn visited_cells(&self) -> impl Iterator<Item = &Cell> {
+        std::iter::once(&self.start_cell).chain(self.movements.iter().map(|(_, cell)| cell))
+    }
+
+    /// Writes one CSV row per turn, with the direction of the roll, the position reached, the value
+    /// scored by the dice, the score and the value of the cell reached, after a header row.
+    fn write_turns_csv(&self, rules: &Rules, writer: &mut dyn Write) -> io::Result<()> {
+        let mut dice = self.initial_dice();
 
-        // Now that we have made back it the start cell, explain the movements applied from start to end.
-        dice_movements.reverse();
-        let mut score = 0;
-        for (turn, dice_movement) in dice_movements.into_iter().enumerate() {
+        writeln!(
+            writer,
+            "turn,direction,row,col,{},score,cell_value",
+            rules.scoring.name()
+        )?;
+        let mut score = rules.initial_score(self.start_cell.get_value());
+        for (turn, &(dice_movement, cell)) in self.movements.iter().enumerate() {
             dice = dice.roll_in(dice_movement);
 
-            let dice_top = dice.get_top().unwrap();
-            let new_score = score + (turn as i16 + 1) * dice_top;
-            explanation.push(
-            format!(
-                "Turn {} we rolled the dice {:?} (top={}). Score was {}, now is `{} + ({} x {}) = {}` (cell value = {}).",
-                turn+1,
+            // Only values landing on any cell can remain unknown, and they do not change the score.
+            let scored_value = rules.scoring.scored_value(&dice);
+            let turn_multiplier = turn_multiplier(rules, turn + 1).map_err(io::Error::other)?;
+            score = rules.next_score(score, turn_multiplier, scored_value.unwrap_or_default());
+            let (row, col) = cell.get_position();
+            writeln!(
+                writer,
+                "{},{:?},{},{},{},{},{}",
+                turn + 1,
                 dice_movement,
-                dice_top, score, score, turn+1, dice_top, new_score, self.visited_cells[turn+1].get_value()
-            ));
+                row,
+                col,
+                scored_value.map_or(String::new(), |value| value.to_string()),
+                score,
+                cell.get_value()
+            )?;
+        }
+
+        writer.flush()
+    }
 
-            score = new_score;
+    /// Draws the journey on the board as an SVG document, animated or not, to the given writer.
+    fn write_svg(&self, board: &Board, animated: bool, writer: &mut dyn Write) -> io::Result<()> {
+        let mut dice = self.initial_dice();
+        let mut frames = vec![(*self.start_cell.get_position(), dice.get_face(Face::Top))];
+        for &(dice_movement, cell) in &self.movements {
+            dice = dice.roll_in(dice_movement);
+            frames.push((*cell.get_position(), dice.get_face(Face::Top)));
         }
 
-        explanation.join("\n")
+        writeln!(writer, "{}", draw_journey_svg(board, &frames, animated))?;
+        writer.flush()
+    }
+}
+
+/// A single step of a candidate journey, stored in the solver's arena of steps. Each step points
+/// back to the step it was reached from, so that journeys sharing a common prefix also share its
+/// storage, and extending a journey never copies its visited cells.
+#[derive(Clone, Copy, Debug)]
+struct Step {
+    /// Disposition of the dice after this step.
+    dice: Dice,
+    /// How many rotations have been applied to the dice up to this step.
+    turn: Turn,
+    /// The cell the dice landed onto during this step.
+    cell: Cell,
+    /// Index of the previous step in the arena, and the direction the dice rolled in from it
+    /// during this step, or `None` for the start cell.
+    reached_from: Option<(usize, Direction)>,
+    /// Waypoints visited up to this step, as a bitmask indexed like the solver's waypoints.
+    waypoints: u64,
+}
+
+impl Step {
+    /// Returns the index of the previous step in the arena, or `None` for the start cell.
+    fn previous(self) -> Option<usize> {
+        self.reached_from.map(|(previous, _)| previous)
+    }
+
+    /// Returns the direction the dice rolled in during this step, or `None` for the start cell.
+    fn direction(self) -> Option<Direction> {
+        self.reached_from.map(|(_, direction)| direction)
+    }
+
+    /// Describes the step as JSON, to save the state of a search.
+    fn to_json(self) -> Value {
+        let (row, col) = *self.cell.get_position();
+        json!({
+            "dice": Face::iter().map(|face| self.dice.get_face(face)).collect::<Vec<_>>(),
+            "turn": i16::from(self.turn),
+            "position": [row, col],
+            "direction": self.direction().map(|direction| direction.letter().to_string()),
+            "previous": self.previous(),
+            "waypoints": self.waypoints,
+        })
+    }
+
+    /// Reads back a step described by `to_json`, landing on a cell of the given board.
+    fn from_json(step: &Value, board: &Board) -> Option<Self> {
+        let sides = step["dice"]
+            .as_array()?
+            .iter()
+            .map(|value| match value {
+                Value::Null => Some(None),
+                value => Some(Some(i16::try_from(value.as_i64()?).ok()?)),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let position = step["position"].as_array()?;
+        let [row, col] = position.as_slice() else {
+            return None;
+        };
+        // Either both the previous step and the direction are given, or neither is.
+        let reached_from = match (&step["previous"], &step["direction"]) {
+            (Value::Null, Value::Null) => None,
+            (previous, direction) => Some((
+                usize::try_from(previous.as_u64()?).ok()?,
+                Direction::from_letter(direction.as_str()?.chars().next()?)?,
+            )),
+        };
+
+        Some(Self {
+            dice: Dice::from_sides(sides.try_into().ok()?),
+            turn: Turn::from(i16::try_from(step["turn"].as_i64()?).ok()?),
+            cell: board.get_cell_at((
+                usize::try_from(row.as_u64()?).ok()?,
+                usize::try_from(col.as_u64()?).ok()?,
+            ))?,
+            reached_from,
+            waypoints: step["waypoints"].as_u64()?,
+        })
     }
 }
 
-/// Enumerates the possible outcomes when trying to roll a dice to an orthogonal cell.
-enum MovementOutcome {
-    /// A journey that made it all the way to the end cell.
-    SolutionJourney(Journey),
-    /// A journey that is valid, but has not reached the end cell.
-    ValidJourney(Journey),
-    /// A journey that is invalid, per the puzzle rules.
-    Invalid,
+/// Enumerates the orders in which waypoints can be required to be visited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WaypointOrder {
+    /// Waypoints can be visited in any order.
+    #[default]
+    Any,
+    /// Waypoints must be visited in the order they were given. Landing on a waypoint before the
+    /// previous ones have been visited does not count as visiting it.
+    Given,
 }
 
+/// Maximum number of waypoints a journey can be required to visit.
+pub const MAX_WAYPOINTS: usize = u64::BITS as usize;
+
 /// Enumerates the possible outcomes when solving the puzzle.
 pub enum Solution {
-    /// If found, this holds the sum of unvisited cells, as well as an explanation message.
-    Found(i16, String),
-    /// No solutions found.
-    NotFound,
+    /// If found, this holds a report of the solution, listing its moves and turns, and explaining
+    /// it when displayed.
+    Found(SolutionReport),
+    /// No solutions found, for the given reason, after the work described by the counters. Holds
+    /// the journey coming the closest to a solution, if partial credit was asked for.
+    NotFound(NotFoundReason, SearchStats, Option<PartialJourney>),
+}
+
+impl Solution {
+    /// Describes the outcome of the search as a JSON document, holding the report of the solution
+    /// if one was found, or why none was otherwise.
+    pub fn to_json(&self) -> Value {
+        versioned(match self {
+            Self::Found(report) => json!({ "found": true, "solution": report.to_json() }),
+            Self::NotFound(reason, stats, _) => json!({
+                "found": false,
+                "reason": reason.to_string(),
+                "expanded": stats.expanded,
+            }),
+        })
+    }
+}
+
+/// Enumerates the files the solver writes about the solution it finds, if asked to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolverOutput {
+    /// The turns of the solution, as CSV.
+    TurnsCsv,
+    /// The journey of the solution, drawn as SVG.
+    JourneySvg,
+    /// The decision points of the solution.
+    Ancestry,
+}
+
+impl fmt::Display for SolverOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TurnsCsv => write!(f, "the turns of the solution"),
+            Self::JourneySvg => write!(f, "the journey of the solution"),
+            Self::Ancestry => write!(f, "the ancestry of the solution"),
+        }
+    }
+}
+
+/// Enumerates the reasons why a search ends without a solution, telling a puzzle which provably has
+/// no solution from a search which gave up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotFoundReason {
+    /// Every journey allowed by the rules was explored, so the puzzle has no solution.
+    Exhausted,
+    /// The search stopped at the given limit before exploring every journey, so solutions might
+    /// exist beyond it.
+    LimitHit(SearchLimit),
+    /// The invariants of the board and the rules prove that no journey from the start cell can
+    /// reach the end cell, so the search did not even start.
+    Contradictory(Unsolvable),
+}
+
+/// Enumerates the limits a search can give up at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchLimit {
+    /// Journeys were not explored past the maximum depth.
+    Depth,
+    /// The time limit was hit.
+    Time,
+}
+
+impl fmt::Display for NotFoundReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exhausted => write!(f, "every journey allowed by the rules was explored"),
+            Self::LimitHit(SearchLimit::Depth) => {
+                write!(f, "journeys were not explored past the maximum depth")
+            }
+            Self::LimitHit(SearchLimit::Time) => write!(f, "the time limit was hit"),
+            Self::Contradictory(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Enumerates the reasons why a journey cannot be explained, which only happens if it does not
+/// follow the rules it is explained with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExplanationError {
+    /// The rules define no multiplier for the given turn, so the dice could not have rolled then.
+    NoMultiplier { turn: usize },
+}
+
+impl fmt::Display for ExplanationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoMultiplier { turn } => {
+                write!(f, "the rules define no multiplier for turn {}", turn)
+            }
+        }
+    }
+}
+
+impl Error for ExplanationError {}
+
+/// Enumerates the ways a solution can be explained.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ExplanationStyle {
+    /// One line per turn.
+    #[default]
+    Text,
+    /// One line per turn, each followed by a pseudo-3D drawing of the dice, showing its orientation.
+    Isometric,
+    /// Full sentences with explicit coordinates and no symbols, suited to screen readers.
+    Accessible,
+}
+
+/// Enumerates the ways the unfolded net of the initial dice can be drawn in an explanation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NetFormat {
+    /// Squares drawn with ASCII characters.
+    Text,
+    /// An SVG document, to be printed.
+    Svg,
+}
+
+/// How many expansions are summarized in a single `expand-batch` event.
+#[cfg(not(test))]
+const EVENT_BATCH_SIZE: u64 = 1_000;
+// Searches on the puzzle board are short, so tests use smaller batches to observe some of them.
+#[cfg(test)]
+const EVENT_BATCH_SIZE: u64 = 10;
+
+/// Counters describing the work done by the solver so far.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchStats {
+    /// Steps popped from the frontier and expanded.
+    pub expanded: u64,
+    /// Valid steps created by expansions, including solution steps.
+    pub generated: u64,
+    /// Movements within the board that were rejected by the rules.
+    pub rejected: u64,
+    /// Steps or movements discarded, as they could not lead to a (better) solution.
+    pub pruned: u64,
+    /// Most steps waiting in the frontier at once.
+    pub peak_frontier: u64,
+    /// Steps stored in the arena, which holds every step generated, as the search does not
+    /// deduplicate them.
+    pub stored: u64,
+}
+
+/// Groups the options a solver can be initialized with, all of which can also be changed afterwards
+/// with the `set_*` methods of the solver.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolverOptions {
+    pub strategy: Strategy,
+    pub rules: Rules,
+    pub explanation_style: ExplanationStyle,
+    /// Maximum number of turns of a journey, if any.
+    pub max_depth: Option<Turn>,
+}
+
+/// Configures a solver step by step, before building it. Unlike the `set_*` methods of the solver,
+/// the board can also be chosen, as it is needed to initialize the solver.
+#[derive(Clone, Default)]
+pub struct SolverBuilder {
+    board: Option<Board>,
+    options: SolverOptions,
+    dice: Dice,
+    max_revisits: Option<u16>,
+    max_straight: Option<u16>,
+    net_format: Option<NetFormat>,
+    verification: bool,
+    notation: Notation,
+    avoided_cells: Vec<Position>,
+    waypoints: Vec<Position>,
+    waypoint_order: WaypointOrder,
+}
+
+impl SolverBuilder {
+    /// Returns the rules configured so far.
+    pub fn rules(&self) -> &Rules {
+        &self.options.rules
+    }
+
+    /// Solves the given board instead of the puzzle board.
+    pub fn board(mut self, board: Board) -> Self {
+        self.board = Some(board);
+
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.options.strategy = strategy;
+
+        self
+    }
+
+    pub fn multiplier(mut self, multiplier: Multiplier) -> Self {
+        self.options.rules.multiplier = multiplier;
+
+        self
+    }
+
+    pub fn arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.options.rules.arithmetic = arithmetic;
+
+        self
+    }
+
+    pub fn recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.options.rules.recurrence = recurrence;
+
+        self
+    }
+
+    pub fn scoring(mut self, scoring: Scoring) -> Self {
+        self.options.rules.scoring = scoring;
+
+        self
+    }
+
+    pub fn face_range(mut self, face_range: FaceRange) -> Self {
+        self.options.rules.face_range = Some(face_range);
+
+        self
+    }
+
+    pub fn opposite_sum(mut self, opposite_sum: i16) -> Self {
+        self.options.rules.opposite_sum = Some(opposite_sum);
+
+        self
+    }
+
+    /// Only allows the given values on the faces of the dice.
+    pub fn allowed_faces(mut self, allowed_faces: Arc<[i16]>) -> Self {
+        self.options.rules.allowed_faces = Some(allowed_faces);
+
+        self
+    }
+
+    /// Forbids two faces of the dice from holding the same value.
+    pub fn distinct_faces(mut self) -> Self {
+        self.options.rules.distinct_faces = true;
+
+        self
+    }
+
+    pub fn final_score(mut self, final_score: FinalScore) -> Self {
+        self.options.rules.final_score = final_score;
+
+        self
+    }
+
+    pub fn explanation_style(mut self, explanation_style: ExplanationStyle) -> Self {
+        self.options.explanation_style = explanation_style;
+
+        self
+    }
+
+    /// Limits journeys to the given number of turns.
+    pub fn max_depth(mut self, max_depth: i16) -> Self {
+        self.options.max_depth = Some(Turn::from(max_depth));
+
+        self
+    }
+
+    /// Limits how many times each cell can be revisited by a journey.
+    pub fn max_revisits(mut self, max_revisits: u16) -> Self {
+        self.max_revisits = Some(max_revisits);
+
+        self
+    }
+
+    /// Limits how many times in a row the dice can roll in the same direction.
+    pub fn max_straight(mut self, max_straight: u16) -> Self {
+        self.max_straight = Some(max_straight);
+
+        self
+    }
+
+    /// Starts with the given dice, instead of one which values are all unknown.
+    pub fn dice(mut self, dice: Dice) -> Self {
+        self.dice = dice;
+
+        self
+    }
+
+    pub fn net_format(mut self, net_format: NetFormat) -> Self {
+        self.net_format = Some(net_format);
+
+        self
+    }
+
+    pub fn verification(mut self, verification: bool) -> Self {
+        self.verification = verification;
+
+        self
+    }
+
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+
+        self
+    }
+
+    /// Forbids the dice to land on the given cells, which are checked when building the solver.
+    pub fn avoided_cells(mut self, avoided_cells: Vec<Position>) -> Self {
+        self.avoided_cells = avoided_cells;
+
+        self
+    }
+
+    /// Requires solutions to visit the given cells in the given order, which are checked when
+    /// building the solver.
+    pub fn waypoints(mut self, waypoints: Vec<Position>, order: WaypointOrder) -> Self {
+        self.waypoints = waypoints;
+        self.waypoint_order = order;
+
+        self
+    }
+
+    /// Builds the solver, or returns `None` if an avoided cell or a waypoint is not a cell of the
+    /// board, or if there are more than `MAX_WAYPOINTS` waypoints.
+    pub fn build(self) -> Option<Solver> {
+        let SolverOptions {
+            strategy,
+            rules,
+            explanation_style,
+            max_depth,
+        } = self.options;
+        let mut solver = Solver::with_board(self.board.unwrap_or_default())
+            .set_strategy(strategy)
+            .set_multiplier(rules.multiplier)
+            .set_arithmetic(rules.arithmetic)
+            .set_recurrence(rules.recurrence)
+            .set_scoring(rules.scoring)
+            .set_face_range(rules.face_range)
+            .set_opposite_sum(rules.opposite_sum)
+            .set_allowed_faces(rules.allowed_faces, rules.distinct_faces)
+            .set_final_score(rules.final_score)
+            .set_explanation_style(explanation_style)
+            .set_dice(self.dice)
+            .set_verification(self.verification)
+            .set_notation(self.notation);
+        if let Some(max_depth) = max_depth {
+            solver = solver.set_max_depth(max_depth.into());
+        }
+        if let Some(max_revisits) = self.max_revisits {
+            solver = solver.set_max_revisits(max_revisits);
+        }
+        if let Some(max_straight) = self.max_straight {
+            solver = solver.set_max_straight(max_straight);
+        }
+        if let Some(net_format) = self.net_format {
+            solver = solver.set_net_format(net_format);
+        }
+
+        solver
+            .set_avoided_cells(self.avoided_cells)?
+            .set_waypoints(self.waypoints, self.waypoint_order)
+    }
 }
 
-/// Solves the puzzle by using a BFS traversal.
+/// Solves the puzzle by traversing the state space, using BFS by default.
 pub struct Solver {
-    board: Board,
-    /// Keeps track of a FIFO list of all the candidate journeys, one of which should eventually be
-    /// a solution to the puzzle.  
-    journeys: VecDeque<Journey>,
+    /// The board searched, shared with the reports of the solutions found on it.
+    board: Arc<Board>,
+    /// Arena holding every step created during the traversal. Steps are never removed, so that
+    /// any journey can be rebuilt by following the `previous` links.
+    steps: Vec<Step>,
+    /// Keeps track of the last steps of all the candidate journeys, one of which should eventually
+    /// be a solution to the puzzle. The order in which they are explored depends on the strategy.
+    frontier: Frontier,
+    /// Strategy used to order the frontier.
+    strategy: Strategy,
+    /// Rules of the variant of the puzzle being solved.
+    rules: Rules,
+    /// Journeys are not extended past this many turns, if set.
+    max_depth: Option<Turn>,
+    /// Journeys cannot revisit a cell more than this many times, if set.
+    max_revisits: Option<u16>,
+    /// Journeys cannot roll in the same direction more than this many times in a row, if set.
+    max_straight: Option<u16>,
+    explanation_style: ExplanationStyle,
+    /// How to draw the net of the initial dice in explanations, if at all.
+    net_format: Option<NetFormat>,
+    /// Whether explanations end with a table verifying each turn.
+    verification: bool,
+    /// How positions are written in explanations.
+    notation: Notation,
+    stats: SearchStats,
+    /// Where to report the progress of the search, if anywhere.
+    events: Option<EventLog>,
+    /// Callback to report the progress of the search to at a regular interval, if any.
+    progress: Option<ProgressReporter>,
+    /// Where to write the turns of the solution as CSV, if anywhere.
+    turns_csv: Option<Box<dyn Write + Send>>,
+    /// Where to draw the journey of the solution as SVG, if anywhere, and whether to animate it.
+    journey_svg: Option<(Box<dyn Write + Send>, bool)>,
+    /// Where to export the decision points of the solution, if anywhere, and in which format.
+    ancestry: Option<(Box<dyn Write + Send>, AncestryFormat)>,
+    /// Errors hit writing the files above, handed to the report of the solution.
+    output_errors: Vec<(SolverOutput, Arc<io::Error>)>,
+    /// Where to log every step of the search, if anywhere.
+    recorder: Option<SearchRecorder>,
+    /// Metric to choose the journey coming the closest to a solution on, if none is found.
+    partial_credit: Option<PartialCredit>,
+    /// Invariants of the board, derived when the search starts, to prune dead ends early.
+    invariants: Option<Invariants>,
+    /// Cells every solution must visit, if any.
+    waypoints: Vec<Position>,
+    waypoint_order: WaypointOrder,
+    /// Cells the dice must never land on, as if they were walls.
+    avoided_cells: Vec<Position>,
+    /// Direction of the first movement of every journey explored, if restricted.
+    first_move: Option<Direction>,
+    /// Whether journeys come in mirror images, in which case only one journey of each pair is
+    /// explored.
+    symmetric: bool,
+    /// Whether `next_solution` started enumerating solutions.
+    enumerating: bool,
+    /// Last step of the mirror image of the last solution enumerated, which is to be enumerated
+    /// next, if any.
+    pending_mirror: Option<usize>,
+    /// How many solutions `next_solution` returned so far.
+    enumerated: usize,
+    /// Moves of a previous solution to warm-start the search from, if any.
+    warm_start: Option<Vec<Direction>>,
 }
 
 impl Solver {
-    /// Initializes a solver.
+    /// Initializes a solver for the puzzle board.
     pub fn new() -> Self {
-        let board = Board::new();
-        let first_journey = Journey {
+        Self::with_board(Board::new())
+    }
+
+    /// Returns a builder to configure a solver with.
+    ///
+    /// ```
+    /// use die_agony::{Board, Solution, Solver};
+    ///
+    /// let board = Board::from_rows([[0, 5, 3], [0, 2, 0]]);
+    /// let solver = Solver::builder().board(board).max_depth(5).build().unwrap();
+    /// let Solution::Found(report) = solver.solve() else {
+    ///     panic!("the board has a solution");
+    /// };
+    /// assert_eq!((report.unvisited_sum, report.moves_as_letters()), (5, "RRU".to_string()));
+    /// ```
+    pub fn builder() -> SolverBuilder {
+        SolverBuilder::default()
+    }
+
+    /// Initializes a solver for the given board.
+    pub fn with_board(board: Board) -> Self {
+        Self::with_options(board, SolverOptions::default())
+    }
+
+    /// Initializes a solver for the given board, configured with the given options.
+    pub fn with_options(board: Board, options: SolverOptions) -> Self {
+        let first_step = Step {
             dice: Dice::default(),
-            turn: 0,
-            visited_cells: vec![board.start_cell()],
+            turn: Turn::START,
+            cell: board.start_cell(),
+            reached_from: None,
+            waypoints: 0,
         };
 
+        let mut frontier = Frontier::new(options.strategy);
+        frontier.push(
+            0,
+            first_step.turn,
+            board.distance_to_end_cell(&first_step.cell),
+        );
+
         Self {
-            board,
-            journeys: VecDeque::from([first_journey]),
+            board: Arc::new(board),
+            steps: vec![first_step],
+            frontier,
+            strategy: options.strategy,
+            rules: options.rules,
+            max_depth: options.max_depth,
+            max_revisits: None,
+            max_straight: None,
+            explanation_style: options.explanation_style,
+            net_format: None,
+            verification: false,
+            notation: Notation::default(),
+            stats: SearchStats::default(),
+            events: None,
+            progress: None,
+            turns_csv: None,
+            journey_svg: None,
+            ancestry: None,
+            output_errors: Vec::new(),
+            recorder: None,
+            partial_credit: None,
+            invariants: None,
+            waypoints: Vec::new(),
+            waypoint_order: WaypointOrder::default(),
+            avoided_cells: Vec::new(),
+            first_move: None,
+            symmetric: false,
+            enumerating: false,
+            pending_mirror: None,
+            enumerated: 0,
+            warm_start: None,
         }
     }
 
-    /// Solves the puzzle, which consumes the solver.
-    pub fn solve(mut self) -> Solution {
-        match self.find_solution_journey() {
-            Some(solution_journey) => {
-                let sum = self.compute_sum_of_unvisited_cells(&solution_journey);
-                Solution::Found(sum, solution_journey.explain())
-            }
-            None => Solution::NotFound,
+    /// Changes the order in which candidate journeys are explored, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_strategy(mut self, strategy: Strategy) -> Self {
+        let first_step = &self.steps[0];
+        self.frontier = Frontier::new(strategy);
+        self.frontier.push(
+            0,
+            first_step.turn,
+            self.board.distance_to_end_cell(&first_step.cell),
+        );
+        self.strategy = strategy;
+
+        self
+    }
+
+    /// Changes the cell the dice starts from, and returns the solver. The initial score is the value
+    /// of that cell, just like it is 0 on the usual start cell.
+    /// Returns `None` if the position is out of bounds. This must be called before solving.
+    pub fn set_start(mut self, position: Position) -> Option<Self> {
+        self.steps[0].cell = self.board.get_cell_at(position)?;
+
+        Some(self)
+    }
+
+    /// Requires solutions to visit the given cells, in the given order, and returns the solver.
+    /// Returns `None` if a position is out of bounds, or if there are more than `MAX_WAYPOINTS`
+    /// of them. This must be called before solving.
+    pub fn set_waypoints(mut self, waypoints: Vec<Position>, order: WaypointOrder) -> Option<Self> {
+        if waypoints.len() > MAX_WAYPOINTS
+            || waypoints
+                .iter()
+                .any(|&position| self.board.get_cell_at(position).is_none())
+        {
+            return None;
         }
+        self.waypoints = waypoints;
+        self.waypoint_order = order;
+
+        Some(self)
     }
 
-    fn compute_sum_of_unvisited_cells(&self, solution_journey: &Journey) -> i16 {
-        let unique_visited_positions: HashSet<_> = solution_journey
-            .visited_cells
-            .iter()
-            .map(|cell| cell.get_position())
-            .collect();
+    /// Warm-starts `solve` from the moves of a previous solution (e.g. `URRD`), typically of a
+    /// slightly different board, and returns the solver. The moves are replayed first: if they
+    /// still form a solution, it is returned as is, which might not be the solution the search
+    /// would have found first. Otherwise, the search carries on from the journeys branching off
+    /// the valid prefix of the moves, exploring first those which branch off it the latest.
+    /// Returns `None` if a letter is not a move. This must be called before solving.
+    pub fn set_warm_start(mut self, moves: &str) -> Option<Self> {
+        let moves = moves
+            .chars()
+            .map(Direction::from_letter)
+            .collect::<Option<_>>()?;
+        self.warm_start = Some(moves);
 
-        self.board
-            .compute_sum_of_unvisited_cells(&unique_visited_positions)
+        Some(self)
     }
 
-    // This is where we actually run the BFS traversal. For each candidate journey popped,
-    // we will check whether we can roll the dice up, right, down, and left. If a movement is
-    // valid, we push it to the back of the list of candidate journeys, unless the movement
-    // leads to the end cell, in which case we return the solution journey.
-    fn find_solution_journey(&mut self) -> Option<Journey> {
-        while let Some(journey) = self.journeys.pop_front() {
-            let last_visited_cell = journey.get_last_visited_cell();
-            let new_turn = journey.turn + 1;
-
-            for direction in Direction::iter() {
-                // Confirm that a movement in this direction yiels a cell (i.e. not outbounds).
-                if let Some(cell) = self.board.move_in(last_visited_cell, direction) {
-                    // If we are inbounds after this movement, confirm that moving there is valid,
-                    // per the puzzle rules.
-                    let rolled_dice = journey.dice.roll_in(direction);
-                    match Solver::try_dice_movement(
-                        rolled_dice,
-                        last_visited_cell.get_value(),
-                        new_turn,
-                        cell,
-                        &journey.visited_cells,
-                    ) {
-                        MovementOutcome::SolutionJourney(journey) => return Some(journey),
-                        MovementOutcome::ValidJourney(journey) => self.journeys.push_back(journey),
-                        MovementOutcome::Invalid => {}
-                    }
-                }
-            }
+    /// Forbids the dice to land on the given cells, and returns the solver.
+    /// Returns `None` if a position is out of bounds. This must be called before solving.
+    pub fn set_avoided_cells(mut self, avoided_cells: Vec<Position>) -> Option<Self> {
+        if avoided_cells
+            .iter()
+            .any(|&position| self.board.get_cell_at(position).is_none())
+        {
+            return None;
         }
+        self.avoided_cells = avoided_cells;
 
-        // Oops, no solution found.
-        None
+        Some(self)
     }
 
-    fn try_dice_movement(
-        dice: Dice,
-        score: i16,
-        new_turn: i16,
-        cell: Cell,
-        visited_cells: &Vec<Cell>,
-    ) -> MovementOutcome {
-        // There are two main scenarios when rolling a dice onto a cell:
-        //  1. The top value on the dice is known.
-        //     In this case, we validate that the new score matches the value of the cell.
-        //     If it does not, then we return an INVALID outcome.
-        //  2. The top value on the dice is yet unknown.
-        //     In this case, we infer an integral top value on the dice resulting in a score matching the
-        //     value of the new cell.
-        //     If no such integral value exists, then we return an INVALID outcome.
-        // If the movement is valid, we finish by checking whether the journey has reached the end cell.
-        // If it does, we annotate it as a solution, otherwise as a valid journey worth further traversing.
-        let valid_journey = match dice.get_top() {
-            Some(dice_top) => {
-                let new_score = score + new_turn * dice_top;
-                if new_score != cell.get_value() {
-                    return MovementOutcome::Invalid;
-                }
+    /// Restricts the search to the journeys whose first movement is in the given direction, and
+    /// returns the solver. Journeys are not paired with their mirror image anymore, so that the
+    /// solutions of the four directions add up to the solutions of the board.
+    /// This must be called before solving.
+    pub fn set_first_move(mut self, direction: Direction) -> Self {
+        self.first_move = Some(direction);
 
-                let mut new_visited_cells = visited_cells.clone();
-                new_visited_cells.push(cell);
-                Journey {
-                    dice,
-                    turn: new_turn,
-                    visited_cells: new_visited_cells,
-                }
-            }
-            None => {
-                let new_score = cell.get_value();
-                let score_diff = new_score - score;
-                if score_diff % new_turn != 0 {
-                    return MovementOutcome::Invalid;
-                }
+        self
+    }
 
-                let mut new_visited_cells = visited_cells.clone();
-                new_visited_cells.push(cell);
-                let new_dice_top = score_diff / new_turn;
-                Journey {
-                    dice: dice.set_top(new_dice_top),
-                    turn: new_turn,
-                    visited_cells: new_visited_cells,
-                }
-            }
-        };
+    /// Changes the dice the journey starts with, which values are all unknown by default, and
+    /// returns the solver. This must be called before solving.
+    pub fn set_dice(mut self, dice: Dice) -> Self {
+        self.steps[0].dice = dice;
 
-        if valid_journey.get_last_visited_cell().is_end_cell() {
-            MovementOutcome::SolutionJourney(valid_journey)
-        } else {
-            MovementOutcome::ValidJourney(valid_journey)
-        }
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Stops extending journeys after the given number of turns, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_max_depth(mut self, max_depth: i16) -> Self {
+        self.max_depth = Some(Turn::from(max_depth));
 
-    impl Solution {
-        /// Return true if a solution was found.
-        fn found_solution(&self) -> bool {
-            matches!(self, Solution::Found(..))
-        }
+        self
+    }
 
-        /// Returns the sum+explanation tuple contained in the `Found` value, consuming itself.
-        ///
-        /// # Panics
-        /// Panics if no solution was found.
-        fn unwrap(self) -> (i16, String) {
-            match self {
-                Solution::Found(sum, explanation) => (sum, explanation),
-                Solution::NotFound => panic!("called `Solution::unwrap()` on a `NotFound` value"),
-            }
-        }
+    /// Stops journeys from revisiting any cell more than the given number of times, and returns
+    /// the solver. With 0, journeys never revisit a cell. This must be called before solving.
+    pub fn set_max_revisits(mut self, max_revisits: u16) -> Self {
+        self.max_revisits = Some(max_revisits);
+
+        self
     }
 
-    fn create_default_journey() -> Journey {
-        Journey {
-            dice: Dice::default(),
-            turn: 0,
-            visited_cells: Vec::new(),
-        }
+    /// Stops journeys from rolling in the same direction more than the given number of times in a
+    /// row, and returns the solver. This must be called before solving.
+    pub fn set_max_straight(mut self, max_straight: u16) -> Self {
+        self.max_straight = Some(max_straight);
+
+        self
     }
 
-    #[test]
-    fn compute_sum_of_unvisited_cells_works() {
-        let solver = Solver::new();
-        let mut journey = create_default_journey();
+    /// Changes how the top of the dice is weighted on each turn, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_multiplier(mut self, multiplier: Multiplier) -> Self {
+        self.rules.multiplier = multiplier;
 
-        assert_eq!(
-            solver.compute_sum_of_unvisited_cells(&journey),
-            solver.board.compute_sum_of_unvisited_cells(&HashSet::new())
-        );
+        self
+    }
 
-        let visited_position = (3, 2);
-        let visited_cell = solver.board.get_cell_at(visited_position.clone()).unwrap();
-        journey.visited_cells = vec![visited_cell];
+    /// Changes how scores are compared to cell values, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.rules.arithmetic = arithmetic;
 
-        assert_eq!(
-            solver.compute_sum_of_unvisited_cells(&journey),
-            solver
-                .board
-                .compute_sum_of_unvisited_cells(&HashSet::from([&visited_position]))
-        );
+        self
     }
 
-    #[test]
-    fn solver_finds_right_solution() {
-        let solution = Solver::new().solve();
+    /// Changes how the score evolves, and when the dice lands on a cell, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.rules.recurrence = recurrence;
 
-        assert!(solution.found_solution());
-        let (sum_unvisited_cells, _) = solution.unwrap();
-        assert_eq!(sum_unvisited_cells, 1935);
+        self
+    }
+
+    /// Changes which value of the dice is scored on each turn, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_scoring(mut self, scoring: Scoring) -> Self {
+        self.rules.scoring = scoring;
+
+        self
+    }
+
+    /// Restricts the values inferred for the faces of the dice to the given range, if any, and
+    /// returns the solver. This must be called before solving.
+    pub fn set_face_range(mut self, face_range: Option<FaceRange>) -> Self {
+        self.rules.face_range = face_range;
+
+        self
+    }
+
+    /// Requires opposite faces of the dice to sum to the given value, if any, and returns the
+    /// solver. This must be called before solving.
+    pub fn set_opposite_sum(mut self, opposite_sum: Option<i16>) -> Self {
+        self.rules.opposite_sum = opposite_sum;
+
+        self
+    }
+
+    /// Restricts the values inferred for the faces of the dice to the given ones, if any, and
+    /// forbids them from repeating the value of another face if distinct, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_allowed_faces(
+        mut self,
+        allowed_faces: Option<Arc<[i16]>>,
+        distinct_faces: bool,
+    ) -> Self {
+        self.rules.allowed_faces = allowed_faces;
+        self.rules.distinct_faces = distinct_faces;
+
+        self
+    }
+
+    /// Changes what the score must be once the dice lands on the end cell, and returns the solver.
+    /// This must be called before solving.
+    pub fn set_final_score(mut self, final_score: FinalScore) -> Self {
+        self.rules.final_score = final_score;
+
+        self
+    }
+
+    /// Changes how solutions are explained, and returns the solver.
+    pub fn set_explanation_style(mut self, explanation_style: ExplanationStyle) -> Self {
+        self.explanation_style = explanation_style;
+
+        self
+    }
+
+    /// Draws the unfolded net of the initial dice after listing its values in explanations, and
+    /// returns the solver. Accessible explanations never include it.
+    pub fn set_net_format(mut self, net_format: NetFormat) -> Self {
+        self.net_format = Some(net_format);
+
+        self
+    }
+
+    /// Ends explanations with a table comparing the score of each turn with the value of the cell
+    /// reached, and returns the solver. Accessible explanations never include it.
+    pub fn set_verification(mut self, verification: bool) -> Self {
+        self.verification = verification;
+
+        self
+    }
+
+    /// Changes how positions are written in explanations, and returns the solver.
+    pub fn set_notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+
+        self
+    }
+
+    /// Reports the progress of the search as NDJSON events to the given log, and returns the solver.
+    pub fn set_events(mut self, events: EventLog) -> Self {
+        self.events = Some(events);
+
+        self
+    }
+
+    /// Reports the progress of the search to the given reporter, and returns the solver.
+    pub fn set_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = Some(progress);
+
+        self
+    }
+
+    /// Solves the puzzle, which consumes the solver.
+    pub fn solve(self) -> Solution {
+        self.solve_with_stats().0
+    }
+
+    /// Solves the puzzle like `solve`, also returning the counters of the work done by the search.
+    pub fn solve_with_stats(mut self) -> (Solution, SearchStats) {
+        let solution = match self.start_search() {
+            Ok(()) => {
+                self.detect_symmetry();
+                match self
+                    .find_warm_solution_step()
+                    .or_else(|| self.find_solution_step())
+                {
+                    Some(solution_step) => {
+                        self.write_outputs(solution_step);
+                        self.create_solution(solution_step)
+                    }
+                    None => self.not_found(false),
+                }
+            }
+            Err(reason) => self.not_found_because(NotFoundReason::Contradictory(reason)),
+        };
+        self.emit_finish(matches!(solution, Solution::Found(..)));
+
+        (solution, self.stats)
+    }
+
+    /// Writes the turns of the solution returned by `solve` or `solve_anytime` as CSV to the given
+    /// writer, and returns the solver.
+    pub fn set_turns_csv(mut self, writer: Box<dyn Write + Send>) -> Self {
+        self.turns_csv = Some(writer);
+
+        self
+    }
+
+    /// Draws the journey of the solution returned by `solve` or `solve_anytime` as an SVG document to
+    /// the given writer, where the dice rolls from cell to cell if animated, and returns the solver.
+    pub fn set_journey_svg(mut self, writer: Box<dyn Write + Send>, animated: bool) -> Self {
+        self.journey_svg = Some((writer, animated));
+
+        self
+    }
+
+    /// Exports the decision points of the solution returned by `solve` or `solve_anytime`, along
+    /// with the sibling branches left at each of them and why, in the given format to the given
+    /// writer, and returns the solver.
+    pub fn set_ancestry(mut self, writer: Box<dyn Write + Send>, format: AncestryFormat) -> Self {
+        self.ancestry = Some((writer, format));
+
+        self
+    }
+
+    /// Reports the journey explored by `solve` or `solve_anytime` which comes the closest to a
+    /// solution on the given metric, if no solution is found, and returns the solver. Journeys
+    /// which provably cannot reach the end cell are then explored too, so the search can take
+    /// much longer without a maximum depth.
+    pub fn set_partial_credit(mut self, metric: PartialCredit) -> Self {
+        self.partial_credit = Some(metric);
+
+        self
+    }
+
+    /// Logs every step generated, expanded or pruned by the search to the given recorder, and
+    /// returns the solver.
+    pub fn set_recorder(mut self, recorder: SearchRecorder) -> Self {
+        self.recorder = Some(recorder);
+
+        self
+    }
+
+    /// Replays the given moves (e.g. `URRD`) under the rules of the solver instead of searching,
+    /// which consumes the solver. Returns the report of the journey if the moves form a solution.
+    pub fn replay(mut self, moves: &str) -> Option<SolutionReport> {
+        let moves: Vec<Direction> = moves
+            .chars()
+            .map(Direction::from_letter)
+            .collect::<Option<_>>()?;
+        self.start_search().ok()?;
+        let path = self.replay_moves(&moves);
+        let step = path[path.len() - 1];
+        if path.len() - 1 < moves.len() || step == 0 || !self.steps[step].cell.is_end_cell() {
+            return None;
+        }
+
+        match self.create_solution(step) {
+            Solution::Found(report) => Some(report),
+            Solution::NotFound(..) => None,
+        }
+    }
+
+    /// Enumerates the solutions of the puzzle, which consumes the solver. The search stops as soon
+    /// as `bound` solutions are found, so that counting solutions on a board with many of them
+    /// remains tractable.
+    ///
+    /// ```
+    /// use die_agony::{Board, Solution, Solver};
+    ///
+    /// let board = Board::from_rows([[0; 3]; 2]);
+    /// let solver = Solver::builder().board(board).max_depth(3).build().unwrap();
+    /// let solutions = solver.find_solutions(10);
+    /// let mut moves: Vec<String> = solutions
+    ///     .into_iter()
+    ///     .filter_map(|solution| match solution {
+    ///         Solution::Found(report) => Some(report.moves_as_letters()),
+    ///         Solution::NotFound(..) => None,
+    ///     })
+    ///     .collect();
+    /// moves.sort();
+    /// assert_eq!(moves, ["RRU", "RUR", "URR"]);
+    /// ```
+    pub fn find_solutions(mut self, bound: usize) -> Vec<Solution> {
+        let solutions: Vec<Solution> = std::iter::from_fn(|| self.next_solution())
+            .take(bound)
+            .collect();
+        self.emit_finish(!solutions.is_empty());
+
+        solutions
+    }
+
+    /// Runs the search to exhaustion without the invariants, and returns for each cell of the board,
+    /// indexed like `Board::index_of`, the last turn on which a journey which died out before
+    /// reaching the end cell visited it, if any did. This exposes the dead-end structure of the
+    /// board.
+    pub fn map_dead_ends(mut self) -> Vec<Option<i16>> {
+        let mut depths = vec![None; self.board.positions().count()];
+        // The invariants would prune most dead ends before they are explored, hiding them.
+        let _ = self.start_search();
+        self.invariants = None;
+        self.detect_symmetry();
+        let mut solution_steps = Vec::new();
+        while let Some(solution_step) = self.find_solution_step() {
+            solution_steps.push(solution_step);
+        }
+
+        // Steps are stored after the step they come from, so walking the arena backwards visits
+        // every step before its previous one.
+        let mut alive = vec![false; self.steps.len()];
+        for solution_step in solution_steps {
+            alive[solution_step] = true;
+        }
+        for step in (1..self.steps.len()).rev() {
+            if let Some(previous) = self.steps[step].previous() {
+                alive[previous] |= alive[step];
+            }
+        }
+
+        for (step, Step { turn, cell, .. }) in self.steps.iter().enumerate() {
+            if alive[step] {
+                continue;
+            }
+            let mut cells = vec![*cell];
+            // Mirror images of the journeys explored die out on the mirrored cells.
+            if self.symmetric {
+                cells.push(self.board.mirror(cell));
+            }
+            for cell in cells {
+                let depth = &mut depths[self.board.index_of(&cell)];
+                *depth = (*depth).max(Some(i16::from(*turn)));
+            }
+        }
+
+        depths
+    }
+
+    /// Explains why the unique solution of the board is forced: at each of its turns, lists the other
+    /// movements the dice could try, and why each of them was eliminated, be it by the edges of the
+    /// board, the rules, the invariants, or because the exhaustive search found no journey reaching
+    /// the end cell from there. This is a sketch of a proof of uniqueness. Fails if the board does
+    /// not have exactly one solution.
+    pub fn explain_forced_moves(mut self) -> Result<String, String> {
+        self.start_search()
+            .map_err(|reason| format!("the board has no solution, as {}", reason))?;
+        self.detect_symmetry();
+        // The search is run to exhaustion, so that the steps left in the arena cover every journey
+        // explored from the alternatives.
+        let mut solution_steps = Vec::new();
+        while let Some(solution_step) = self.find_solution_step() {
+            solution_steps.push(solution_step);
+            if self.symmetric || solution_steps.len() > 1 {
+                return Err("the board has several solutions".to_string());
+            }
+        }
+        let [solution_step] = solution_steps[..] else {
+            return Err("the board has no solution".to_string());
+        };
+
+        let moves = self.trace_moves(solution_step);
+        let mut lines = vec![format!(
+            "The solution is unique, as each of its {} turns is forced:",
+            moves.len()
+        )];
+        for (from, taken_direction, to) in moves {
+            let Step { cell, .. } = self.steps[from];
+            let Step {
+                turn,
+                cell: taken_cell,
+                ..
+            } = self.steps[to];
+            lines.push(format!(
+                "Turn {}: the dice rolls {} from {} to {}.",
+                turn,
+                describe_direction(taken_direction),
+                describe_position(*cell.get_position(), self.notation),
+                describe_position(*taken_cell.get_position(), self.notation)
+            ));
+
+            for direction in Direction::iter().filter(|&direction| direction != taken_direction) {
+                let reason = match self.follow_branch(from, direction) {
+                    Branch::OffBoard => "would leave the board".to_string(),
+                    // The search was exhausted, so every journey explored from there died out.
+                    Branch::Explored {
+                        destination,
+                        steps,
+                        last_turn,
+                    } => format!(
+                        "to {} is valid, but none of the {} steps explored from there reaches the \
+                         end cell, the longest journey dying out after turn {}",
+                        describe_position(destination, self.notation),
+                        steps,
+                        last_turn
+                    ),
+                    Branch::Pruned {
+                        destination,
+                        reason,
+                    } => format!(
+                        "to {} {}",
+                        describe_position(destination, self.notation),
+                        reason
+                    ),
+                };
+                lines.push(format!(
+                    "  Rolling {} {}.",
+                    describe_direction(direction),
+                    reason
+                ));
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Follows the branch rolling in the given direction from the given step, which is on a journey
+    /// to a solution, and tells what became of it.
+    fn follow_branch(&self, step: usize, direction: Direction) -> Branch {
+        let Step {
+            turn, cell, dice, ..
+        } = self.steps[step];
+        let Some(new_cell) = self.board.move_in(&cell, direction) else {
+            return Branch::OffBoard;
+        };
+        let destination = *new_cell.get_position();
+        let pruned = |reason: &str| Branch::Pruned {
+            destination,
+            reason: reason.to_string(),
+        };
+
+        if step == 0 && self.symmetric && (direction.mirror() as u8) < direction as u8 {
+            return pruned(&format!(
+                "is the mirror image of rolling {}, which is explored instead",
+                describe_direction(direction.mirror())
+            ));
+        }
+        if step == 0
+            && self
+                .first_move
+                .is_some_and(|first_move| first_move != direction)
+        {
+            return pruned("is not allowed, as the first movement is restricted");
+        }
+
+        // Steps explored from there are stored after the step they come from.
+        let mut explored = vec![false; self.steps.len()];
+        let mut steps = 0;
+        let mut last_turn = turn;
+        for (index, other) in self.steps.iter().enumerate().skip(step + 1) {
+            let Some((previous, rolled)) = other.reached_from else {
+                continue;
+            };
+            if (previous == step && rolled == direction) || explored[previous] {
+                explored[index] = true;
+                steps += 1;
+                last_turn = last_turn.max(other.turn);
+            }
+        }
+        if steps > 0 {
+            return Branch::Explored {
+                destination,
+                steps,
+                last_turn: last_turn.into(),
+            };
+        }
+
+        if self.avoided_cells.contains(&destination) {
+            return pruned("is not allowed, as the cell is avoided");
+        }
+        if self
+            .max_revisits
+            .is_some_and(|max_revisits| self.count_visits(step, &new_cell) > max_revisits as usize)
+        {
+            return pruned("would revisit it too many times");
+        }
+        if self.max_straight.is_some_and(|max_straight| {
+            self.count_straight(step, direction) >= max_straight as usize
+        }) {
+            return pruned("would roll in the same direction too many times in a row");
+        }
+        if let Some(reason) = self.explain_landing(turn, cell, dice, direction, new_cell) {
+            return pruned(&reason);
+        }
+        // Landing is only possible if the turn after this one can be counted.
+        let Some(new_turn) = turn.next() else {
+            return pruned("would last more turns than can be counted");
+        };
+        if self
+            .invariants
+            .as_ref()
+            .is_some_and(|invariants| !invariants.can_finish(&self.board, new_turn, &new_cell))
+        {
+            return pruned(
+                "is ruled out, as no roll sequence from there lands on the end cell, whatever the \
+                 values on the dice",
+            );
+        }
+
+        if new_cell.is_end_cell()
+            && !self
+                .rules
+                .accepts_final_score(new_turn, new_cell.get_value())
+        {
+            return pruned(&format!(
+                "would end the journey with a final score of {}, which is not the one required",
+                self.rules.landed_score(new_turn, new_cell.get_value())
+            ));
+        }
+
+        pruned("would end the journey before visiting every waypoint")
+    }
+
+    /// Explains why the dice of the given step, on the given cell after the given turn, does not
+    /// land on the new cell when rolling in the given direction, or returns `None` if it does.
+    fn explain_landing(
+        &self,
+        turn: Turn,
+        cell: Cell,
+        dice: Dice,
+        direction: Direction,
+        new_cell: Cell,
+    ) -> Option<String> {
+        let Some(multiplier) = turn
+            .next()
+            .and_then(|new_turn| self.rules.multiplier.at(new_turn))
+        else {
+            return Some("is not possible, as the multipliers ran out".to_string());
+        };
+        let score = if turn.is_start() {
+            self.rules.initial_score(cell.get_value())
+        } else {
+            cell.get_value()
+        };
+        let cell_value = new_cell.get_value();
+        let affine_integers = matches!(
+            (self.rules.recurrence, self.rules.arithmetic),
+            (Recurrence::Affine, Arithmetic::Integer)
+        );
+        let scored = self.rules.scoring.name();
+        let rolled_dice = dice.roll_in(direction);
+        match self.rules.scoring.scored_value(&rolled_dice) {
+            Some(value) if self.rules.lands(score, multiplier, value, cell_value) => None,
+            Some(value) if affine_integers => Some(format!(
+                "brings the known {} of {}, scoring {} + {} × {} = {}, not {}",
+                scored,
+                value,
+                score,
+                multiplier,
+                value,
+                score as i32 + multiplier as i32 * value as i32,
+                cell_value
+            )),
+            Some(value) => Some(format!(
+                "brings the known {} of {}, which does not land on {}",
+                scored, value, cell_value
+            )),
+            None if self
+                .rules
+                .lands_with_any_value(score, multiplier, cell_value) =>
+            {
+                None
+            }
+            None => {
+                let mut values = self
+                    .rules
+                    .infer_values(score, multiplier, cell_value)
+                    .peekable();
+                if values.peek().is_none() {
+                    Some(if affine_integers {
+                        format!(
+                            "would need a {} of ({} - {}) / {}, which is not an integer",
+                            scored, cell_value, score, multiplier
+                        )
+                    } else {
+                        format!(
+                            "would need a {} landing on {}, and there is none",
+                            scored, cell_value
+                        )
+                    })
+                } else if values
+                    .all(|value| self.rules.set_inferred_score(rolled_dice, value).is_none())
+                {
+                    Some(format!(
+                        "would need a {} breaking the constraints on the faces",
+                        scored
+                    ))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Returns the next solution of the puzzle, or `None` once every solution was enumerated. Each
+    /// call resumes the search where the previous one stopped, and the state of the enumeration can
+    /// be saved in between with `enumeration_state`.
+    pub fn next_solution(&mut self) -> Option<Solution> {
+        if !self.enumerating {
+            self.enumerating = true;
+            if self.start_search().is_err() {
+                // There is nothing to enumerate, so subsequent calls must not search either.
+                self.frontier = Frontier::new(self.strategy);
+                return None;
+            }
+            self.detect_symmetry();
+        }
+
+        let solution_step = match self.pending_mirror.take() {
+            Some(mirrored_step) => mirrored_step,
+            None => {
+                let solution_step = self.find_solution_step()?;
+                // The mirror image of the solution was not explored, but is a solution too.
+                if self.symmetric {
+                    self.pending_mirror = Some(self.mirror_journey(solution_step));
+                }
+                solution_step
+            }
+        };
+        self.enumerated += 1;
+
+        Some(self.create_solution(solution_step))
+    }
+
+    /// Returns how many solutions `next_solution` returned so far, including before the
+    /// enumeration was resumed.
+    pub fn solutions_enumerated(&self) -> usize {
+        self.enumerated
+    }
+
+    /// Saves where the enumeration of `next_solution` stands as JSON: the steps created so far, the
+    /// frontier and the counters. A solver configured the same way for the same board can resume
+    /// the enumeration from it with `resume_enumeration`, e.g. to page through solutions across
+    /// requests.
+    pub fn enumeration_state(&self) -> Value {
+        let SearchStats {
+            expanded,
+            generated,
+            rejected,
+            pruned,
+            ..
+        } = self.stats;
+        versioned(json!({
+            "board_hash": compute_board_hash(&self.board),
+            "configuration": self.configuration(),
+            "enumerating": self.enumerating,
+            "symmetric": self.symmetric,
+            "pending_mirror": self.pending_mirror,
+            "enumerated": self.enumerated,
+            "steps": self.steps.iter().map(|step| step.to_json()).collect::<Vec<_>>(),
+            "frontier": self.frontier.steps_in_push_order(),
+            "stats": [expanded, generated, rejected, pruned],
+        }))
+    }
+
+    /// Restores the state of an enumeration saved with `enumeration_state`, and returns the solver,
+    /// on which `next_solution` resumes the enumeration. Fails if the state was saved by a solver
+    /// configured differently, or for another board. This must be called before solving.
+    pub fn resume_enumeration(mut self, state: &Value) -> Result<Self, String> {
+        check_format_version(state)?;
+        if state["board_hash"] != json!(compute_board_hash(&self.board))
+            || state["configuration"] != json!(self.configuration())
+        {
+            return Err("the state was saved for another board or configuration".to_string());
+        }
+        let invalid = |what: &str| format!("the state holds invalid {}", what);
+
+        let steps: Vec<Step> = state["steps"]
+            .as_array()
+            .and_then(|steps| {
+                steps
+                    .iter()
+                    .map(|step| Step::from_json(step, &self.board))
+                    .collect()
+            })
+            .ok_or_else(|| invalid("steps"))?;
+        // Steps must point back to earlier steps, down to the start step of this solver.
+        let linked = steps
+            .iter()
+            .enumerate()
+            .all(|(index, step)| step.previous().is_none_or(|previous| previous < index));
+        match steps.first() {
+            Some(first)
+                if linked
+                    && first.dice == self.steps[0].dice
+                    && first.cell == self.steps[0].cell => {}
+            _ => return Err(invalid("steps")),
+        }
+        let in_arena = |step: &Value| {
+            step.as_u64()
+                .and_then(|step| usize::try_from(step).ok())
+                .filter(|&step| step < steps.len())
+        };
+
+        let frontier: Vec<usize> = state["frontier"]
+            .as_array()
+            .and_then(|frontier| frontier.iter().map(in_arena).collect())
+            .ok_or_else(|| invalid("frontier"))?;
+        let pending_mirror = match &state["pending_mirror"] {
+            Value::Null => None,
+            step => Some(in_arena(step).ok_or_else(|| invalid("pending mirror"))?),
+        };
+        let stats: Vec<u64> = state["stats"]
+            .as_array()
+            .and_then(|stats| stats.iter().map(Value::as_u64).collect())
+            .ok_or_else(|| invalid("counters"))?;
+        let [expanded, generated, rejected, pruned] = stats[..] else {
+            return Err(invalid("counters"));
+        };
+        let (Some(enumerating), Some(symmetric), Some(enumerated)) = (
+            state["enumerating"].as_bool(),
+            state["symmetric"].as_bool(),
+            state["enumerated"].as_u64(),
+        ) else {
+            return Err(invalid("progress"));
+        };
+
+        self.steps = steps;
+        self.frontier = Frontier::new(self.strategy);
+        for step in frontier {
+            let Step { turn, cell, .. } = self.steps[step];
+            self.frontier
+                .push(step, turn, self.board.distance_to_end_cell(&cell));
+        }
+        self.pending_mirror = pending_mirror;
+        self.stats = SearchStats {
+            expanded,
+            generated,
+            rejected,
+            pruned,
+            peak_frontier: self.frontier.len() as u64,
+            stored: self.steps.len() as u64,
+        };
+        self.enumerating = enumerating;
+        self.symmetric = symmetric;
+        self.enumerated = enumerated as usize;
+        if enumerating {
+            self.invariants = Some(self.derive_invariants());
+        }
+
+        Ok(self)
+    }
+
+    /// Describes everything the search depends on besides the board, to check that an enumeration
+    /// is resumed with the configuration it was saved with.
+    fn configuration(&self) -> String {
+        format!(
+            "{:?}",
+            (
+                self.strategy,
+                &self.rules,
+                self.max_depth,
+                self.max_revisits,
+                self.max_straight,
+                &self.waypoints,
+                self.waypoint_order,
+                &self.avoided_cells,
+            )
+        )
+    }
+
+    /// Solves the puzzle in an anytime fashion, which consumes the solver. The first solution is
+    /// reported as soon as it is found, then the search keeps going, reporting every solution
+    /// improving on the given objective, until either the state space is exhausted or the time limit
+    /// is hit. Returns the best solution found.
+    pub fn solve_anytime(
+        mut self,
+        objective: &Objective,
+        time_limit: Duration,
+        mut on_improvement: impl FnMut(&Solution),
+    ) -> Solution {
+        if let Err(reason) = self.start_search() {
+            self.emit_finish(false);
+            return self.not_found_because(NotFoundReason::Contradictory(reason));
+        }
+        let deadline = Instant::now() + time_limit;
+        let mut best: Option<(SolutionKey, usize)> = None;
+        let mut timed_out = false;
+
+        while let Some(step) = self.frontier.pop() {
+            if Instant::now() >= deadline {
+                timed_out = true;
+                break;
+            }
+
+            // When the objective favours short paths, there is no point in extending journeys that
+            // cannot reach the end cell quickly enough to compete with the best solution.
+            if let Some((best_key, _)) = &best {
+                let Step { turn, cell, .. } = self.steps[step];
+                if objective.can_prune(
+                    i16::from(turn) + self.board.distance_to_end_cell(&cell) as i16,
+                    best_key,
+                ) {
+                    self.stats.pruned += 1;
+                    if let Some(recorder) = &mut self.recorder {
+                        recorder.pruned(step);
+                    }
+                    continue;
+                }
+            }
+
+            let Some(solution_step) = self.explore(step) else {
+                continue;
+            };
+
+            let key = self.create_solution_key(solution_step);
+            if best.as_ref().is_none_or(|(best_key, _)| {
+                objective.compare(&key, best_key) == std::cmp::Ordering::Less
+            }) {
+                best = Some((key, solution_step));
+                on_improvement(&self.create_solution(solution_step));
+            }
+        }
+
+        let solution = match best {
+            Some((_, solution_step)) => {
+                self.write_outputs(solution_step);
+                self.create_solution(solution_step)
+            }
+            None => self.not_found(timed_out),
+        };
+        self.emit_finish(matches!(solution, Solution::Found(..)));
+
+        solution
+    }
+
+    /// Explains why the search ended without a solution, given whether it hit the time limit.
+    fn not_found(&self, timed_out: bool) -> Solution {
+        let reason = if timed_out {
+            NotFoundReason::LimitHit(SearchLimit::Time)
+        } else if self.max_depth.is_some() {
+            NotFoundReason::LimitHit(SearchLimit::Depth)
+        } else {
+            NotFoundReason::Exhausted
+        };
+
+        self.not_found_because(reason)
+    }
+
+    fn not_found_because(&self, reason: NotFoundReason) -> Solution {
+        let partial_journey = self
+            .partial_credit
+            .map(|metric| self.find_partial_journey(metric));
+
+        Solution::NotFound(reason, self.stats, partial_journey)
+    }
+
+    /// Returns the journey explored by the search which comes the closest to a solution on the
+    /// given metric. Journeys are compared step by step without being rebuilt, only the closest
+    /// one is.
+    fn find_partial_journey(&self, metric: PartialCredit) -> PartialJourney {
+        let cells_visited = self.count_cells_visited();
+        let describe = |step: usize| {
+            let Step { turn, cell, .. } = self.steps[step];
+            PartialJourney {
+                moves: String::new(),
+                position: *cell.get_position(),
+                distance_to_end: self.board.distance_to_end_cell(&cell),
+                cells_visited: cells_visited[step],
+                score: if turn.is_start() {
+                    self.rules.initial_score(cell.get_value()) as i32
+                } else {
+                    self.rules.landed_score(turn, cell.get_value())
+                },
+            }
+        };
+
+        let (mut best_step, mut best) = (0, describe(0));
+        for step in 1..self.steps.len() {
+            let journey = describe(step);
+            if metric.prefers(&journey, &best) {
+                (best_step, best) = (step, journey);
+            }
+        }
+        best.moves = self
+            .rebuild_journey(best_step)
+            .movements
+            .iter()
+            .map(|(direction, _)| direction.letter())
+            .collect();
+
+        best
+    }
+
+    /// Counts the distinct cells visited by the journey ending on each step, indexed like the
+    /// arena. The tree of steps is walked depth first, keeping track of the visits of each cell
+    /// along the journey being walked, so that every step is only visited twice.
+    fn count_cells_visited(&self) -> Vec<usize> {
+        // The children of each step are grouped by parent, those of step `i` being listed between
+        // `offsets[i]` and `offsets[i + 1]`.
+        let mut offsets = vec![0; self.steps.len() + 1];
+        for previous in self.steps.iter().filter_map(|step| step.previous()) {
+            offsets[previous + 1] += 1;
+        }
+        for step in 0..self.steps.len() {
+            offsets[step + 1] += offsets[step];
+        }
+        let mut children = vec![0; offsets[self.steps.len()]];
+        let mut next_child = offsets.clone();
+        for (step, other) in self.steps.iter().enumerate() {
+            if let Some(previous) = other.previous() {
+                children[next_child[previous]] = step;
+                next_child[previous] += 1;
+            }
+        }
+
+        let mut visits = vec![0u32; self.board.positions().count()];
+        let mut distinct_cells = 0;
+        let mut cells_visited = vec![0; self.steps.len()];
+        // Each step is pushed once to be entered, and once more to be left after its children.
+        let mut stack: Vec<(usize, bool)> = (0..self.steps.len())
+            .filter(|&step| self.steps[step].reached_from.is_none())
+            .map(|step| (step, true))
+            .collect();
+        while let Some((step, entering)) = stack.pop() {
+            let cell = self.board.index_of(&self.steps[step].cell);
+            if entering {
+                if visits[cell] == 0 {
+                    distinct_cells += 1;
+                }
+                visits[cell] += 1;
+                cells_visited[step] = distinct_cells;
+                stack.push((step, false));
+                stack.extend(
+                    children[offsets[step]..offsets[step + 1]]
+                        .iter()
+                        .map(|&child| (child, true)),
+                );
+            } else {
+                visits[cell] -= 1;
+                if visits[cell] == 0 {
+                    distinct_cells -= 1;
+                }
+            }
+        }
+
+        cells_visited
+    }
+
+    fn create_solution_key(&self, solution_step: usize) -> SolutionKey {
+        let journey = self.rebuild_journey(solution_step);
+        SolutionKey {
+            turns: self.steps[solution_step].turn.into(),
+            unvisited_sum: self.compute_sum_of_unvisited_cells(&journey),
+            moves: journey
+                .movements
+                .iter()
+                .map(|(direction, _)| direction.letter())
+                .collect(),
+        }
+    }
+
+    fn create_solution(&mut self, solution_step: usize) -> Solution {
+        let solution_journey = self.rebuild_journey(solution_step);
+        let sum = self.compute_sum_of_unvisited_cells(&solution_journey);
+        let moves: String = solution_journey
+            .movements
+            .iter()
+            .map(|(direction, _)| direction.letter())
+            .collect();
+        if let Some(events) = &mut self.events {
+            events.emit(
+                "solution",
+                json!({
+                    "turns": i16::from(self.steps[solution_step].turn),
+                    "unvisited_sum": sum,
+                    "moves": moves,
+                    "move_stats": MoveStats::from_moves(&moves).to_json(),
+                }),
+            );
+        }
+
+        Solution::Found(SolutionReport {
+            moves: solution_journey
+                .movements
+                .iter()
+                .map(|&(direction, _)| direction)
+                .collect(),
+            initial_dice: solution_journey.initial_dice(),
+            unvisited_sum: sum,
+            board: Arc::clone(&self.board),
+            start_cell: solution_journey.start_cell,
+            rules: self.rules.clone(),
+            output_errors: mem::take(&mut self.output_errors),
+            settings: ExplanationSettings {
+                style: self.explanation_style,
+                net_format: self.net_format,
+                verification: self.verification,
+                notation: self.notation,
+            },
+        })
+    }
+
+    /// Writes the files asked for about the solution ending with the given step, keeping the
+    /// errors hit for the report of the solution.
+    fn write_outputs(&mut self, solution_step: usize) {
+        let written = [
+            (SolverOutput::TurnsCsv, self.write_turns_csv(solution_step)),
+            (
+                SolverOutput::JourneySvg,
+                self.write_journey_svg(solution_step),
+            ),
+            (SolverOutput::Ancestry, self.write_ancestry(solution_step)),
+        ];
+        self.output_errors.extend(
+            written
+                .into_iter()
+                .filter_map(|(output, result)| Some((output, Arc::new(result.err()?)))),
+        );
+    }
+
+    fn write_turns_csv(&mut self, solution_step: usize) -> io::Result<()> {
+        let Some(mut writer) = self.turns_csv.take() else {
+            return Ok(());
+        };
+        let solution_journey = self.rebuild_journey(solution_step);
+        solution_journey.write_turns_csv(&self.rules, &mut writer)
+    }
+
+    fn write_journey_svg(&mut self, solution_step: usize) -> io::Result<()> {
+        let Some((mut writer, animated)) = self.journey_svg.take() else {
+            return Ok(());
+        };
+        let solution_journey = self.rebuild_journey(solution_step);
+        solution_journey.write_svg(&self.board, animated, &mut writer)
+    }
+
+    fn write_ancestry(&mut self, solution_step: usize) -> io::Result<()> {
+        let Some((mut writer, format)) = self.ancestry.take() else {
+            return Ok(());
+        };
+        let decisions = self.trace_decisions(solution_step);
+        let document = export_ancestry(&decisions, format, self.notation);
+        writeln!(writer, "{}", document)
+    }
+
+    /// Lists the moves of the journey ending with the given step, in order, each as the step it
+    /// rolled from, the direction it rolled in and the step it landed on.
+    fn trace_moves(&self, last_step: usize) -> Vec<(usize, Direction, usize)> {
+        let mut moves = Vec::new();
+        let mut step = last_step;
+        while let Some((previous, direction)) = self.steps[step].reached_from {
+            moves.push((previous, direction, step));
+            step = previous;
+        }
+        moves.reverse();
+
+        moves
+    }
+
+    /// Lists the decision points of the journey ending with the given step, with what became of
+    /// the branches it did not take.
+    fn trace_decisions(&self, last_step: usize) -> Vec<Decision> {
+        self.trace_moves(last_step)
+            .into_iter()
+            .map(|(from, direction, to)| {
+                let Step {
+                    turn, cell, dice, ..
+                } = self.steps[to];
+                let siblings = Direction::iter()
+                    .filter(|&sibling| sibling != direction)
+                    .map(|sibling| (sibling, self.follow_branch(from, sibling)))
+                    .collect();
+
+                Decision {
+                    turn: turn.into(),
+                    from: *self.steps[from].cell.get_position(),
+                    direction,
+                    to: *cell.get_position(),
+                    top: dice.get_face(Face::Top),
+                    siblings,
+                }
+            })
+            .collect()
+    }
+
+    /// Prepares the search, once the solver is fully configured. Fails if the invariants of the
+    /// board and the rules prove the puzzle unsolvable, in which case there is no need to search.
+    fn start_search(&mut self) -> Result<(), Unsolvable> {
+        self.steps[0].waypoints = self.visit_waypoints(0, &self.steps[0].cell);
+        // Partial credit goes to journeys which cannot reach the end cell, so they are explored
+        // rather than pruned by the invariants, even when the puzzle is provably unsolvable.
+        if self.partial_credit.is_some() {
+            self.emit_start();
+            return Ok(());
+        }
+        let invariants = self.derive_invariants();
+        let solvable = invariants.check_solvable(&self.board, &self.steps[0].cell);
+        self.invariants = Some(invariants);
+        self.emit_start();
+
+        solvable
+    }
+
+    fn derive_invariants(&self) -> Invariants {
+        Invariants::derive(
+            &self.board,
+            &self.rules,
+            self.max_depth,
+            &self.avoided_cells,
+        )
+    }
+
+    /// Detects whether the board, the start cell and the initial dice are all symmetric across the
+    /// diagonal going through the start and end cells. If so, every journey has a mirror image
+    /// starting with the mirrored movement, so only one of them needs to be explored.
+    fn detect_symmetry(&mut self) {
+        let Step { dice, cell, .. } = self.steps[0];
+        self.symmetric = self.waypoints.is_empty()
+            && self.avoided_cells.is_empty()
+            && self.first_move.is_none()
+            && self.board.is_symmetric()
+            && self.board.mirror(&cell) == cell
+            && dice.mirror() == dice;
+    }
+
+    /// Returns the waypoints visited after landing on the given cell, given those visited before.
+    fn visit_waypoints(&self, waypoints: u64, cell: &Cell) -> u64 {
+        self.waypoints
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| *position == cell.get_position())
+            .fold(waypoints, |waypoints, (index, _)| {
+                let previous_waypoints = (1 << index) - 1;
+                match self.waypoint_order {
+                    WaypointOrder::Given
+                        if waypoints & previous_waypoints != previous_waypoints =>
+                    {
+                        waypoints
+                    }
+                    _ => waypoints | 1 << index,
+                }
+            })
+    }
+
+    /// Counts how many times the journey ending at the given step visited the given cell. This
+    /// walks the journey back, without allocating.
+    fn count_visits(&self, last_step: usize, cell: &Cell) -> usize {
+        let mut visits = 0;
+        let mut step = Some(last_step);
+        while let Some(index) = step {
+            if self.steps[index].cell == *cell {
+                visits += 1;
+            }
+            step = self.steps[index].previous();
+        }
+
+        visits
+    }
+
+    /// Counts how many times in a row the dice rolled in the given direction, up to the given step.
+    fn count_straight(&self, last_step: usize, direction: Direction) -> usize {
+        let mut straight = 0;
+        let mut step = last_step;
+        while let Some((previous, rolled)) = self.steps[step].reached_from {
+            if rolled != direction {
+                break;
+            }
+            straight += 1;
+            step = previous;
+        }
+
+        straight
+    }
+
+    /// Returns whether all the waypoints have been visited.
+    fn visited_all_waypoints(&self, waypoints: u64) -> bool {
+        waypoints.count_ones() as usize == self.waypoints.len()
+    }
+
+    /// Adds the mirror image of the journey ending at the given step to the arena, and returns
+    /// the index of its last step. The start step is its own mirror image, so it is shared.
+    fn mirror_journey(&mut self, last_step: usize) -> usize {
+        let mut journey_steps = Vec::new();
+        let mut step = last_step;
+        while let Some((previous, direction)) = self.steps[step].reached_from {
+            journey_steps.push((step, direction));
+            step = previous;
+        }
+
+        let mut previous = 0;
+        for (index, direction) in journey_steps.into_iter().rev() {
+            let Step {
+                dice,
+                turn,
+                cell,
+                waypoints,
+                ..
+            } = self.steps[index];
+            self.steps.push(Step {
+                dice: dice.mirror(),
+                turn,
+                cell: self.board.mirror(&cell),
+                reached_from: Some((previous, direction.mirror())),
+                waypoints,
+            });
+            previous = self.steps.len() - 1;
+        }
+
+        previous
+    }
+
+    fn emit_start(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            let Step {
+                dice, turn, cell, ..
+            } = self.steps[0];
+            recorder.generated(
+                0,
+                None,
+                turn.into(),
+                *cell.get_position(),
+                dice.get_face(Face::Top),
+            );
+        }
+        if let Some(events) = &mut self.events {
+            events.emit(
+                "start",
+                json!({
+                    "strategy": format!("{:?}", self.strategy),
+                    "rules": format!("{:?}", self.rules),
+                    "start": self.steps[0].cell.get_position(),
+                }),
+            );
+        }
+    }
+
+    fn emit_finish(&mut self, found: bool) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.flush();
+        }
+        if let Some(events) = &mut self.events {
+            let SearchStats {
+                expanded,
+                generated,
+                rejected,
+                pruned,
+                ..
+            } = self.stats;
+            events.emit(
+                "prune-summary",
+                json!({ "rejected": rejected, "pruned": pruned }),
+            );
+            events.emit(
+                "finish",
+                json!({
+                    "found": found,
+                    "expanded": expanded,
+                    "generated": generated,
+                    "frontier": self.frontier.len(),
+                }),
+            );
+        }
+    }
+
+    fn compute_sum_of_unvisited_cells(&self, solution_journey: &Journey) -> i32 {
+        let unique_visited_positions: HashSet<_> = solution_journey
+            .visited_cells()
+            .map(|cell| cell.get_position())
+            .collect();
+
+        self.board
+            .compute_sum_of_unvisited_cells(&unique_visited_positions)
+    }
+
+    /// Rebuilds the full journey ending at the given step, by walking back the arena.
+    fn rebuild_journey(&self, last_step: usize) -> Journey {
+        let mut movements = Vec::new();
+        let mut step = last_step;
+        while let Step {
+            reached_from: Some((previous, direction)),
+            cell,
+            ..
+        } = self.steps[step]
+        {
+            movements.push((direction, cell));
+            step = previous;
+        }
+        movements.reverse();
+
+        Journey {
+            dice: self.steps[last_step].dice,
+            start_cell: self.steps[step].cell,
+            movements,
+        }
+    }
+
+    // This is where we actually run the traversal. For each candidate journey popped,
+    // we will check whether we can roll the dice up, right, down, and left. If a movement is
+    // valid, we push it to the frontier of candidate journeys, unless the movement
+    // leads to the end cell, in which case we return the solution step. Calling it again resumes
+    // the traversal, looking for the next solution.
+    fn find_solution_step(&mut self) -> Option<usize> {
+        while let Some(step) = self.frontier.pop() {
+            if let Some(solution_step) = self.explore(step) {
+                return Some(solution_step);
+            }
+        }
+
+        // Oops, no solution found.
+        None
+    }
+
+    /// Replays the moves to warm-start from, if any, and returns the index of the solution step if
+    /// they still form a solution, or if replaying them came across one. Otherwise, the frontier
+    /// is seeded with every journey branching off their valid prefix, those branching off the
+    /// latest first, so that the search carries on from there without expanding any step twice.
+    fn find_warm_solution_step(&mut self) -> Option<usize> {
+        let moves = self.warm_start.take()?;
+        let path = self.replay_moves(&moves);
+        // Steps are only created on the end cell for solutions, which the moves might have come
+        // across while branching off.
+        if let Some(solution_step) =
+            (1..self.steps.len()).find(|&step| self.steps[step].cell.is_end_cell())
+        {
+            return Some(solution_step);
+        }
+
+        // The last step reached was only expanded if the next move was rejected.
+        let expanded = match path.len() - 1 < moves.len() {
+            true => &path[..],
+            false => &path[..path.len() - 1],
+        };
+        let mut continuations: Vec<usize> = (0..self.steps.len())
+            .filter(|step| !expanded.contains(step))
+            .collect();
+        continuations.sort_by_key(|&step| std::cmp::Reverse(self.steps[step].turn));
+        self.frontier = Frontier::new(self.strategy);
+        for step in continuations {
+            let Step { turn, cell, .. } = self.steps[step];
+            self.frontier
+                .push(step, turn, self.board.distance_to_end_cell(&cell));
+        }
+
+        None
+    }
+
+    /// Rolls the dice along the given moves from the start cell, and returns the steps reached,
+    /// from the start step. Rolling is validated by expanding each step like the search does,
+    /// which also creates its other continuations, but leaves the frontier empty. The replay stops
+    /// at the first move the rules reject, after expanding the step it was rolled from, or once
+    /// the end cell is reached.
+    fn replay_moves(&mut self, moves: &[Direction]) -> Vec<usize> {
+        let mut path = vec![0];
+        for &direction in moves {
+            let step = path[path.len() - 1];
+            self.frontier = Frontier::new(self.strategy);
+            let first_new_step = self.steps.len();
+            self.explore(step);
+            let next_step = (first_new_step..self.steps.len())
+                .find(|&new_step| self.steps[new_step].direction() == Some(direction));
+            match next_step {
+                Some(next_step) => path.push(next_step),
+                None => break,
+            }
+            // Steps are only created on the end cell for solutions.
+            if self.steps[path[path.len() - 1]].cell.is_end_cell() {
+                break;
+            }
+        }
+        self.frontier = Frontier::new(self.strategy);
+
+        path
+    }
+
+    /// Expands the given step, keeping track of the search progress.
+    fn explore(&mut self, step: usize) -> Option<usize> {
+        let first_new_step = self.steps.len();
+        let solution_step = self.expand(step);
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.expanded(step);
+            for (
+                new_step,
+                Step {
+                    dice, turn, cell, ..
+                },
+            ) in self.steps.iter().enumerate().skip(first_new_step)
+            {
+                recorder.generated(
+                    new_step,
+                    Some(step),
+                    (*turn).into(),
+                    *cell.get_position(),
+                    dice.get_face(Face::Top),
+                );
+            }
+        }
+
+        self.stats.peak_frontier = self.stats.peak_frontier.max(self.frontier.len() as u64);
+        self.stats.stored = self.steps.len() as u64;
+        if self.stats.expanded.is_multiple_of(EVENT_BATCH_SIZE) {
+            if let Some(events) = &mut self.events {
+                events.emit(
+                    "expand-batch",
+                    json!({
+                        "expanded": self.stats.expanded,
+                        "generated": self.stats.generated,
+                        "frontier": self.frontier.len(),
+                        "turn": i16::from(self.steps[step].turn),
+                    }),
+                );
+            }
+        }
+        if let Some(progress) = &mut self.progress {
+            progress.observe(self.stats, self.frontier.len());
+        }
+
+        solution_step
+    }
+
+    /// Tries to roll the dice in every direction from the given step, pushing the valid steps to
+    /// the frontier. Returns the index of the solution step, if one is reached. Solution steps are
+    /// not pushed to the frontier, as a journey ends once it reaches the end cell.
+    /// This is the hot path of the traversal: besides the amortized growth of the arena and of the
+    /// frontier, it must not allocate.
+    fn expand(&mut self, step: usize) -> Option<usize> {
+        let Step {
+            dice,
+            turn,
+            cell,
+            waypoints,
+            ..
+        } = self.steps[step];
+        let mut solution_step = None;
+        self.stats.expanded += 1;
+        // No movement is allowed once the multiplier runs out, past the maximum depth, or once
+        // turns cannot be counted anymore.
+        let new_turn = turn.next()?;
+        let multiplier = self.rules.multiplier.at(new_turn)?;
+        if self.max_depth.is_some_and(|max_depth| new_turn > max_depth) {
+            return None;
+        }
+
+        // After the first turn, the score matches the value of the cell landed on. Landing on
+        // divisible values does not depend on the score anyway.
+        let score = if turn.is_start() {
+            self.rules.initial_score(cell.get_value())
+        } else {
+            cell.get_value()
+        };
+        for direction in Direction::iter() {
+            // Confirm that a movement in this direction yiels a cell (i.e. not outbounds).
+            if let Some(new_cell) = self.board.move_in(&cell, direction) {
+                // Mirror images of the movements from the start cell are not explored, as they lead
+                // to mirror images of the same journeys.
+                if step == 0 && self.symmetric && (direction.mirror() as u8) < direction as u8 {
+                    self.stats.pruned += 1;
+                    continue;
+                }
+                if step == 0
+                    && self
+                        .first_move
+                        .is_some_and(|first_move| first_move != direction)
+                {
+                    self.stats.pruned += 1;
+                    continue;
+                }
+                if self.avoided_cells.contains(new_cell.get_position())
+                    || self.max_revisits.is_some_and(|max_revisits| {
+                        self.count_visits(step, &new_cell) > max_revisits as usize
+                    })
+                    || self.max_straight.is_some_and(|max_straight| {
+                        self.count_straight(step, direction) >= max_straight as usize
+                    })
+                {
+                    self.stats.rejected += 1;
+                    continue;
+                }
+                if let Some(invariants) = &self.invariants {
+                    // Skip movements the precomputed divisibility tables rule out, which saves
+                    // inferring the top of the dice.
+                    if !invariants.can_roll(&self.board, turn, &cell, direction) {
+                        self.stats.rejected += 1;
+                        continue;
+                    }
+                    // Skip cells from which the end cell provably cannot be reached anymore.
+                    if !invariants.can_finish(&self.board, new_turn, &new_cell) {
+                        self.stats.pruned += 1;
+                        continue;
+                    }
+                }
+                // Journeys end on the end cell, so they must have visited every waypoint by then,
+                // and end with the required final score.
+                let new_waypoints = self.visit_waypoints(waypoints, &new_cell);
+                if new_cell.is_end_cell()
+                    && (!self.visited_all_waypoints(new_waypoints)
+                        || !self
+                            .rules
+                            .accepts_final_score(new_turn, new_cell.get_value()))
+                {
+                    self.stats.pruned += 1;
+                    continue;
+                }
+
+                // If we are inbounds after this movement, confirm that moving there is valid,
+                // per the puzzle rules. There might be several valid ways to do so, when the top
+                // of the dice is unknown.
+                let rolled_dice = dice.roll_in(direction);
+                let generated = self.stats.generated;
+                for landed_dice in Self::try_dice_movement(
+                    &self.rules,
+                    rolled_dice,
+                    score,
+                    multiplier,
+                    new_cell.get_value(),
+                ) {
+                    let new_step = Step {
+                        dice: landed_dice,
+                        turn: new_turn,
+                        cell: new_cell,
+                        reached_from: Some((step, direction)),
+                        waypoints: new_waypoints,
+                    };
+                    self.steps.push(new_step);
+                    self.stats.generated += 1;
+
+                    // If the step has reached the end cell, it is a solution. Otherwise, it is a
+                    // valid step worth further traversing.
+                    if new_cell.is_end_cell() {
+                        solution_step = Some(self.steps.len() - 1);
+                    } else {
+                        self.frontier.push(
+                            self.steps.len() - 1,
+                            new_turn,
+                            self.board.distance_to_end_cell(&new_cell),
+                        );
+                    }
+                }
+
+                if self.stats.generated == generated {
+                    self.stats.rejected += 1;
+                }
+            }
+        }
+
+        solution_step
+    }
+
+    /// Lists the dispositions the dice can have after validly rolling onto a cell under the given
+    /// rules. The rules are borrowed on their own, so that steps can be pushed while iterating.
+    fn try_dice_movement(
+        rules: &Rules,
+        dice: Dice,
+        score: i16,
+        multiplier: i16,
+        cell_value: i16,
+    ) -> impl Iterator<Item = Dice> + '_ {
+        // There are two main scenarios when rolling a dice onto a cell:
+        //  1. The scored value of the dice is known.
+        //     In this case, we validate that the dice lands on the cell, e.g. that the new score
+        //     matches the value of the cell. If it does not, then the movement is invalid.
+        //  2. The scored value of the dice is yet unknown.
+        //     In this case, we infer the values resulting in the dice landing on the new cell.
+        //     With integers, there is at most one such integral value, but there can be several
+        //     with modular arithmetic, or when landing on divisible values.
+        //     If no such value exists, then the movement is invalid. If any value lands, then
+        //     nothing is inferred, and the value remains unknown.
+        //     The inferred values must also satisfy the constraints on faces, if any.
+        let (tops, inferred) = match rules.scoring.scored_value(&dice) {
+            Some(scored_value) if rules.lands(score, multiplier, scored_value, cell_value) => {
+                (CandidateTops::only(scored_value), false)
+            }
+            Some(_) => (CandidateTops::none(), false),
+            // The dice is kept as is, whichever the candidate.
+            None if rules.lands_with_any_value(score, multiplier, cell_value) => {
+                (CandidateTops::only(0), false)
+            }
+            None => (rules.infer_values(score, multiplier, cell_value), true),
+        };
+
+        tops.filter_map(move |top| {
+            if inferred {
+                rules.set_inferred_score(dice, top)
+            } else {
+                Some(dice)
+            }
+        })
+    }
+}
+
+impl Default for Solver {
+    /// Returns a solver for the puzzle board.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Movement;
+    use crate::objective::Criterion;
+    use crate::progress::{ProgressInterval, ProgressReport};
+    use crate::render::{draw_isometric_dice, draw_net};
+
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell as StdCell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::{Arc, Mutex};
+
+    /// Wraps the system allocator to count the allocations made by the current thread,
+    /// so that tests running concurrently do not interfere with each other.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: StdCell<usize> = const { StdCell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = ALLOCATIONS.with(StdCell::get);
+        let result = f();
+        (result, ALLOCATIONS.with(StdCell::get) - before)
+    }
+
+    impl Solution {
+        /// Return true if a solution was found.
+        fn found_solution(&self) -> bool {
+            matches!(self, Solution::Found(..))
+        }
+
+        /// Returns the sum+explanation tuple contained in the `Found` value, consuming itself.
+        ///
+        /// # Panics
+        /// Panics if no solution was found.
+        fn unwrap(self) -> (i32, String) {
+            match self {
+                Solution::Found(report) => (report.unvisited_sum, report.to_string()),
+                Solution::NotFound(..) => {
+                    panic!("called `Solution::unwrap()` on a `NotFound` value")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compute_sum_of_unvisited_cells_works() {
+        let solver = Solver::new();
+        let start_cell = solver.board.start_cell();
+        let mut journey = Journey {
+            dice: Dice::default(),
+            start_cell,
+            movements: Vec::new(),
+        };
+
+        // The start cell is always visited, even before the dice moves.
+        assert_eq!(
+            solver.compute_sum_of_unvisited_cells(&journey),
+            solver
+                .board
+                .compute_sum_of_unvisited_cells(&HashSet::from([start_cell.get_position()]))
+        );
+
+        let visited_position = (4, 0);
+        let visited_cell = solver.board.get_cell_at(visited_position).unwrap();
+        journey.movements.push((Direction::UP, visited_cell));
+
+        assert_eq!(
+            solver.compute_sum_of_unvisited_cells(&journey),
+            solver.board.compute_sum_of_unvisited_cells(&HashSet::from([
+                start_cell.get_position(),
+                &visited_position
+            ]))
+        );
+    }
+
+    #[test]
+    fn solver_finds_right_solution() {
+        let solution = Solver::new().solve();
+
+        assert!(solution.found_solution());
+        let (sum_unvisited_cells, _) = solution.unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+    }
+
+    #[test]
+    fn expanding_a_step_does_not_allocate() {
+        let mut solver = Solver::new();
+
+        // Expand a few levels, so that we exercise steps with known dice values too.
+        for _ in 0..20 {
+            let step = solver.frontier.pop().unwrap();
+            assert!(solver.expand(step).is_none());
+        }
+
+        // Make room for every direction, so that only the expansion itself is measured.
+        solver.steps.reserve(4);
+        solver.frontier.reserve(4);
+        let step = solver.frontier.pop().unwrap();
+        let (_, allocations) = count_allocations(|| solver.expand(step));
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn solutions_are_explained_only_when_displayed() {
+        let mut solver = Solver::new();
+        solver.start_search().unwrap();
+        let solution_step = solver.find_solution_step().unwrap();
+
+        // Building the solution does not work out anything per turn, whereas explaining it does.
+        let (solution, allocations) = count_allocations(|| solver.create_solution(solution_step));
+        assert!(allocations < 32, "{} allocations", allocations);
+        let Solution::Found(report) = solution else {
+            unreachable!();
+        };
+        let (_, allocations) = count_allocations(|| report.to_string());
+        assert!(allocations > 32, "{} allocations", allocations);
+    }
+
+    #[test]
+    fn rebuild_journey_follows_previous_steps() {
+        let mut solver = Solver::new();
+        let step = solver.frontier.pop().unwrap();
+        solver.expand(step);
+        let last_step = solver.steps.len() - 1;
+
+        let journey = solver.rebuild_journey(last_step);
+        assert_eq!(journey.start_cell, solver.board.start_cell());
+        assert_eq!(journey.movements.len(), 1);
+        assert_eq!(
+            journey.visited_cells().last(),
+            Some(&solver.steps[last_step].cell)
+        );
+    }
+
+    #[test]
+    fn astar_finds_right_solution() {
+        let solution = Solver::new()
+            .set_strategy(Strategy::AStar { weight: 1.0 })
+            .solve();
+
+        let (sum_unvisited_cells, _) = solution.unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+    }
+
+    #[test]
+    fn weighted_astar_and_greedy_find_a_solution() {
+        for strategy in [Strategy::AStar { weight: 2.5 }, Strategy::Greedy] {
+            let solution = Solver::new().set_strategy(strategy).solve();
+            assert!(solution.found_solution());
+        }
+    }
+
+    #[test]
+    fn anytime_reports_improving_solutions() {
+        for criteria in [
+            vec![Criterion::Turns],
+            vec![Criterion::UnvisitedSum],
+            vec![Criterion::Moves, Criterion::Turns],
+        ] {
+            let mut improvements = Vec::new();
+            let solution = Solver::new().set_strategy(Strategy::Greedy).solve_anytime(
+                &Objective::new(criteria),
+                Duration::from_secs(60),
+                |improvement| improvements.push(improvement.found_solution()),
+            );
+
+            // The puzzle has a single solution, so there is exactly one improvement.
+            assert_eq!(improvements, vec![true]);
+            let (sum_unvisited_cells, _) = solution.unwrap();
+            assert_eq!(sum_unvisited_cells, 1935);
+        }
+    }
+
+    #[test]
+    fn anytime_stops_at_time_limit() {
+        let mut improvements = 0;
+        let solution = Solver::new().solve_anytime(
+            &Objective::new(vec![Criterion::UnvisitedSum]),
+            Duration::ZERO,
+            |_| improvements += 1,
+        );
+
+        assert_eq!(improvements, 0);
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn rebuilt_journeys_hold_the_directions_rolled_in() {
+        let mut solver = Solver::new();
+        let solution_step = solver.find_solution_step().unwrap();
+        let journey = solver.rebuild_journey(solution_step);
+        assert_eq!(journey.movements.len(), 32);
+
+        // Each movement leads from a visited cell to the next one.
+        for (&(movement, cell), previous_cell) in
+            journey.movements.iter().zip(journey.visited_cells())
+        {
+            assert_eq!(solver.board.move_in(previous_cell, movement), Some(cell));
+        }
+    }
+
+    #[test]
+    fn solving_extreme_configurations_does_not_panic() {
+        let mut alternating = [[i16::MAX; 6]; 6];
+        for (row, values) in alternating.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                if (row + col) % 2 == 0 {
+                    *value = i16::MIN;
+                }
+            }
+        }
+        let boards = [
+            [[i16::MIN; 6]; 6],
+            [[i16::MAX; 6]; 6],
+            [[0; 6]; 6],
+            alternating,
+        ];
+        let multipliers = [
+            Multiplier::Ascending,
+            Multiplier::Descending { from: 1 },
+            Multiplier::Descending { from: i16::MIN },
+            Multiplier::Fibonacci,
+            Multiplier::Sequence(Arc::from([0, 0, 0])),
+            Multiplier::Sequence(Arc::from([i16::MIN, -1, i16::MAX])),
+        ];
+        let arithmetics = [
+            Arithmetic::Integer,
+            Arithmetic::Modular { modulus: 0 },
+            Arithmetic::Modular { modulus: 1 },
+            Arithmetic::Modular { modulus: -7 },
+            Arithmetic::Modular { modulus: i16::MIN },
+        ];
+        let recurrences = [
+            Recurrence::Affine,
+            Recurrence::Divisibility,
+            Recurrence::Multiplicative { initial: 0 },
+            Recurrence::Multiplicative { initial: i16::MIN },
+        ];
+
+        // Starting next to the end cell, so that short journeys reach it and get explained.
+        let mut found = 0;
+        for board in boards {
+            for multiplier in &multipliers {
+                for arithmetic in arithmetics {
+                    for recurrence in recurrences {
+                        let builder = Solver::builder()
+                            .board(Board::from_rows(board))
+                            .multiplier(multiplier.clone())
+                            .arithmetic(arithmetic)
+                            .recurrence(recurrence)
+                            .max_depth(3)
+                            .verification(true);
+                        for style in [ExplanationStyle::Text, ExplanationStyle::Accessible] {
+                            let solver = builder.clone().explanation_style(style).build().unwrap();
+                            if solver.set_start((1, 5)).unwrap().solve().found_solution() {
+                                found += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn enumeration_resumes_from_a_saved_state() {
+        let builder = || {
+            Solver::builder()
+                .board(Board::from_rows([[0; 6]; 6]))
+                .max_depth(10)
+        };
+        for strategy in [Strategy::Bfs, Strategy::Greedy] {
+            let all_moves: Vec<String> = builder()
+                .strategy(strategy)
+                .build()
+                .unwrap()
+                .find_solutions(usize::MAX)
+                .into_iter()
+                .map(|solution| match solution {
+                    Solution::Found(report) => report.moves_as_letters(),
+                    Solution::NotFound(..) => unreachable!(),
+                })
+                .collect();
+
+            // Page through the solutions 25 at a time, through JSON, as across requests.
+            let mut paged_moves = Vec::new();
+            let mut state: Option<String> = None;
+            loop {
+                let mut solver = builder().strategy(strategy).build().unwrap();
+                if let Some(state) = &state {
+                    let state = serde_json::from_str(state).unwrap();
+                    solver = solver.resume_enumeration(&state).unwrap();
+                }
+                let page: Vec<String> = std::iter::from_fn(|| solver.next_solution())
+                    .take(25)
+                    .map(|solution| match solution {
+                        Solution::Found(report) => report.moves_as_letters(),
+                        Solution::NotFound(..) => unreachable!(),
+                    })
+                    .collect();
+                if page.is_empty() {
+                    break;
+                }
+                paged_moves.extend(page);
+                assert_eq!(solver.solutions_enumerated(), paged_moves.len());
+                state = Some(solver.enumeration_state().to_string());
+            }
+            assert_eq!(paged_moves, all_moves);
+        }
+    }
+
+    #[test]
+    fn enumeration_cannot_resume_with_another_configuration() {
+        let mut solver = Solver::new();
+        solver.next_solution().unwrap();
+        let state = solver.enumeration_state();
+
+        assert!(Solver::new().resume_enumeration(&state).is_ok());
+        assert!(Solver::builder()
+            .max_depth(40)
+            .build()
+            .unwrap()
+            .resume_enumeration(&state)
+            .is_err());
+        assert!(Solver::new()
+            .set_start((4, 0))
+            .unwrap()
+            .resume_enumeration(&state)
+            .is_err());
+        assert!(Solver::new().resume_enumeration(&json!({})).is_err());
+
+        let mut state = state;
+        state["format_version"] = json!(0);
+        assert_eq!(
+            Solver::new().resume_enumeration(&state).err(),
+            Some("unsupported format version 0".to_string())
+        );
+    }
+
+    #[test]
+    fn find_solutions_enumerates_the_unique_solution() {
+        let solutions = Solver::new().find_solutions(2);
+        assert_eq!(solutions.len(), 1);
+
+        let (sum_unvisited_cells, _) = solutions.into_iter().next().unwrap().unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+
+        assert!(Solver::new().find_solutions(0).is_empty());
+    }
+
+    #[test]
+    fn set_start_changes_the_first_cell() {
+        assert!(Solver::new().set_start((6, 0)).is_none());
+
+        let solver = Solver::new().set_start((2, 3)).unwrap();
+        assert_eq!(
+            solver.steps[0].cell,
+            solver.board.get_cell_at((2, 3)).unwrap()
+        );
+
+        let usual_start = *Solver::new().board.start_cell().get_position();
+        let (sum_unvisited_cells, _) = Solver::new()
+            .set_start(usual_start)
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+    }
+
+    #[test]
+    fn descending_multiplier_limits_the_journey_length() {
+        // The only solution of the original puzzle takes 32 turns, which cannot be reproduced
+        // with fewer turns available.
+        let solution = Solver::new()
+            .set_multiplier(Multiplier::Descending { from: 10 })
+            .solve();
+        assert!(!solution.found_solution());
+
+        let mut solver = Solver::new().set_multiplier(Multiplier::Descending { from: 1 });
+        let step = solver.frontier.pop().unwrap();
+        solver.expand(step);
+        for step in 1..solver.steps.len() {
+            assert!(solver.expand(step).is_none());
+        }
+        assert_eq!(
+            solver.steps.iter().map(|step| step.turn).max(),
+            Some(Turn::from(1))
+        );
+    }
+
+    #[test]
+    fn explanation_shows_the_whole_dice_after_each_turn() {
+        let (_, explanation) = Solver::new().solve().unwrap();
+        let lines: Vec<&str> = explanation.lines().collect();
+
+        // Every face gets rolled onto the top during the journey, so the whole dice is known.
+        assert!(lines[1]
+            .ends_with("The dice is now: top=5, bottom=-9, left=9, right=-3, front=9, back=7."));
+        assert!(lines[32]
+            .ends_with("The dice is now: top=7, bottom=9, left=9, right=-3, front=5, back=-9."));
+    }
+
+    #[test]
+    fn modular_arithmetic_finds_solutions() {
+        let (_, explanation) = Solver::new()
+            .set_arithmetic(Arithmetic::Modular { modulus: 7 })
+            .solve()
+            .unwrap();
+
+        // Scores are displayed as residues, congruent to the cell values.
+        assert!(explanation.contains("Score was 0, now is `0 + (1 x 0) = 0` (cell value = 77)."));
+
+        // With a modulus larger than any score difference, the original solution is found back.
+        let (sum_unvisited_cells, _) = Solver::new()
+            .set_arithmetic(Arithmetic::Modular { modulus: 1000 })
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+    }
+
+    #[test]
+    fn events_describe_the_search() {
+        let path =
+            std::env::temp_dir().join(format!("die-agony-events-{}.ndjson", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let solution = Solver::new()
+            .set_events(EventLog::new(Box::new(file)))
+            .solve();
+        assert!(solution.found_solution());
+
+        let output = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|event| event["event"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(kinds.first(), Some(&"start"));
+        assert!(kinds.contains(&"expand-batch"));
+        assert_eq!(
+            kinds[kinds.len() - 3..],
+            ["solution", "prune-summary", "finish"]
+        );
+
+        let solution = &events[events.len() - 3];
+        assert_eq!(solution["turns"], 32);
+        assert_eq!(solution["unvisited_sum"], 1935);
+        let finish = &events[events.len() - 1];
+        assert_eq!(finish["found"], true);
+        assert!(finish["expanded"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn isometric_explanation_draws_the_dice_every_turn() {
+        let (_, text) = Solver::new().solve().unwrap();
+        let (_, isometric) = Solver::new()
+            .set_explanation_style(ExplanationStyle::Isometric)
+            .solve()
+            .unwrap();
+
+        // The text lines are the same, with a drawing after the initial dice and after each turn.
+        let drawing_height = draw_isometric_dice(&Dice::default()).lines().count();
+        let text_lines = text.lines().count();
+        assert_eq!(isometric.lines().count(), text_lines * (1 + drawing_height));
+        assert_eq!(
+            isometric
+                .lines()
+                .step_by(1 + drawing_height)
+                .collect::<Vec<_>>(),
+            text.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn accessible_explanation_matches_snapshot() {
+        let (_, explanation) = Solver::new()
+            .set_explanation_style(ExplanationStyle::Accessible)
+            .solve()
+            .unwrap();
+
+        assert_eq!(
+            explanation,
+            include_str!("snapshots/accessible_explanation.txt").trim_end()
+        );
+    }
+
+    #[test]
+    fn set_dice_constrains_the_journey() {
+        // The dice found by the default solution leads to the same solution.
+        let dice = Dice::from_sides([Some(9), Some(7), Some(9), Some(-3), Some(-9), Some(5)]);
+        let (sum_unvisited_cells, _) = Solver::new().set_dice(dice).solve().unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+
+        // Whereas a dice holding 1 on every side cannot even leave the start cell.
+        let dice = Dice::from_sides([Some(1); 6]);
+        assert!(!Solver::new().set_dice(dice).solve().found_solution());
+    }
+
+    #[test]
+    fn net_follows_the_initial_dice() {
+        let (_, explanation) = Solver::new()
+            .set_net_format(NetFormat::Text)
+            .solve()
+            .unwrap();
+        let initial_dice =
+            Dice::from_sides([Some(9), Some(7), Some(9), Some(-3), Some(-9), Some(5)]);
+
+        let mut lines = explanation.lines().skip(1);
+        for net_line in draw_net(&initial_dice).lines() {
+            assert_eq!(lines.next(), Some(net_line));
+        }
+        assert!(lines.next().unwrap().starts_with("Turn 1 "));
+    }
+
+    #[test]
+    fn verification_table_checks_every_turn() {
+        let (_, explanation) = Solver::new().set_verification(true).solve().unwrap();
+        let lines: Vec<&str> = explanation.lines().collect();
+
+        // The table follows the 32 turns, with a header of 2 lines and a trailing checksum.
+        let table = &lines[1 + 32..];
+        assert_eq!(table.len(), 2 + 32 + 1);
+        assert_eq!(table[2], "   1 |          5 |     5 | ✓");
+        assert!(table[2..2 + 32].iter().all(|row| row.ends_with('✓')));
+        assert_eq!(
+            table[table.len() - 1],
+            "Checksum: the scores sum up to 8472, and the cell values to 8472 ✓"
+        );
+    }
+
+    #[test]
+    fn turns_csv_lists_every_turn() {
+        let path = std::env::temp_dir().join(format!("die-agony-turns-{}.csv", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        Solver::new().set_turns_csv(Box::new(file)).solve();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 1 + 32);
+        assert_eq!(rows[0], "turn,direction,row,col,top,score,cell_value");
+        assert_eq!(rows[1], "1,UP,4,0,5,5,5");
+        assert_eq!(rows[32], "32,UP,0,5,7,732,732");
+    }
+
+    #[test]
+    fn output_errors_are_handed_to_the_report() {
+        struct BrokenWriter;
+
+        impl Write for BrokenWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let Solution::Found(report) = Solver::new()
+            .set_turns_csv(Box::new(BrokenWriter))
+            .set_ancestry(Box::new(BrokenWriter), AncestryFormat::Json)
+            .solve()
+        else {
+            panic!("The puzzle has a solution.");
+        };
+        assert_eq!(report.unvisited_sum, 1935);
+        let outputs: Vec<SolverOutput> = report
+            .output_errors
+            .iter()
+            .map(|(output, _)| *output)
+            .collect();
+        assert_eq!(outputs, [SolverOutput::TurnsCsv, SolverOutput::Ancestry]);
+        assert_eq!(report.output_errors[0].1.to_string(), "disk full");
+    }
+
+    #[test]
+    fn map_dead_ends_covers_the_journeys_dying_out() {
+        let board = Board::new();
+        let depths = Solver::new().map_dead_ends();
+        assert_eq!(depths.len(), 36);
+        // The start step leads to the solution, but the journeys going right first die out.
+        assert_eq!(depths[board.index_of(&board.start_cell())], None);
+        assert_eq!(
+            depths[board.index_of(&board.get_cell_at((5, 1)).unwrap())],
+            Some(3)
+        );
+        // Journeys end on the end cell, so none dies out there.
+        assert_eq!(
+            depths[board.index_of(&board.get_cell_at((0, 5)).unwrap())],
+            None
+        );
+
+        // On a symmetric board, mirror images of the journeys die out on the mirrored cells.
+        let board = Board::from_rows([[0; 6]; 6]);
+        let depths = Solver::with_board(board.clone())
+            .set_max_depth(10)
+            .map_dead_ends();
+        for position in board.positions() {
+            let cell = board.get_cell_at(position).unwrap();
+            assert_eq!(
+                depths[board.index_of(&cell)],
+                depths[board.index_of(&board.mirror(&cell))]
+            );
+        }
+    }
+
+    #[test]
+    fn explain_forced_moves_eliminates_every_alternative() {
+        let explanation = Solver::new().explain_forced_moves().unwrap();
+        let lines: Vec<&str> = explanation.lines().collect();
+        assert_eq!(lines.len(), 1 + 32 * 4);
+        assert_eq!(
+            lines[1],
+            "Turn 1: the dice rolls up from row 6 column 1 to row 5 column 1."
+        );
+        assert!(lines[2].starts_with("  Rolling right to row 6 column 2 is valid, but none of"));
+        assert_eq!(lines[3], "  Rolling down would leave the board.");
+        assert!(explanation.contains(
+            "  Rolling left to row 5 column 1 brings the known top of 5, scoring 23 + 3 × 5 = 38, not 5."
+        ));
+
+        assert!(Solver::with_board(Board::from_rows([[0; 6]; 6]))
+            .explain_forced_moves()
+            .is_err());
+        assert!(Solver::new()
+            .set_max_depth(10)
+            .explain_forced_moves()
+            .is_err());
+    }
+
+    #[test]
+    fn warm_start_reuses_the_previous_solution() {
+        const MOVES: &str = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+        let (solution, stats) = Solver::new()
+            .set_warm_start(MOVES)
+            .unwrap()
+            .solve_with_stats();
+        let Solution::Found(report) = solution else {
+            panic!("the previous solution should still hold");
+        };
+        assert_eq!(report.unvisited_sum, 1935);
+        assert_eq!(report.moves_as_letters(), MOVES);
+        // Replaying the solution expands one step per turn, and nothing else.
+        assert_eq!(stats.expanded, 32);
+
+        // Once a cell of the solution changes, the search carries on from the valid prefix, or
+        // from the start cell.
+        for broken_moves in ["URRDLUUUUURDLLDRRDRDDRRUULLUURRD", "RRR", "LLL", ""] {
+            let solution = Solver::new().set_warm_start(broken_moves).unwrap().solve();
+            assert_eq!(solution.unwrap().0, 1935);
+        }
+        // No step is expanded twice: the journeys branching off a broken prefix are searched once.
+        let (_, cold_stats) = Solver::new().solve_with_stats();
+        for broken_moves in ["RRR", "LLL", ""] {
+            let (_, stats) = Solver::new()
+                .set_warm_start(broken_moves)
+                .unwrap()
+                .solve_with_stats();
+            assert_eq!(
+                (stats.expanded, stats.generated, stats.stored),
+                (cold_stats.expanded, cold_stats.generated, cold_stats.stored),
+                "{}",
+                broken_moves
+            );
+        }
+        let board = Board::new().set_cell_value((0, 5), 733).unwrap();
+        let solution = Solver::with_board(board)
+            .set_warm_start(MOVES)
+            .unwrap()
+            .solve();
+        assert!(matches!(solution, Solution::NotFound(..)));
+
+        // Letters which are not moves are rejected rather than skipped.
+        assert!(Solver::new().set_warm_start("URRDX").is_none());
+    }
+
+    #[test]
+    fn replay_checks_moves_under_the_rules() {
+        const MOVES: &str = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+        let report = Solver::new().replay(MOVES).unwrap();
+        assert_eq!(report.unvisited_sum, 1935);
+        assert_eq!(report.moves_as_letters(), MOVES);
+
+        // Stopping short of the end cell, going past it or rolling off the board is no solution.
+        for broken_moves in [
+            &MOVES[..31],
+            "URRDLUUUUURDLLDRRDRDDRRUULLUURRUL",
+            "LLL",
+            "",
+            "UXR",
+        ] {
+            assert!(Solver::new().replay(broken_moves).is_none());
+        }
+        // The moves are checked against the rules of the solver, not the default ones.
+        let solver = Solver::builder().max_depth(31).build().unwrap();
+        assert!(solver.replay(MOVES).is_none());
+        let solver = Solver::builder()
+            .avoided_cells(vec![(4, 1)])
+            .build()
+            .unwrap();
+        assert!(solver.replay(MOVES).is_none());
+    }
+
+    #[test]
+    fn builders_check_avoided_cells_and_waypoints() {
+        let builder = Solver::builder().avoided_cells(vec![(2, 5)]);
+        assert!(builder.clone().build().is_some());
+        assert!(builder.avoided_cells(vec![(6, 0)]).build().is_none());
+        let builder = Solver::builder().waypoints(vec![(4, 1)], WaypointOrder::Any);
+        let (sum_unvisited_cells, _) = builder.clone().build().unwrap().solve().unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+        assert!(builder
+            .waypoints(vec![(0, 0); MAX_WAYPOINTS + 1], WaypointOrder::Any)
+            .build()
+            .is_none());
+    }
+
+    #[test]
+    fn journey_svg_follows_the_solution() {
+        let path =
+            std::env::temp_dir().join(format!("die-agony-journey-{}.svg", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        Solver::new().set_journey_svg(Box::new(file), true).solve();
+
+        let svg = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(svg.starts_with("<svg "));
+        // The dice shows its top on the start cell and after each of the 32 turns.
+        assert_eq!(
+            svg.matches(r#"<animate attributeName="visibility""#)
+                .count(),
+            1 + 32
+        );
+        assert_eq!(svg.matches("<animateTransform ").count(), 1);
+    }
+
+    #[test]
+    fn ancestry_explains_the_siblings_of_each_turn() {
+        let path =
+            std::env::temp_dir().join(format!("die-agony-ancestry-{}.json", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        Solver::new()
+            .set_ancestry(Box::new(file), AncestryFormat::Json)
+            .solve();
+
+        let document: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(document["moves"], "URRDLUUUUURDLLDRRDRDDRRUULLUURRU");
+        let decisions = document["decisions"].as_array().unwrap();
+        assert_eq!(decisions.len(), 32);
+        assert!(decisions
+            .iter()
+            .all(|decision| decision["siblings"].as_array().unwrap().len() == 3));
+        // Rolling right first is explored, but dies out after turn 3.
+        assert_eq!(
+            decisions[0]["siblings"][0],
+            json!({ "direction": "R", "to": [5, 1], "outcome": "explored", "steps": 3, "last_turn": 3 })
+        );
+        assert_eq!(
+            decisions[1]["siblings"][1]["reason"],
+            "would need a top of (0 - 5) / 2, which is not an integer"
+        );
+    }
+
+    #[test]
+    fn with_board_solves_other_boards() {
+        // On a board full of zeros, any top of 0 lands on any cell, and BFS goes up, then right.
+        let mut rows = [[0; 6]; 6];
+        rows[5][5] = 7;
+        let (sum_unvisited_cells, explanation) =
+            Solver::with_board(Board::from_rows(rows)).solve().unwrap();
+
+        assert_eq!(sum_unvisited_cells, 7);
+        assert_eq!(explanation.lines().count(), 1 + 10);
+    }
+
+    #[test]
+    fn explanation_counts_the_faces_never_scoring() {
+        let (_, explanation) = Solver::with_board(Board::from_rows([[0; 6]; 6]))
+            .set_start((0, 4))
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert!(explanation
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with("(5 face(s) never scored, so they can hold any value)"));
+    }
+
+    #[test]
+    fn with_options_configures_the_solver() {
+        let options = SolverOptions {
+            strategy: Strategy::AStar { weight: 1.0 },
+            rules: Rules {
+                multiplier: Multiplier::Descending { from: 12 },
+                ..Rules::default()
+            },
+            explanation_style: ExplanationStyle::Isometric,
+            max_depth: Some(Turn::from(40)),
+        };
+        let solver = Solver::with_options(Board::new(), options.clone());
+
+        assert_eq!(solver.strategy, options.strategy);
+        assert_eq!(solver.rules, options.rules);
+        assert_eq!(solver.explanation_style, options.explanation_style);
+        assert_eq!(solver.max_depth, options.max_depth);
+        assert!(matches!(solver.frontier, Frontier::Prioritized { .. }));
+    }
+
+    #[test]
+    fn sequence_multiplier_reproduces_the_ascending_multiplier() {
+        static TURNS: [i16; 32] = [
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+            25, 26, 27, 28, 29, 30, 31, 32,
+        ];
+        let (sum_unvisited_cells, _) = Solver::new()
+            .set_multiplier(Multiplier::Sequence(Arc::from(TURNS)))
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+
+        // The only solution takes 32 turns, which one multiplier short cannot cover.
+        let solution = Solver::new()
+            .set_multiplier(Multiplier::Sequence(Arc::from(&TURNS[..31])))
+            .solve();
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn builder_configures_the_solver() {
+        let dice = Dice::from_sides([Some(9), Some(7), Some(9), Some(-3), Some(-9), Some(5)]);
+        let solver = Solver::builder()
+            .board(Board::new())
+            .strategy(Strategy::Greedy)
+            .multiplier(Multiplier::Descending { from: 40 })
+            .arithmetic(Arithmetic::Modular { modulus: 7 })
+            .recurrence(Recurrence::Divisibility)
+            .scoring(Scoring::Bottom)
+            .face_range(FaceRange { min: 0, max: 6 })
+            .opposite_sum(6)
+            .allowed_faces(Arc::from([0, 1, 5, 6]))
+            .distinct_faces()
+            .final_score(FinalScore::Target(12))
+            .explanation_style(ExplanationStyle::Accessible)
+            .max_depth(32)
+            .max_revisits(1)
+            .max_straight(5)
+            .dice(dice)
+            .net_format(NetFormat::Svg)
+            .verification(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(solver.strategy, Strategy::Greedy);
+        assert_eq!(
+            solver.rules,
+            Rules {
+                multiplier: Multiplier::Descending { from: 40 },
+                arithmetic: Arithmetic::Modular { modulus: 7 },
+                recurrence: Recurrence::Divisibility,
+                scoring: Scoring::Bottom,
+                face_range: Some(FaceRange { min: 0, max: 6 }),
+                opposite_sum: Some(6),
+                allowed_faces: Some(Arc::from([0, 1, 5, 6])),
+                distinct_faces: true,
+                final_score: FinalScore::Target(12),
+            }
+        );
+        assert_eq!(solver.explanation_style, ExplanationStyle::Accessible);
+        assert_eq!(solver.max_depth, Some(Turn::from(32)));
+        assert_eq!(solver.max_revisits, Some(1));
+        assert_eq!(solver.max_straight, Some(5));
+        assert_eq!(solver.steps[0].dice, dice);
+        assert_eq!(solver.net_format, Some(NetFormat::Svg));
+        assert!(solver.verification);
+    }
+
+    #[test]
+    fn bottom_scoring_finds_the_same_journey_with_a_flipped_dice() {
+        let (sum_unvisited_cells, explanation) = Solver::builder()
+            .scoring(Scoring::Bottom)
+            .build()
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+        assert!(explanation.contains("Turn 1 we rolled the dice UP (bottom=5)."));
+    }
+
+    #[test]
+    fn visible_sum_scoring_infers_the_face_rolled_into_view() {
+        let mut rows = [[0; 6]; 6];
+        rows[0][5] = 7;
+        let dice = Dice::from_sides([Some(0), None, Some(0), Some(0), Some(0), Some(0)]);
+        let (_, explanation) = Solver::builder()
+            .board(Board::from_rows(rows))
+            .scoring(Scoring::VisibleSum)
+            .dice(dice)
+            .build()
+            .unwrap()
+            .set_start((0, 4))
+            .unwrap()
+            .solve()
+            .unwrap();
+
+        // Rolling right brings the unknown bottom face to the left, which must complete the sum.
+        assert!(explanation.contains("Turn 1 we rolled the dice RIGHT (visible sum=7)."));
+        assert!(explanation
+            .ends_with("The dice is now: top=0, bottom=0, left=7, right=0, front=0, back=0."));
+    }
+
+    #[test]
+    fn divisibility_infers_a_divisor_of_the_cell_value() {
+        let mut rows = [[0; 6]; 6];
+        rows[0][5] = 12;
+        let (_, explanation) = Solver::builder()
+            .board(Board::from_rows(rows))
+            .recurrence(Recurrence::Divisibility)
+            .face_range(FaceRange { min: 5, max: 6 })
+            .build()
+            .unwrap()
+            .set_start((0, 4))
+            .unwrap()
+            .solve()
+            .unwrap();
+
+        assert!(explanation.contains(
+            "Turn 1 we rolled the dice RIGHT (top=6). Score was 0, now is 1 (cell value = 12, divisible by 6)."
+        ));
+    }
+
+    #[test]
+    fn divisibility_leaves_the_value_landing_on_zero_unknown() {
+        let (_, explanation) = Solver::builder()
+            .board(Board::from_rows([[0; 6]; 6]))
+            .recurrence(Recurrence::Divisibility)
+            .verification(true)
+            .build()
+            .unwrap()
+            .set_start((0, 4))
+            .unwrap()
+            .solve()
+            .unwrap();
+
+        assert!(explanation.contains(
+            "Turn 1 we rolled the dice RIGHT (top=?). Score was 0, now is 1 (cell value = 0, divisible by ?)."
+        ));
+        assert!(explanation.ends_with("   1 |          0 |     1 | ✓"));
+    }
+
+    #[test]
+    fn multiplicative_recurrence_starts_from_the_initial_score() {
+        let mut rows = [[0; 6]; 6];
+        rows[0][5] = 6;
+        let (_, explanation) = Solver::builder()
+            .board(Board::from_rows(rows))
+            .recurrence(Recurrence::Multiplicative { initial: 2 })
+            .build()
+            .unwrap()
+            .set_start((0, 4))
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert!(explanation.contains(
+            "Turn 1 we rolled the dice RIGHT (top=3). Score was 2, now is `2 x 3 = 6` (cell value = 6)."
+        ));
+
+        // A score of 0 stays 0, so the end cell cannot be reached.
+        let solver = Solver::builder()
+            .board(Board::from_rows(rows))
+            .recurrence(Recurrence::Multiplicative { initial: 0 })
+            .max_depth(3)
+            .build()
+            .unwrap()
+            .set_start((0, 4))
+            .unwrap();
+        assert!(matches!(
+            solver.solve(),
+            Solution::NotFound(NotFoundReason::Contradictory(_), ..)
+        ));
+    }
+
+    #[test]
+    fn max_depth_limits_journeys() {
+        let solution = Solver::builder().max_depth(32).build().unwrap().solve();
+        assert!(solution.found_solution());
+
+        let solution = Solver::builder().max_depth(31).build().unwrap().solve();
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn wrapping_boards_roll_off_the_edges() {
+        let board = Board::from_rows([[0; 6]; 6]).set_movement(Movement::OrthogonalWrap);
+        let solver = Solver::builder().board(board).max_depth(2).build().unwrap();
+        let moves: Vec<String> = solver
+            .find_solutions(usize::MAX)
+            .into_iter()
+            .map(|solution| match solution {
+                Solution::Found(report) => report.moves_as_letters(),
+                Solution::NotFound(..) => unreachable!(),
+            })
+            .collect();
+
+        // Rolling off the bottom and left edges reaches the opposite corner in two turns.
+        assert_eq!(moves, ["DL", "LD"]);
+    }
+
+    #[test]
+    fn rectangular_boards_are_solved_from_corner_to_corner() {
+        let board = Board::from_rows([[0; 3]; 2]);
+        let solver = Solver::builder().board(board).max_depth(3).build().unwrap();
+        let mut moves: Vec<String> = solver
+            .find_solutions(usize::MAX)
+            .into_iter()
+            .map(|solution| match solution {
+                Solution::Found(report) => report.moves_as_letters(),
+                Solution::NotFound(..) => unreachable!(),
+            })
+            .collect();
+        moves.sort();
+
+        assert_eq!(moves, ["RRU", "RUR", "URR"]);
+    }
+
+    #[test]
+    fn symmetric_boards_enumerate_mirror_images() {
+        let board = Board::from_rows([[0; 6]; 6]);
+        let solver = Solver::builder()
+            .board(board)
+            .max_depth(10)
+            .build()
+            .unwrap();
+        let solutions = solver.find_solutions(usize::MAX);
+
+        // Every shortest path to the end cell is a solution, and they are all different.
+        let moves: HashSet<String> = solutions
+            .into_iter()
+            .map(|solution| match solution {
+                Solution::Found(report) => report.moves_as_letters(),
+                Solution::NotFound(..) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(moves.len(), 252);
+        assert!(moves.contains("UUUUURRRRR"));
+        assert!(moves.contains("RRRRRUUUUU"));
+    }
+
+    #[test]
+    fn waypoints_are_enforced_during_search() {
+        let solve_via = |waypoints: Vec<Position>, order| {
+            Solver::builder()
+                .max_depth(32)
+                .build()
+                .unwrap()
+                .set_waypoints(waypoints, order)
+                .unwrap()
+                .solve()
+        };
+
+        // The solution goes through (4, 1) on its second turn, but never through (2, 5).
+        let (sum_unvisited_cells, _) = solve_via(vec![(4, 1)], WaypointOrder::Any).unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+        assert!(!solve_via(vec![(2, 5)], WaypointOrder::Any).found_solution());
+
+        assert!(Solver::new()
+            .set_waypoints(vec![(6, 0)], WaypointOrder::Any)
+            .is_none());
+        assert!(Solver::new()
+            .set_waypoints(vec![(0, 0); MAX_WAYPOINTS + 1], WaypointOrder::Any)
+            .is_none());
+    }
+
+    #[test]
+    fn avoided_cells_are_never_landed_on() {
+        let solve_avoiding = |avoided_cells: Vec<Position>| {
+            Solver::builder()
+                .max_depth(32)
+                .build()
+                .unwrap()
+                .set_avoided_cells(avoided_cells)
+                .unwrap()
+                .solve()
+        };
+
+        // The solution never goes through (2, 5), but goes through (4, 1).
+        let (sum_unvisited_cells, _) = solve_avoiding(vec![(2, 5)]).unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+        assert!(!solve_avoiding(vec![(4, 1)]).found_solution());
+
+        // Walling the start cell in leaves no way out.
+        let solver = Solver::new()
+            .set_avoided_cells(vec![(4, 0), (5, 1)])
+            .unwrap();
+        assert!(matches!(
+            solver.solve(),
+            Solution::NotFound(NotFoundReason::Contradictory(Unsolvable::DeadEnd), ..)
+        ));
+
+        assert!(Solver::new().set_avoided_cells(vec![(0, 6)]).is_none());
+    }
+
+    #[test]
+    fn max_revisits_limits_how_often_cells_are_visited() {
+        // The solution visits (4, 1), (2, 1), (1, 1) and (3, 3) twice each.
+        let (sum_unvisited_cells, _) = Solver::builder()
+            .max_revisits(1)
+            .build()
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+
+        let solution = Solver::builder()
+            .max_revisits(0)
+            .max_depth(32)
+            .build()
+            .unwrap()
+            .solve();
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn max_straight_limits_runs_in_the_same_direction() {
+        // The solution rolls up 5 times in a row, and never more.
+        let (sum_unvisited_cells, _) = Solver::builder()
+            .max_straight(5)
+            .build()
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert_eq!(sum_unvisited_cells, 1935);
+
+        let solution = Solver::builder()
+            .max_straight(4)
+            .max_depth(32)
+            .build()
+            .unwrap()
+            .solve();
+        assert!(!solution.found_solution());
+
+        let solutions = Solver::builder()
+            .board(Board::from_rows([[0; 6]; 6]))
+            .max_straight(1)
+            .max_depth(10)
+            .build()
+            .unwrap()
+            .find_solutions(usize::MAX);
+        let moves: Vec<String> = solutions
+            .into_iter()
+            .map(|solution| match solution {
+                Solution::Found(report) => report.moves_as_letters(),
+                Solution::NotFound(..) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(moves, ["URURURURUR", "RURURURURU"]);
+    }
+
+    #[test]
+    fn waypoints_can_be_required_in_order() {
+        let solve_via = |waypoints: Vec<Position>, order| {
+            Solver::builder()
+                .board(Board::from_rows([[0; 6]; 6]))
+                .max_depth(10)
+                .build()
+                .unwrap()
+                .set_waypoints(waypoints, order)
+                .unwrap()
+                .solve()
+        };
+
+        // Within 10 turns, journeys cannot go back to (4, 0) after (4, 1).
+        assert!(solve_via(vec![(4, 0), (4, 1)], WaypointOrder::Given).found_solution());
+        assert!(!solve_via(vec![(4, 1), (4, 0)], WaypointOrder::Given).found_solution());
+        assert!(solve_via(vec![(4, 1), (4, 0)], WaypointOrder::Any).found_solution());
+    }
+
+    #[test]
+    fn solving_reports_progress_at_the_configured_interval() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let solution = Solver::new()
+            .set_progress(ProgressReporter::new(ProgressInterval::Nodes(10), {
+                let reported = reported.clone();
+                move |report: &ProgressReport| reported.lock().unwrap().push(report.stats.expanded)
+            }))
+            .solve();
+        assert_eq!(solution.unwrap().0, 1935);
+        // The search expands 35 steps.
+        assert_eq!(*reported.lock().unwrap(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn solving_detects_journeys_too_short() {
+        let solver = Solver::builder().max_depth(9).build().unwrap();
+        let Solution::NotFound(reason, stats, _) = solver.solve() else {
+            panic!("journeys of 9 turns cannot reach the end cell");
+        };
+        assert_eq!(reason, NotFoundReason::Contradictory(Unsolvable::DeadEnd));
+        assert_eq!(stats.expanded, 0);
+    }
+
+    #[test]
+    fn json_tells_whether_a_solution_was_found() {
+        let document = Solver::new().solve().to_json();
+        assert_eq!(document["format_version"], 1);
+        assert_eq!(document["found"], true);
+        assert_eq!(document["solution"]["unvisited_sum"], 1935);
+
+        let document = Solver::builder()
+            .max_depth(9)
+            .build()
+            .unwrap()
+            .solve()
+            .to_json();
+        assert_eq!(
+            document,
+            json!({
+                "format_version": 1,
+                "found": false,
+                "reason": Unsolvable::DeadEnd.to_string(),
+                "expanded": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn not_found_tells_exhausted_searches_from_limits_hit() {
+        // Only a top of 1 lands on the end cell on turn 12, from a neighbour holding 0. Ignoring
+        // the dice, the end cell can be reached, but not with a dice holding 0 on every face.
+        let mut rows = [[0; 6]; 6];
+        rows[0][5] = 12;
+        let builder = Solver::builder()
+            .board(Board::from_rows(rows))
+            .dice(Dice::from_sides([Some(0); 6]));
+
+        let Solution::NotFound(reason, stats, _) =
+            builder.clone().max_depth(12).build().unwrap().solve()
+        else {
+            panic!("no top of 1 can be scored");
+        };
+        assert_eq!(reason, NotFoundReason::LimitHit(SearchLimit::Depth));
+        assert!(stats.expanded > 0);
+
+        // When the multiplier runs out instead, every journey allowed by the rules was explored.
+        static TURNS: [i16; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let solution = builder
+            .multiplier(Multiplier::Sequence(Arc::from(TURNS)))
+            .build()
+            .unwrap()
+            .solve();
+        assert!(matches!(
+            solution,
+            Solution::NotFound(NotFoundReason::Exhausted, ..)
+        ));
+
+        let solution = Solver::new().solve_anytime(
+            &Objective::new(vec![Criterion::Turns]),
+            Duration::ZERO,
+            |_| {},
+        );
+        assert!(matches!(
+            solution,
+            Solution::NotFound(NotFoundReason::LimitHit(SearchLimit::Time), ..)
+        ));
+    }
+
+    #[test]
+    fn invariants_prune_dead_ends_without_losing_the_solution() {
+        let mut solver = Solver::builder().max_depth(32).build().unwrap();
+        solver.start_search().unwrap();
+        let solution_step = solver.find_solution_step().unwrap();
+        assert_eq!(solver.steps[solution_step].turn, Turn::from(32));
+        assert!(solver.stats.pruned > 0);
+    }
+
+    #[test]
+    fn accessible_explanation_can_use_chess_notation() {
+        let (_, explanation) = Solver::builder()
+            .explanation_style(ExplanationStyle::Accessible)
+            .notation(Board::new().chess_notation())
+            .build()
+            .unwrap()
+            .solve()
+            .unwrap();
+
+        let lines: Vec<&str> = explanation.lines().collect();
+        assert!(lines[0].starts_with("The dice starts on square a1, "));
+        assert!(lines[1].starts_with("Turn 1: from square a1, roll up to square a2. "));
+        assert_eq!(
+            lines[lines.len() - 1],
+            "The dice reached square f6 after 32 turns."
+        );
+    }
+
+    #[test]
+    fn recorder_logs_the_search() {
+        let path =
+            std::env::temp_dir().join(format!("die-agony-record-{}.bin", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let solver = Solver::new().set_recorder(SearchRecorder::new(Box::new(file), u64::MAX));
+        assert!(solver.solve().found_solution());
+
+        let log = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&log[..4], b"DASL");
+        // The first record is the start cell, with an unknown top.
+        assert_eq!(
+            &log[5..20],
+            &[b'G', 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 5, 0, 0x80, 0]
+        );
+        // It is then expanded, and its first child goes up.
+        assert_eq!(&log[20..25], &[b'E', 0, 0, 0, 0]);
+        assert_eq!(
+            &log[25..40],
+            &[b'G', 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 4, 0, 0, 5]
+        );
+    }
+
+    #[test]
+    fn opposite_sum_infers_the_opposite_faces() {
+        // The faces of the solution do not fit on a standard dice.
+        let solution = Solver::builder().opposite_sum(7).build().unwrap().solve();
+        assert!(!solution.found_solution());
+
+        // The top of 0 inferred on the single turn makes the bottom 0 as well.
+        let (_, explanation) = Solver::with_board(Board::from_rows([[0; 6]; 6]))
+            .set_start((0, 4))
+            .unwrap()
+            .set_opposite_sum(Some(0))
+            .solve()
+            .unwrap();
+        assert!(explanation
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with("(4 face(s) never scored, so they can hold any value)"));
+    }
+
+    #[test]
+    fn allowed_faces_discard_journeys_inferring_other_values() {
+        // The solution holds 9 on two faces, along with 7, 5, -3 and -9.
+        let solution = Solver::builder()
+            .allowed_faces(Arc::from([-9, -3, 5, 7, 9]))
+            .build()
+            .unwrap()
+            .solve();
+        assert_eq!(solution.unwrap().0, 1935);
+
+        let solution = Solver::builder()
+            .allowed_faces(Arc::from([-9, -3, 5, 7, 9]))
+            .distinct_faces()
+            .build()
+            .unwrap()
+            .solve();
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn final_score_discards_journeys_ending_with_other_scores() {
+        // Landing on divisible values, the score counts the turns, and any value lands on zeros.
+        let solver = |final_score| {
+            Solver::with_board(Board::from_rows([[0; 6]; 6]))
+                .set_recurrence(Recurrence::Divisibility)
+                .set_max_revisits(0)
+                .set_final_score(final_score)
+        };
+        let (_, explanation) = solver(FinalScore::Any).solve().unwrap();
+        assert_eq!(explanation.lines().count(), 1 + 10);
+        let (_, explanation) = solver(FinalScore::Target(12)).solve().unwrap();
+        assert_eq!(explanation.lines().count(), 1 + 12);
+
+        // The score of the original puzzle always ends on the value of the end cell.
+        let solution = Solver::builder()
+            .final_score(FinalScore::EndCellValue)
+            .build()
+            .unwrap()
+            .solve();
+        assert_eq!(solution.unwrap().0, 1935);
+    }
+
+    #[test]
+    fn partial_credit_reports_the_closest_journey() {
+        let solve = |metric| match Solver::new()
+            .set_max_depth(20)
+            .set_partial_credit(metric)
+            .solve()
+        {
+            Solution::NotFound(_, _, Some(partial_journey)) => partial_journey,
+            _ => panic!("the solution lasts 32 turns"),
+        };
+
+        // The journeys explored follow the solution, which comes within 3 movements of the end
+        // cell on turn 11.
+        let closest = solve(PartialCredit::ClosestToEnd);
+        assert_eq!(closest.moves, "URRDLUUUUUR");
+        assert_eq!(closest.distance_to_end, 3);
+        let most_cells = solve(PartialCredit::MostCells);
+        assert_eq!(most_cells.moves, "URRDLUUUUURDLLDRRDRD");
+        assert_eq!(most_cells.cells_visited, 18);
+
+        // Without partial credit, there is nothing to report.
+        let solution = Solver::new().set_max_depth(20).solve();
+        assert!(matches!(solution, Solution::NotFound(_, _, None)));
+    }
+
+    #[test]
+    fn cells_visited_are_counted_for_every_step() {
+        let mut solver = Solver::builder()
+            .board(Board::from_rows([[0; 4]; 4]))
+            .max_depth(6)
+            .build()
+            .unwrap();
+        assert!(solver.find_solution_step().is_some());
+
+        let cells_visited = solver.count_cells_visited();
+        assert_eq!(cells_visited.len(), solver.steps.len());
+        for (step, &count) in cells_visited.iter().enumerate() {
+            let journey = solver.rebuild_journey(step);
+            let cells: HashSet<_> = journey.visited_cells().map(Cell::get_position).collect();
+            assert_eq!(count, cells.len(), "step {}", step);
+        }
+    }
+
+    #[test]
+    fn face_range_discards_journeys_inferring_other_values() {
+        // The faces of the solution are between -9 and 9.
+        let solution = Solver::builder()
+            .face_range(FaceRange { min: -9, max: 9 })
+            .build()
+            .unwrap()
+            .solve();
+        assert_eq!(solution.unwrap().0, 1935);
+
+        let solution = Solver::builder()
+            .face_range(FaceRange { min: -8, max: 9 })
+            .build()
+            .unwrap()
+            .solve();
+        assert!(!solution.found_solution());
+    }
+
+    #[test]
+    fn public_api_never_panics_on_extreme_boards() {
+        let boards = [
+            Board::from_rows([[0], [0]]),
+            Board::from_rows([[i16::MAX; 7]]),
+            Board::from_rows([[i16::MIN]; 7]),
+            Board::from_rows([[i16::MIN, i16::MAX], [i16::MAX, i16::MIN]]),
+            Board::from_rows([[0; 3]; 3]).set_end((2, 0)).unwrap(),
+            Board::from_rows([[1; 2]; 2]).set_movement(Movement::OrthogonalWrap),
+        ];
+        for board in boards {
+            for max_depth in [-1, 0, 6] {
+                let builder = || {
+                    Solver::builder()
+                        .board(board.clone())
+                        .max_depth(max_depth)
+                        .build()
+                        .unwrap()
+                };
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                    if let Solution::Found(report) = builder().solve() {
+                        report.to_string();
+                    }
+                    builder().find_solutions(3);
+                    builder().map_dead_ends();
+                    let _ = builder().explain_forced_moves();
+                    builder().replay("URDL");
+                    builder().replay("not a move");
+                    let _ = builder().resume_enumeration(&json!({ "moves": ["X"] }));
+                }));
+                assert!(
+                    outcome.is_ok(),
+                    "{} with {}",
+                    board.canonical_form(),
+                    max_depth
+                );
+            }
+        }
     }
 }
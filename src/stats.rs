@@ -0,0 +1,339 @@
+use crate::{
+    board::{Board, Notation, Position},
+    dice::Dice,
+    direction::Direction,
+    rules::{Recurrence, Rules, Scoring},
+    turn::Turn,
+};
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+/// Statistics on the moves of one or several solutions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MoveStats {
+    /// How many moves went in each direction, indexed by `Direction`.
+    counts: [usize; 4],
+    /// Longest run of consecutive moves in the same direction, if there are any moves.
+    longest_run: Option<(Direction, usize)>,
+}
+
+impl MoveStats {
+    /// Computes the statistics of a move string, e.g. `URRD`.
+    pub fn from_moves(moves: &str) -> Self {
+        let mut stats = Self::default();
+        let mut run: Option<(Direction, usize)> = None;
+        for direction in moves.chars().filter_map(Direction::from_letter) {
+            stats.counts[direction as usize] += 1;
+            run = match run {
+                Some((run_direction, length)) if run_direction == direction => {
+                    Some((direction, length + 1))
+                }
+                _ => Some((direction, 1)),
+            };
+            stats.keep_longest_run(run);
+        }
+
+        stats
+    }
+
+    /// Adds the statistics of another solution to these ones.
+    pub fn merge(&mut self, other: &Self) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts) {
+            *count += other_count;
+        }
+        self.keep_longest_run(other.longest_run);
+    }
+
+    /// Describes the statistics in a sentence.
+    pub fn describe(&self) -> String {
+        let counts: Vec<String> = Direction::iter()
+            .map(|direction| format!("{} {:?}", self.counts[direction as usize], direction))
+            .collect();
+        match self.longest_run {
+            Some((direction, length)) => format!(
+                "Moves: {}. The longest straight run is {} moves {:?}.",
+                counts.join(", "),
+                length,
+                direction
+            ),
+            None => format!("Moves: {}.", counts.join(", ")),
+        }
+    }
+
+    /// Describes the statistics as JSON.
+    pub fn to_json(self) -> Value {
+        let counts: serde_json::Map<String, Value> = Direction::iter()
+            .map(|direction| {
+                (
+                    format!("{:?}", direction).to_lowercase(),
+                    json!(self.counts[direction as usize]),
+                )
+            })
+            .collect();
+        json!({
+            "counts": counts,
+            "longest_run": self.longest_run.map(|(direction, length)| json!({
+                "direction": format!("{:?}", direction).to_lowercase(),
+                "length": length,
+            })),
+        })
+    }
+
+    fn keep_longest_run(&mut self, run: Option<(Direction, usize)>) {
+        if let Some((_, length)) = run {
+            if self.longest_run.is_none_or(|(_, longest)| length > longest) {
+                self.longest_run = run;
+            }
+        }
+    }
+}
+
+/// Statistics on the values of a board, to sanity check it before solving it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardStats {
+    /// Distinct values of the board, in increasing order, each with the cells holding it.
+    values: Vec<(i16, Vec<Position>)>,
+    /// Sums of the values of each row, from the top of the board.
+    row_sums: Vec<i32>,
+    /// Sums of the values of each column, from the left of the board.
+    column_sums: Vec<i32>,
+    /// Values the dice cannot land on at any turn under the rules, each with the cells holding it.
+    unreachable: Vec<(i16, Vec<Position>)>,
+}
+
+impl BoardStats {
+    /// Computes the statistics of the board, with the values it cannot land on under the given
+    /// rules.
+    pub fn from_board(board: &Board, rules: &Rules) -> Self {
+        let mut values: BTreeMap<i16, Vec<Position>> = BTreeMap::new();
+        let (mut row_sums, mut column_sums) = (Vec::new(), Vec::new());
+        for cell in board.cells() {
+            let (position @ (row, col), value) = (*cell.get_position(), cell.get_value());
+            values.entry(value).or_default().push(position);
+            for (sums, index) in [(&mut row_sums, row), (&mut column_sums, col)] {
+                if sums.len() <= index {
+                    sums.resize(index + 1, 0);
+                }
+                sums[index] += value as i32;
+            }
+        }
+
+        let initial_score = rules.initial_score(board.start_cell().get_value());
+        let distinct_values: Vec<i16> = values.keys().copied().collect();
+        let unreachable = values
+            .iter()
+            .filter(|(&value, _)| !can_land_on(rules, initial_score, &distinct_values, value))
+            .map(|(&value, positions)| (value, positions.clone()))
+            .collect();
+
+        Self {
+            values: values.into_iter().collect(),
+            row_sums,
+            column_sums,
+            unreachable,
+        }
+    }
+
+    /// Describes the statistics, one line each, with cells in the given notation.
+    pub fn describe(&self, notation: Notation) -> String {
+        let cells = |positions: &[Position]| {
+            positions
+                .iter()
+                .map(|&position| notation.format(position))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let join = |sums: &[i32]| {
+            sums.iter()
+                .map(|sum| sum.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (Some((min, min_cells)), Some((max, max_cells))) =
+            (self.values.first(), self.values.last())
+        else {
+            return "The board holds no cell.".to_string();
+        };
+
+        let cell_count: usize = self.values.iter().map(|(_, cells)| cells.len()).sum();
+        let sum: i32 = self.row_sums.iter().sum();
+        let distribution: Vec<String> = self
+            .values
+            .iter()
+            .map(|(value, cells)| format!("{}: {}", value, cells.len()))
+            .collect();
+        let duplicates: Vec<String> = self
+            .values
+            .iter()
+            .filter(|(_, positions)| positions.len() > 1)
+            .map(|(value, positions)| format!("{} on {}", value, cells(positions)))
+            .collect();
+        let unreachable: Vec<String> = self
+            .unreachable
+            .iter()
+            .map(|(value, positions)| format!("{} on {}", value, cells(positions)))
+            .collect();
+
+        let mut lines = vec![
+            format!(
+                "The board holds {} cells, summing to {}, with values from {} (on {}) to {} (on \
+                 {}).",
+                cell_count,
+                sum,
+                min,
+                cells(min_cells),
+                max,
+                cells(max_cells)
+            ),
+            format!("Distribution of the values: {}.", distribution.join(", ")),
+        ];
+        lines.push(match duplicates.as_slice() {
+            [] => "No value is held by several cells.".to_string(),
+            duplicates => format!("Duplicated values: {}.", duplicates.join("; ")),
+        });
+        lines.push(format!(
+            "Sums of the rows, from the top: {}.",
+            join(&self.row_sums)
+        ));
+        lines.push(format!(
+            "Sums of the columns, from the left: {}.",
+            join(&self.column_sums)
+        ));
+        lines.push(match unreachable.as_slice() {
+            [] => "The dice can land on every value at some turn.".to_string(),
+            unreachable => format!(
+                "The dice cannot land on these values at any turn: {}.",
+                unreachable.join("; ")
+            ),
+        });
+
+        lines.join("\n")
+    }
+}
+
+/// Returns whether the dice can land on a cell with the given value at some turn, coming from the
+/// start cell on the first turn, or from a cell holding any of the given values later on. The
+/// values the dice would score are checked against the constraints on faces, one at a time.
+fn can_land_on(rules: &Rules, initial_score: i16, values: &[i16], value: i16) -> bool {
+    // Only the affine recurrence depends on the multiplier, which is useless beyond the spread of
+    // the scores, as it could only be multiplied by 0 then. The other recurrences depend on the
+    // previous score at most.
+    let turns = match rules.recurrence {
+        Recurrence::Affine => {
+            let (min, max) = values
+                .iter()
+                .chain([&initial_score])
+                .fold((i16::MAX, i16::MIN), |(min, max), &value| {
+                    (min.min(value), max.max(value))
+                });
+            i16::try_from(max as i32 - min as i32 + 1).unwrap_or(i16::MAX)
+        }
+        Recurrence::Divisibility => 1,
+        Recurrence::Multiplicative { .. } => 2,
+    };
+    let scores_value = |scored: i16| {
+        rules.scoring == Scoring::VisibleSum
+            || rules.set_inferred_score(Dice::default(), scored).is_some()
+    };
+
+    (1..=turns)
+        .map(Turn::from)
+        .map_while(|turn| Some((turn, rules.multiplier.at(turn)?)))
+        .any(|(turn, multiplier)| {
+            let scores: Vec<i16> = match turn.previous() {
+                Some(previous_turn) if !previous_turn.is_start() => values
+                    .iter()
+                    .filter_map(|&previous| {
+                        i16::try_from(rules.landed_score(previous_turn, previous)).ok()
+                    })
+                    .collect(),
+                _ => vec![initial_score],
+            };
+            scores.into_iter().any(|score| {
+                rules.lands_with_any_value(score, multiplier, value)
+                    || rules
+                        .infer_values(score, multiplier, value)
+                        .any(scores_value)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn from_moves_counts_directions_and_runs() {
+        let stats = MoveStats::from_moves("URRDLUUUR");
+        assert_eq!(stats.counts, [4, 3, 1, 1]);
+        assert_eq!(stats.longest_run, Some((Direction::UP, 3)));
+        assert_eq!(
+            stats.describe(),
+            "Moves: 4 UP, 3 RIGHT, 1 DOWN, 1 LEFT. The longest straight run is 3 moves UP."
+        );
+
+        let stats = MoveStats::from_moves("");
+        assert_eq!(stats, MoveStats::default());
+        assert_eq!(stats.describe(), "Moves: 0 UP, 0 RIGHT, 0 DOWN, 0 LEFT.");
+    }
+
+    #[test]
+    fn merge_adds_counts_and_keeps_the_longest_run() {
+        let mut stats = MoveStats::from_moves("URR");
+        stats.merge(&MoveStats::from_moves("LLLD"));
+        assert_eq!(stats.counts, [1, 2, 1, 3]);
+        assert_eq!(stats.longest_run, Some((Direction::LEFT, 3)));
+    }
+
+    #[test]
+    fn to_json_lists_counts_and_longest_run() {
+        assert_eq!(
+            MoveStats::from_moves("URR").to_json(),
+            json!({
+                "counts": { "up": 1, "right": 2, "down": 0, "left": 0 },
+                "longest_run": { "direction": "right", "length": 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn board_stats_of_the_puzzle_board() {
+        let stats = BoardStats::from_board(&Board::new(), &Rules::default());
+        assert_eq!(stats.values.first(), Some(&(-7, vec![(3, 0)])));
+        assert_eq!(stats.values.last(), Some(&(732, vec![(0, 5)])));
+        assert_eq!(stats.row_sums[5], 1301);
+        assert_eq!(stats.column_sums[0], 322);
+        // Any top can be inferred, so every value can be landed on.
+        assert!(stats.unreachable.is_empty());
+
+        let description = stats.describe(Board::new().chess_notation());
+        assert!(description.starts_with(
+            "The board holds 36 cells, summing to 9767, with values from -7 (on a3) to 732 (on \
+             f6)."
+        ));
+        assert!(description.contains("Duplicated values: 452 on e4, d3, f1."));
+        assert!(description.ends_with("The dice can land on every value at some turn."));
+    }
+
+    #[test]
+    fn board_stats_list_unreachable_values() {
+        // Only even values can be divided by a dice holding nothing but twos.
+        let rules = Rules {
+            recurrence: Recurrence::Divisibility,
+            allowed_faces: Some(Arc::from([2])),
+            ..Rules::default()
+        };
+        let mut rows = [[2; 6]; 6];
+        rows[0][0] = 3;
+        rows[1][0] = 3;
+        rows[2][0] = 0;
+        let stats = BoardStats::from_board(&Board::from_rows(rows), &rules);
+        assert_eq!(stats.unreachable, vec![(3, vec![(0, 0), (1, 0)])]);
+        assert!(stats
+            .describe(Notation::RowCol)
+            .ends_with("The dice cannot land on these values at any turn: 3 on (0, 0), (1, 0)."));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    board::{Board, Notation},
+    direction::Direction,
+    signature::compute_board_hash,
+};
+
+/// Enumerates what the answer submitted to a puzzle holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubmissionContent {
+    /// Only the sum of the values in the unvisited cells.
+    Answer,
+    /// The sum of the values in the unvisited cells, followed by the cells visited on a second line.
+    AnswerAndPath,
+}
+
+/// Built-in puzzles, identified by the hash of their board, with what their submission holds.
+const BUILT_IN_PUZZLES: [(&str, SubmissionContent); 1] = [
+    // Die Agony, which only asks for the sum of the unvisited cells.
+    ("ee972a36c654fb13", SubmissionContent::Answer),
+];
+
+impl SubmissionContent {
+    /// Returns what the submission to the puzzle played on this board holds, which is the answer
+    /// alone for boards which are not built in.
+    pub fn for_board(board: &Board) -> Self {
+        let board_hash = compute_board_hash(board);
+        BUILT_IN_PUZZLES
+            .iter()
+            .find(|(hash, _)| *hash == board_hash)
+            .map_or(Self::Answer, |&(_, content)| content)
+    }
+}
+
+/// Writes exactly the text to submit for a solution of the board, given its answer and its moves
+/// (e.g. `URRD`) from the start cell. Cells of the path are written in the given notation.
+pub fn format_submission(
+    board: &Board,
+    content: SubmissionContent,
+    answer: i32,
+    moves: &str,
+    notation: Notation,
+) -> String {
+    match content {
+        SubmissionContent::Answer => answer.to_string(),
+        SubmissionContent::AnswerAndPath => {
+            let mut cell = board.start_cell();
+            let mut path = vec![notation.format(*cell.get_position())];
+            for direction in moves.chars().filter_map(Direction::from_letter) {
+                let Some(next_cell) = board.move_in(&cell, direction) else {
+                    break;
+                };
+                cell = next_cell;
+                path.push(notation.format(*cell.get_position()));
+            }
+
+            format!("{}\n{}", answer, path.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_puzzles_are_recognized() {
+        assert_eq!(BUILT_IN_PUZZLES[0].0, compute_board_hash(&Board::new()));
+        assert_eq!(
+            SubmissionContent::for_board(&Board::new()),
+            SubmissionContent::Answer
+        );
+        assert_eq!(
+            SubmissionContent::for_board(&Board::from_rows([[0; 6]; 6])),
+            SubmissionContent::Answer
+        );
+    }
+
+    #[test]
+    fn format_submission_writes_the_path_in_the_notation() {
+        let board = Board::new();
+        assert_eq!(
+            format_submission(
+                &board,
+                SubmissionContent::Answer,
+                1935,
+                "URR",
+                Board::new().chess_notation()
+            ),
+            "1935"
+        );
+        assert_eq!(
+            format_submission(
+                &board,
+                SubmissionContent::AnswerAndPath,
+                1935,
+                "URR",
+                Board::new().chess_notation()
+            ),
+            "1935\na1 a2 b2 c2"
+        );
+        assert_eq!(
+            format_submission(
+                &board,
+                SubmissionContent::AnswerAndPath,
+                1935,
+                "U",
+                Notation::RowCol
+            ),
+            "1935\n(5, 0) (4, 0)"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    direction::Direction,
+    solver::{Solution, Solver},
+};
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::{self, JoinHandle},
+};
+use strum::IntoEnumIterator;
+
+/// Handle on a solve running on its own thread. Solvers, boards, dice and solutions can all be
+/// sent across threads, and the handle can be shared between them, so that a server can run
+/// concurrent solves without wrapping them in a mutex.
+pub struct SolveTask {
+    handle: JoinHandle<Solution>,
+}
+
+impl SolveTask {
+    /// Starts solving the puzzle with the given solver on a new thread.
+    pub fn spawn(solver: Solver) -> Self {
+        Self {
+            handle: thread::spawn(move || solver.solve()),
+        }
+    }
+
+    /// Returns whether the solve finished, without waiting for it.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the solve to finish, and returns its solution. Fails if the solving thread
+    /// panicked, which a caller such as a server can report instead of aborting.
+    pub fn join(self) -> thread::Result<Solution> {
+        self.handle.join()
+    }
+}
+
+/// Enumerates the solutions of the puzzle on the given number of threads, with solvers made by
+/// `make_solver`, and stops once `bound` solutions are found, like `Solver::find_solutions`.
+///
+/// The search is partitioned by the direction of the first movement, and each part is enumerated
+/// up to the bound by whichever thread is free. Parts are then combined in the order of their
+/// direction, so the solutions returned, and their count, do not depend on the number of threads.
+/// Fails if a solver panicked, once every thread is done.
+pub fn find_solutions_in_parallel<F>(
+    make_solver: F,
+    bound: usize,
+    threads: usize,
+) -> thread::Result<Vec<Solution>>
+where
+    F: Fn() -> Solver + Sync,
+{
+    let first_moves: Vec<Direction> = Direction::iter().collect();
+    let next_part = AtomicUsize::new(0);
+    let parts = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, first_moves.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut parts = Vec::new();
+                    loop {
+                        let part = next_part.fetch_add(1, Ordering::Relaxed);
+                        let Some(&first_move) = first_moves.get(part) else {
+                            return parts;
+                        };
+                        let solutions = make_solver()
+                            .set_first_move(first_move)
+                            .find_solutions(bound);
+                        parts.push((part, solutions));
+                    }
+                })
+            })
+            .collect();
+        // Every worker is joined before failing, so that none is left running.
+        workers
+            .into_iter()
+            .map(|worker| worker.join())
+            .collect::<Vec<_>>()
+    });
+    let mut parts: Vec<(usize, Vec<Solution>)> = parts
+        .into_iter()
+        .collect::<thread::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    parts.sort_by_key(|(part, _)| *part);
+
+    Ok(parts
+        .into_iter()
+        .flat_map(|(_, solutions)| solutions)
+        .take(bound)
+        .collect())
+}
+
+/// Solves the puzzle once per item on the given number of threads, with a solver made from each
+/// item by `make_solver`, and returns the solutions in the order of the items, or `None` for the
+/// items `make_solver` made no solver from. Solvers are only made once a thread is free to run
+/// them, so that there are never more of them at once than threads. Like `SolveTask::join`, a
+/// solve fails on its own if making or running its solver panicked, without stopping the others.
+pub fn solve_each_in_parallel<T, F>(
+    items: &[T],
+    make_solver: F,
+    threads: usize,
+) -> Vec<Option<thread::Result<Solution>>>
+where
+    T: Sync,
+    F: Fn(&T) -> Option<Solver> + Sync,
+{
+    let next_item = AtomicUsize::new(0);
+    let mut solutions: Vec<(usize, Option<thread::Result<Solution>>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut solutions = Vec::new();
+                    loop {
+                        let index = next_item.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            return solutions;
+                        };
+                        let solution = panic::catch_unwind(AssertUnwindSafe(|| {
+                            make_solver(item).map(Solver::solve)
+                        }));
+                        solutions.push((index, solution.transpose()));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("solver panics are caught"))
+            .collect()
+    });
+    solutions.sort_by_key(|(index, _)| *index);
+
+    solutions
+        .into_iter()
+        .map(|(_, solution)| solution)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Board,
+        dice::Dice,
+        solver::{SolverBuilder, SolverOptions},
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn shared_types_are_send_and_sync() {
+        assert_send_sync::<Board>();
+        assert_send_sync::<Dice>();
+        assert_send_sync::<SolverOptions>();
+        assert_send_sync::<SolverBuilder>();
+        assert_send_sync::<Solution>();
+        assert_send_sync::<SolveTask>();
+    }
+
+    #[test]
+    fn parallel_counts_do_not_depend_on_the_number_of_threads() {
+        // The zeros board is symmetric, so the sequential search pairs journeys with their mirror.
+        let make_solver = || {
+            Solver::builder()
+                .board(Board::from_rows([[0; 6]; 6]))
+                .max_depth(10)
+                .build()
+                .unwrap()
+        };
+        let moves = |threads| -> Vec<String> {
+            find_solutions_in_parallel(make_solver, 100, threads)
+                .unwrap()
+                .into_iter()
+                .map(|solution| match solution {
+                    Solution::Found(report) => report.moves_as_letters(),
+                    Solution::NotFound(..) => unreachable!(),
+                })
+                .collect()
+        };
+        for threads in [1, 2, 4, 8] {
+            assert_eq!(
+                find_solutions_in_parallel(make_solver, usize::MAX, threads)
+                    .unwrap()
+                    .len(),
+                252
+            );
+            assert_eq!(moves(threads).len(), 100);
+            assert_eq!(moves(threads), moves(1));
+        }
+        assert_eq!(
+            find_solutions_in_parallel(Solver::new, 2, 3).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn parallel_solves_report_panics() {
+        let make_solver = || -> Solver { panic!("the solver could not be made") };
+        assert!(find_solutions_in_parallel(make_solver, 2, 2).is_err());
+
+        // Each start is solved on its own, in the order of the starts.
+        let starts = [(5, 0), (6, 0), (5, 1)];
+        let solutions = solve_each_in_parallel(
+            &starts,
+            |&start| match start {
+                (5, 1) => panic!("the solver could not be made"),
+                start => Solver::new().set_start(start),
+            },
+            2,
+        );
+        assert_eq!(solutions.len(), 3);
+        assert!(matches!(solutions[0], Some(Ok(Solution::Found(..)))));
+        assert!(solutions[1].is_none());
+        assert!(matches!(solutions[2], Some(Err(_))));
+    }
+
+    #[test]
+    fn concurrent_solves_find_the_solution() {
+        let tasks: Vec<SolveTask> = (0..4).map(|_| SolveTask::spawn(Solver::new())).collect();
+        for task in tasks {
+            let Ok(Solution::Found(report)) = task.join() else {
+                panic!("the puzzle board should be solved");
+            };
+            assert_eq!(report.unvisited_sum, 1935);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{
+    board::{Board, Notation},
+    dice::{Dice, Face},
+    direction::Direction,
+    turn::Turn,
+};
+
+use std::io::{self, BufRead, Write};
+
+/// Walks the learner through a solution of the board with the rules of the puzzle, given by its
+/// moves (e.g. `URRD`), asking a question before each turn and explaining the deduction behind
+/// its answer. When the top of the dice is already known before a roll, the question is where the
+/// dice rolls; otherwise it is what the top must be to land on the cell reached. Directions are
+/// answered with their letter or name, e.g. `u` or `up`. Stops early once the input ends, and
+/// returns how many questions were answered right, out of how many were asked.
+pub fn teach(
+    board: &Board,
+    moves: &str,
+    notation: Notation,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> io::Result<(usize, usize)> {
+    let mut cell = board.start_cell();
+    let mut dice = Dice::default();
+    let mut score = cell.get_value() as i32;
+    let (mut right, mut asked) = (0, 0);
+    writeln!(
+        output,
+        "The dice starts on {} with a score of {}. Each turn, the score grows by the turn number \
+         times the top of the dice, and must match the value of the cell reached.",
+        notation.format(*cell.get_position()),
+        score
+    )?;
+
+    for (turn, direction) in moves.chars().filter_map(Direction::from_letter).enumerate() {
+        let turn = Turn::from(turn as i16 + 1);
+        let Some(next_cell) = board.move_in(&cell, direction) else {
+            break;
+        };
+        let value = next_cell.get_value() as i32;
+        let rolled = dice.roll_in(direction);
+        let known_top = rolled.get_face(Face::Top);
+
+        let (question, explanation) = match known_top {
+            Some(top) => (
+                format!(
+                    "Turn {}: the dice is on {} with a score of {}. Which way does it roll?",
+                    turn,
+                    notation.format(*cell.get_position()),
+                    score
+                ),
+                format!(
+                    "It rolls {}, bringing the known {} on top, and {} + {} × {} = {}, the value of {}.",
+                    direction_name(direction),
+                    top,
+                    score,
+                    turn,
+                    top,
+                    score + turn * top,
+                    notation.format(*next_cell.get_position())
+                ),
+            ),
+            None => (
+                format!(
+                    "Turn {}: the dice rolls {} onto {}, which holds {}, with a score of {}. The face \
+                     coming on top is unknown. What must it be?",
+                    turn,
+                    direction_name(direction),
+                    notation.format(*next_cell.get_position()),
+                    value,
+                    score
+                ),
+                format!(
+                    "It must be ({} - {}) / {} = {}, which is now written on that face for good.",
+                    value,
+                    score,
+                    turn,
+                    (value - score) / i32::from(turn)
+                ),
+            ),
+        };
+        writeln!(output, "{}", question)?;
+        write!(output, "> ")?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            writeln!(output)?;
+            break;
+        }
+        let answer = answer.trim();
+        let is_right = match known_top {
+            Some(_) => parse_direction(answer) == Some(direction),
+            None => answer.parse::<i32>().ok() == Some((value - score) / i32::from(turn)),
+        };
+        asked += 1;
+        if is_right {
+            right += 1;
+        }
+        writeln!(
+            output,
+            "{} {}",
+            if is_right { "Right!" } else { "Not quite." },
+            explanation
+        )?;
+
+        dice = match known_top {
+            Some(_) => rolled,
+            None => rolled.set_face(Face::Top, ((value - score) / i32::from(turn)) as i16),
+        };
+        score = value;
+        cell = next_cell;
+    }
+
+    writeln!(
+        output,
+        "You answered {} of {} questions right.",
+        right, asked
+    )?;
+
+    Ok((right, asked))
+}
+
+fn direction_name(direction: Direction) -> String {
+    format!("{:?}", direction).to_lowercase()
+}
+
+/// Parses a direction from its letter or its name, in any case.
+fn parse_direction(answer: &str) -> Option<Direction> {
+    let answer = answer.to_uppercase();
+    match answer.chars().collect::<Vec<_>>()[..] {
+        [letter] => Direction::from_letter(letter),
+        _ => Direction::from_letter(answer.chars().next()?)
+            .filter(|direction| format!("{:?}", direction) == answer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOVES: &str = "URRDLUUUUURDLLDRRDRDDRRUULLUURRU";
+
+    #[test]
+    fn teach_checks_every_answer() {
+        // The first tops are inferred: 5 on the first turn, then -6, and so on.
+        let mut input = "5\n-1\nup\n".as_bytes();
+        let mut output = Vec::new();
+        let (right, asked) = teach(
+            &Board::new(),
+            MOVES,
+            Board::new().chess_notation(),
+            &mut input,
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!((right, asked), (1, 3));
+        assert!(output.contains("Right! It must be (5 - 0) / 1 = 5"));
+        assert!(output.contains("Not quite. It must be (23 - 5) / 2 = 9"));
+        assert!(output.ends_with("You answered 1 of 3 questions right.\n"));
+    }
+
+    #[test]
+    fn teach_asks_for_directions_once_the_top_is_known() {
+        let mut output = Vec::new();
+        let answers: String = MOVES
+            .chars()
+            .map(|letter| format!("{}\n", letter))
+            .collect();
+        teach(
+            &Board::new(),
+            MOVES,
+            Notation::RowCol,
+            &mut answers.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.matches("Which way does it roll?").count(), 32 - 6);
+        assert_eq!(output.matches("What must it be?").count(), 6);
+    }
+
+    #[test]
+    fn parse_direction_accepts_letters_and_names() {
+        assert_eq!(parse_direction("u"), Some(Direction::UP));
+        assert_eq!(parse_direction("Left"), Some(Direction::LEFT));
+        assert_eq!(parse_direction("lft"), None);
+        assert_eq!(parse_direction(""), None);
+    }
+}
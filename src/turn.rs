@@ -0,0 +1,93 @@
+use std::{fmt, ops::Mul};
+
+/// A turn of a journey, counted from 1 for the first roll of the dice, or equivalently the number
+/// of turns taken so far. The start of a journey, before the dice rolls, is turn 0.
+///
+/// Turns are kept apart from scores and cell values, which share their underlying integer type, so
+/// that one cannot be passed for the other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Turn(i16);
+
+impl Turn {
+    /// The start of a journey, before the dice first rolls.
+    pub const START: Self = Self(0);
+
+    /// Returns the turn after this one, or `None` if turns cannot be counted that far.
+    pub fn next(self) -> Option<Self> {
+        self.0.checked_add(1).map(Self)
+    }
+
+    /// Returns the turn before this one, or `None` at the start of a journey.
+    pub fn previous(self) -> Option<Self> {
+        (self.0 > 0).then(|| Self(self.0 - 1))
+    }
+
+    /// Returns the index of this turn in a sequence holding one value per turn, from the first
+    /// one. Returns `None` if the dice does not roll on this turn.
+    pub fn index(self) -> Option<usize> {
+        usize::try_from(self.0).ok()?.checked_sub(1)
+    }
+
+    /// Returns whether this is the start of a journey.
+    pub fn is_start(self) -> bool {
+        self == Self::START
+    }
+}
+
+impl From<i16> for Turn {
+    fn from(turn: i16) -> Self {
+        Self(turn)
+    }
+}
+
+impl From<Turn> for i16 {
+    fn from(turn: Turn) -> Self {
+        turn.0
+    }
+}
+
+impl From<Turn> for i32 {
+    fn from(turn: Turn) -> Self {
+        turn.0 as i32
+    }
+}
+
+/// Multiplies a score, or the value of a face, by the turn number. The product is widened, as it
+/// does not always fit in a score.
+impl Mul<i16> for Turn {
+    type Output = i32;
+
+    fn mul(self, value: i16) -> i32 {
+        self.0 as i32 * value as i32
+    }
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_count_from_the_start() {
+        let first = Turn::START.next().unwrap();
+        assert_eq!(i16::from(first), 1);
+        assert_eq!(first.index(), Some(0));
+        assert_eq!(Turn::START.index(), None);
+        assert_eq!(first.previous(), Some(Turn::START));
+        assert_eq!(Turn::START.previous(), None);
+        assert!(Turn::START.is_start() && !first.is_start());
+        assert_eq!(Turn::from(i16::MAX).next(), None);
+        assert_eq!(first.to_string(), "1");
+    }
+
+    #[test]
+    fn multiplying_by_a_turn_does_not_overflow() {
+        assert_eq!(Turn::from(3) * -4, -12);
+        assert_eq!(Turn::from(i16::MAX) * i16::MAX, 32767 * 32767);
+    }
+}
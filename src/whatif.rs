@@ -0,0 +1,221 @@
+use crate::{
+    board::{Board, Notation, Position},
+    solver::{Solution, SolverBuilder},
+};
+
+use std::ops::RangeInclusive;
+
+/// Change of the value of a cell, to analyze how the solutions of the board depend on it. When
+/// given a range of values, the board is solved with each of them in turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellEdit {
+    pub position: Position,
+    pub values: RangeInclusive<i16>,
+}
+
+/// Parses the value of a cell edit, which is either a single value, e.g. `-7`, or a range of
+/// values with both ends included, e.g. `-10..10`.
+pub fn parse_edit_values(values: &str) -> Result<RangeInclusive<i16>, String> {
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<i16>()
+            .map_err(|_| format!("`{}` is not a valid cell value", value))
+    };
+    match values.split_once("..") {
+        Some((min, max)) => {
+            let (min, max) = (parse(min)?, parse(max)?);
+            if min > max {
+                return Err(format!("`{}` is an empty range of values", values));
+            }
+            Ok(min..=max)
+        }
+        None => parse(values).map(|value| value..=value),
+    }
+}
+
+/// Solves the board as is, and then with the cells edited, reporting for each board how many
+/// solutions it has, counting up to the given bound, and the answer of its first solution. Boards
+/// are solved by the solvers the builder configures for them. At most one edit can span a range of
+/// values, in which case there is a row per value, giving a sensitivity table of the board to that
+/// cell.
+pub fn analyze_what_if(
+    builder: &SolverBuilder,
+    board: &Board,
+    edits: &[CellEdit],
+    bound: usize,
+    notation: Notation,
+) -> Result<String, String> {
+    let Some(first_edit) = edits.first() else {
+        return Err("there is no cell to edit".to_string());
+    };
+    let ranges = edits.iter().filter(|edit| edit.values.len() > 1).count();
+    if ranges > 1 {
+        return Err("at most one cell can be given a range of values".to_string());
+    }
+
+    let mut rows = vec![(
+        "original board".to_string(),
+        describe_solutions(builder, board.clone(), bound)?,
+    )];
+    let swept = edits
+        .iter()
+        .find(|edit| edit.values.len() > 1)
+        .unwrap_or(first_edit);
+    for value in swept.values.clone() {
+        let mut edited = board.clone();
+        let mut labels = Vec::new();
+        for edit in edits {
+            let value = if edit == swept {
+                value
+            } else {
+                *edit.values.start()
+            };
+            edited = edited.set_cell_value(edit.position, value).ok_or_else(|| {
+                format!(
+                    "{} is not a cell of the board",
+                    notation.format(edit.position)
+                )
+            })?;
+            labels.push(format!("{}={}", notation.format(edit.position), value));
+        }
+        rows.push((
+            labels.join(" "),
+            describe_solutions(builder, edited, bound)?,
+        ));
+    }
+
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let mut lines = vec![format!("{:width$}  Solutions  Answer", "Board")];
+    for (label, (solutions, answer)) in rows {
+        lines.push(format!("{:width$}  {:>9}  {}", label, solutions, answer));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Counts the solutions of the board up to the given bound, and returns the count and the answer
+/// of the first solution, as they are written in the table. Fails if the builder cannot build a
+/// solver for the board.
+fn describe_solutions(
+    builder: &SolverBuilder,
+    board: Board,
+    bound: usize,
+) -> Result<(String, String), String> {
+    let solutions = builder
+        .clone()
+        .board(board)
+        .build()
+        .ok_or("the avoided cells and waypoints do not fit the board")?
+        .find_solutions(bound);
+    let count = match solutions.len() {
+        count if count == bound => format!("{}+", count),
+        count => count.to_string(),
+    };
+    let answer = match solutions.first() {
+        Some(Solution::Found(report)) => report.unvisited_sum.to_string(),
+        _ => "-".to_string(),
+    };
+
+    Ok((count, answer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    #[test]
+    fn parse_edit_values_accepts_values_and_ranges() {
+        assert_eq!(parse_edit_values("-7"), Ok(-7..=-7));
+        assert_eq!(parse_edit_values("-10..10"), Ok(-10..=10));
+        assert_eq!(
+            parse_edit_values("-3..-5").map(|_| ()),
+            Err("`-3..-5` is an empty range of values".to_string())
+        );
+        assert!(parse_edit_values("x").is_err());
+        assert!(parse_edit_values("40000").is_err());
+    }
+
+    #[test]
+    fn analyze_what_if_tabulates_each_value() {
+        let board = Board::new();
+        let table = analyze_what_if(
+            &Solver::builder(),
+            &board,
+            &[
+                CellEdit {
+                    position: (0, 0),
+                    values: 56..=58,
+                },
+                CellEdit {
+                    position: (4, 0),
+                    values: 6..=6,
+                },
+            ],
+            2,
+            Notation::RowCol,
+        )
+        .unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 1 + 1 + 3);
+        assert!(lines[1].starts_with("original board"));
+        assert!(lines[1].ends_with("1  1935"));
+        // The dice lands on this cell on the first turn, and the journey breaks down from there.
+        assert!(lines[2].starts_with("(0, 0)=56 (4, 0)=6"));
+        assert!(lines[2].ends_with("0  -"));
+    }
+
+    #[test]
+    fn analyze_what_if_sweeps_a_single_range() {
+        let board = Board::new();
+        let edit = |position| CellEdit {
+            position,
+            values: 0..=1,
+        };
+        assert!(analyze_what_if(
+            &Solver::builder(),
+            &board,
+            &[edit((0, 0)), edit((1, 1))],
+            2,
+            Board::new().chess_notation()
+        )
+        .is_err());
+        assert!(analyze_what_if(
+            &Solver::builder(),
+            &board,
+            &[edit((6, 0))],
+            2,
+            Board::new().chess_notation()
+        )
+        .is_err());
+
+        assert!(analyze_what_if(&Solver::builder(), &board, &[], 2, Notation::RowCol).is_err());
+        // Edits are solved under the rules of the builder.
+        let table = analyze_what_if(
+            &Solver::builder().max_depth(31),
+            &board,
+            &[edit((0, 0))],
+            2,
+            Notation::RowCol,
+        )
+        .unwrap();
+        assert!(table.lines().all(|line| !line.ends_with("1935")));
+
+        // The top left cell is never visited, so its value only changes the answer.
+        let table = analyze_what_if(
+            &Solver::builder(),
+            &board,
+            &[edit((0, 0))],
+            2,
+            Board::new().chess_notation(),
+        )
+        .unwrap();
+        assert!(table.contains("a6=0"));
+        assert!(table
+            .lines()
+            .nth(2)
+            .unwrap()
+            .ends_with(&format!("1  {}", 1935 - 57)));
+    }
+}